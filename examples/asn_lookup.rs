@@ -14,37 +14,37 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     if let RdapObject::Autnum(autnum) = result {
         println!("=== AS Number Information ===\n");
 
-        if let Some(start) = autnum.start_autnum {
-            if let Some(end) = autnum.end_autnum {
-                if start == end {
-                    println!("AS Number: AS{}", start);
-                } else {
-                    println!("AS Range: AS{} - AS{}", start, end);
-                }
+        if let Some(start) = autnum.start_autnum
+            && let Some(end) = autnum.end_autnum
+        {
+            if start == end {
+                println!("AS Number: AS{start}");
+            } else {
+                println!("AS Range: AS{start} - AS{end}");
             }
         }
 
         if let Some(name) = &autnum.name {
-            println!("Name: {}", name);
+            println!("Name: {name}");
         }
 
         if let Some(handle) = &autnum.handle {
-            println!("Handle: {}", handle);
+            println!("Handle: {handle}");
         }
 
         if let Some(as_type) = &autnum.as_type {
-            println!("Type: {}", as_type);
+            println!("Type: {as_type}");
         }
 
         if let Some(country) = &autnum.country {
-            println!("Country: {}", country);
+            println!("Country: {country}");
         }
 
         // Status
         if !autnum.status.is_empty() {
             println!("\nStatus:");
             for status in &autnum.status {
-                println!("  - {}", status);
+                println!("  - {status}");
             }
         }
 
@@ -65,21 +65,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
                     if let Some(vcard) = &entity.vcard {
                         if let Some(name) = vcard.name() {
-                            println!("    Name: {}", name);
+                            println!("    Name: {name}");
                         }
                         if let Some(org) = vcard.org() {
-                            println!("    Organization: {}", org);
+                            println!("    Organization: {org}");
                         }
                         if let Some(email) = vcard.email() {
-                            println!("    Email: {}", email);
+                            println!("    Email: {email}");
                         }
                         if let Some(tel) = vcard.tel() {
-                            println!("    Phone: {}", tel);
+                            println!("    Phone: {tel}");
                         }
-                        if let Some(addr) = vcard.address() {
-                            if let Some(label) = &addr.label {
-                                println!("    Address: {}", label);
-                            }
+                        if let Some(addr) = vcard.address()
+                            && let Some(label) = &addr.label
+                        {
+                            println!("    Address: {label}");
                         }
                     }
                 }
@@ -15,11 +15,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ];
 
     for query in queries {
-        println!("\n=== Querying: {} ===", query);
+        println!("\n=== Querying: {query} ===");
 
         // Auto-detect query type
         let query_type = RdapRequest::detect_type(query)?;
-        println!("Detected type: {:?}", query_type);
+        println!("Detected type: {query_type:?}");
 
         let request = RdapRequest::new(query_type, query);
         let result = client.query(&request).await?;
@@ -1,38 +1,40 @@
-//! Batch query example - query multiple domains/IPs
+//! Batch query example - query multiple domains/IPs concurrently
 
 use rdap::{RdapClient, RdapRequest};
-use tokio::time::{Duration, sleep};
+
+/// Maximum number of queries in flight at once
+const CONCURRENCY: usize = 4;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let client = RdapClient::new()?;
 
     // List of domains to query
-    let queries = vec!["example.com", "google.com", "github.com", "rust-lang.org"];
+    let queries = ["example.com", "google.com", "github.com", "rust-lang.org"];
 
     println!("Querying {} domains...\n", queries.len());
 
-    for query in queries {
-        println!("=== {} ===", query);
+    // Auto-detect type
+    let requests = queries
+        .iter()
+        .map(|query| Ok(RdapRequest::new(RdapRequest::detect_type(query)?, *query)))
+        .collect::<rdap::Result<Vec<_>>>()?;
 
-        // Auto-detect type
-        let query_type = RdapRequest::detect_type(query)?;
-        let request = RdapRequest::new(query_type, query);
+    // Results come back in input order; one failure doesn't stop the rest
+    for (request, result) in client.query_batch(&requests, CONCURRENCY).await {
+        println!("=== {} ===", request.query);
 
-        match client.query(&request).await {
+        match result {
             Ok(result) => {
                 use rdap::display::RdapDisplay;
                 result.display(false);
             }
             Err(e) => {
-                eprintln!("Error querying {}: {}", query, e);
+                eprintln!("Error querying {}: {e}", request.query);
             }
         }
 
         println!();
-
-        // Be nice to the server - add a small delay
-        sleep(Duration::from_millis(500)).await;
     }
 
     Ok(())
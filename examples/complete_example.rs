@@ -50,7 +50,7 @@ async fn query_domain(client: &RdapClient, domain: &str) -> Result<(), Box<dyn s
     if let RdapObject::Domain(domain_obj) = result {
         // Extract basic information
         if let Some(name) = &domain_obj.ldh_name {
-            println!("  Domain: {}", name);
+            println!("  Domain: {name}");
         }
 
         // Status information
@@ -63,16 +63,16 @@ async fn query_domain(client: &RdapClient, domain: &str) -> Result<(), Box<dyn s
             println!("  Nameservers:");
             for ns in &domain_obj.nameservers {
                 if let Some(name) = &ns.ldh_name {
-                    println!("    - {}", name);
+                    println!("    - {name}");
                 }
             }
         }
 
         // DNSSEC status
-        if let Some(dnssec) = &domain_obj.secure_dns {
-            if let Some(signed) = dnssec.delegation_signed {
-                println!("  DNSSEC: {}", if signed { "Enabled" } else { "Disabled" });
-            }
+        if let Some(dnssec) = &domain_obj.secure_dns
+            && let Some(signed) = dnssec.delegation_signed
+        {
+            println!("  DNSSEC: {}", if signed { "Enabled" } else { "Disabled" });
         }
 
         // Important dates
@@ -95,24 +95,24 @@ async fn query_ip(client: &RdapClient, ip: &str) -> Result<(), Box<dyn std::erro
 
     if let RdapObject::IpNetwork(network) = result {
         if let Some(name) = &network.name {
-            println!("  Network: {}", name);
+            println!("  Network: {name}");
         }
 
         if let (Some(start), Some(end)) = (&network.start_address, &network.end_address) {
-            println!("  Range: {} - {}", start, end);
+            println!("  Range: {start} - {end}");
         }
 
         if let Some(country) = &network.country {
-            println!("  Country: {}", country);
+            println!("  Country: {country}");
         }
 
         // Find registrant
         for entity in &network.entities {
             if entity.roles.contains(&"registrant".to_string()) {
-                if let Some(vcard) = &entity.vcard {
-                    if let Some(org) = vcard.name() {
-                        println!("  Organization: {}", org);
-                    }
+                if let Some(vcard) = &entity.vcard
+                    && let Some(org) = vcard.name()
+                {
+                    println!("  Organization: {org}");
                 }
                 break;
             }
@@ -129,15 +129,15 @@ async fn query_asn(client: &RdapClient, asn: &str) -> Result<(), Box<dyn std::er
 
     if let RdapObject::Autnum(autnum) = result {
         if let Some(start) = autnum.start_autnum {
-            println!("  AS Number: AS{}", start);
+            println!("  AS Number: AS{start}");
         }
 
         if let Some(name) = &autnum.name {
-            println!("  Name: {}", name);
+            println!("  Name: {name}");
         }
 
         if let Some(country) = &autnum.country {
-            println!("  Country: {}", country);
+            println!("  Country: {country}");
         }
 
         // Count entities by role
@@ -151,7 +151,7 @@ async fn query_asn(client: &RdapClient, asn: &str) -> Result<(), Box<dyn std::er
         if !role_counts.is_empty() {
             println!("  Contacts:");
             for (role, count) in role_counts {
-                println!("    - {}: {}", role, count);
+                println!("    - {role}: {count}");
             }
         }
     }
@@ -163,12 +163,12 @@ async fn demonstrate_error_handling(client: &RdapClient) {
     let test_queries = vec!["nonexistent-domain-xyz123.com", "999.999.999.999"];
 
     for query in test_queries {
-        println!("  Testing: {}", query);
+        println!("  Testing: {query}");
 
         let query_type = match RdapRequest::detect_type(query) {
             Ok(qt) => qt,
             Err(e) => {
-                println!("    ❌ Invalid query: {}", e);
+                println!("    ❌ Invalid query: {e}");
                 continue;
             }
         };
@@ -180,7 +180,7 @@ async fn demonstrate_error_handling(client: &RdapClient) {
                 RdapObject::Error(err) => {
                     println!("    ⚠️  RDAP Error:");
                     if let Some(title) = &err.title {
-                        println!("       {}", title);
+                        println!("       {title}");
                     }
                 }
                 _ => {
@@ -189,13 +189,13 @@ async fn demonstrate_error_handling(client: &RdapClient) {
             },
             Err(e) => match e {
                 RdapError::Bootstrap(msg) => {
-                    println!("    ❌ Bootstrap error: {}", msg);
+                    println!("    ❌ Bootstrap error: {msg}");
                 }
                 RdapError::NotFound => {
                     println!("    ℹ️  Object not found");
                 }
                 _ => {
-                    println!("    ❌ Error: {}", e);
+                    println!("    ❌ Error: {e}");
                 }
             },
         }
@@ -13,24 +13,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         // Basic info
         if let Some(name) = &domain.ldh_name {
-            println!("Domain: {}", name);
+            println!("Domain: {name}");
         }
 
         if let Some(handle) = &domain.handle {
-            println!("Handle: {}", handle);
+            println!("Handle: {handle}");
         }
 
         // Status
         println!("\nStatus:");
         for status in &domain.status {
-            println!("  - {}", status);
+            println!("  - {status}");
         }
 
         // Nameservers
         println!("\nNameservers:");
         for ns in &domain.nameservers {
             if let Some(name) = &ns.ldh_name {
-                print!("  - {}", name);
+                print!("  - {name}");
                 if let Some(ips) = &ns.ip_addresses {
                     let addrs: Vec<String> = ips.v4.iter().chain(&ips.v6).cloned().collect();
                     if !addrs.is_empty() {
@@ -59,11 +59,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         (ds.key_tag, ds.algorithm, ds.digest_type)
                     {
                         println!(
-                            "    - Key Tag: {}, Algorithm: {}, Digest Type: {}",
-                            tag, alg, digest_type
+                            "    - Key Tag: {tag}, Algorithm: {alg}, Digest Type: {digest_type}"
                         );
                         if let Some(digest) = &ds.digest {
-                            println!("      Digest: {}", digest);
+                            println!("      Digest: {digest}");
                         }
                     }
                 }
@@ -89,10 +88,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
                 if let Some(vcard) = &entity.vcard {
                     if let Some(name) = vcard.name() {
-                        println!("    Name: {}", name);
+                        println!("    Name: {name}");
                     }
                     if let Some(email) = vcard.email() {
-                        println!("    Email: {}", email);
+                        println!("    Email: {email}");
                     }
                 }
             }
@@ -14,57 +14,54 @@ async fn main() {
     ];
 
     for (query, query_type) in queries {
-        println!("\n=== Querying: {} ===", query);
+        println!("\n=== Querying: {query} ===");
 
         let request = RdapRequest::new(query_type, query);
 
         match client.query(&request).await {
             Ok(result) => {
                 // Check if it's an error response from the RDAP server
-                match result {
-                    RdapObject::Error(err) => {
-                        println!("❌ RDAP Error Response:");
-                        if let Some(code) = err.error_code {
-                            println!("   Code: {}", code);
-                        }
-                        if let Some(title) = &err.title {
-                            println!("   Title: {}", title);
-                        }
-                        for desc in &err.description {
-                            println!("   Description: {}", desc);
-                        }
+                if let RdapObject::Error(err) = result {
+                    println!("❌ RDAP Error Response:");
+                    if let Some(code) = err.error_code {
+                        println!("   Code: {code}");
                     }
-                    _ => {
-                        println!("✅ Query successful");
-                        use rdap::display::RdapDisplay;
-                        result.display(false);
+                    if let Some(title) = &err.title {
+                        println!("   Title: {title}");
+                    }
+                    for desc in &err.description {
+                        println!("   Description: {desc}");
                     }
+                } else {
+                    println!("✅ Query successful");
+                    use rdap::display::RdapDisplay;
+                    result.display(false);
                 }
             }
             Err(e) => {
                 println!("❌ Client Error:");
                 match e {
                     RdapError::Bootstrap(msg) => {
-                        println!("   Bootstrap error: {}", msg);
+                        println!("   Bootstrap error: {msg}");
                         println!("   (Try specifying a server with -s option)");
                     }
                     RdapError::Http(err) => {
-                        println!("   HTTP error: {}", err);
+                        println!("   HTTP error: {err}");
                     }
                     RdapError::InvalidQuery(msg) => {
-                        println!("   Invalid query: {}", msg);
+                        println!("   Invalid query: {msg}");
                     }
                     RdapError::Json(err) => {
-                        println!("   JSON parse error: {}", err);
+                        println!("   JSON parse error: {err}");
                     }
                     RdapError::InvalidUrl(err) => {
-                        println!("   URL error: {}", err);
+                        println!("   URL error: {err}");
                     }
                     RdapError::Io(err) => {
-                        println!("   I/O error: {}", err);
+                        println!("   I/O error: {err}");
                     }
                     _ => {
-                        println!("   Other error: {}", e);
+                        println!("   Other error: {e}");
                     }
                 }
             }
@@ -15,38 +15,38 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("=== IP Network Information ===\n");
 
         if let Some(name) = &network.name {
-            println!("Network Name: {}", name);
+            println!("Network Name: {name}");
         }
 
         if let Some(handle) = &network.handle {
-            println!("Handle: {}", handle);
+            println!("Handle: {handle}");
         }
 
         if let (Some(start), Some(end)) = (&network.start_address, &network.end_address) {
-            println!("Address Range: {} - {}", start, end);
+            println!("Address Range: {start} - {end}");
         }
 
         if let Some(version) = &network.ip_version {
-            println!("IP Version: IPv{}", version);
+            println!("IP Version: IPv{version}");
         }
 
         if let Some(net_type) = &network.network_type {
-            println!("Network Type: {}", net_type);
+            println!("Network Type: {net_type}");
         }
 
         if let Some(country) = &network.country {
-            println!("Country: {}", country);
+            println!("Country: {country}");
         }
 
         if let Some(parent) = &network.parent_handle {
-            println!("Parent Network: {}", parent);
+            println!("Parent Network: {parent}");
         }
 
         // Status
         if !network.status.is_empty() {
             println!("\nStatus:");
             for status in &network.status {
-                println!("  - {}", status);
+                println!("  - {status}");
             }
         }
 
@@ -59,10 +59,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
                     if let Some(vcard) = &entity.vcard {
                         if let Some(name) = vcard.name() {
-                            println!("    Name: {}", name);
+                            println!("    Name: {name}");
                         }
                         if let Some(org) = vcard.org() {
-                            println!("    Organization: {}", org);
+                            println!("    Organization: {org}");
                         }
                     }
                 }
@@ -11,7 +11,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Output as pretty JSON
     let json = serde_json::to_string_pretty(&result)?;
-    println!("{}", json);
+    println!("{json}");
 
     Ok(())
 }
@@ -1,22 +1,229 @@
 //! Bootstrap service discovery
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::cache::{Cache, CachedRegistryFile};
 use crate::config::{self, Config, TldOverrides};
 use crate::error::{RdapError, Result};
 use crate::ip;
+use crate::net;
 use crate::request::{QueryType, RdapRequest};
-use ipnet::IpNet;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+#[cfg(not(feature = "wasm"))]
+use std::time::Instant;
 use url::Url;
+#[cfg(feature = "wasm")]
+use web_time::Instant;
 
-/// Bootstrap registry file
+/// One IANA bootstrap registry file (RFC 9224), e.g. `dns.json`
+///
+/// Matching is local: load a file with [`Bootstrap::from_json`] and ask which
+/// servers serve a domain, address or AS number, without an
+/// [`crate::RdapClient`] or network access.
+///
+/// ```
+/// use rdap::Bootstrap;
+///
+/// let dns = Bootstrap::from_json(
+///     r#"{"services": [[["dev", "app"], ["https://rdap.example/"]]]}"#,
+/// )?;
+/// assert_eq!(dns.match_domain("web.dev")[0].as_str(), "https://rdap.example/");
+/// # Ok::<(), rdap::RdapError>(())
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Bootstrap {
+    /// Format version, `"1.0"` for RFC 9224 files
+    #[serde(default)]
+    pub version: String,
+    /// When IANA published the file
+    pub publication: Option<String>,
+    pub description: Option<String>,
+    /// `[entries, urls]` pairs; object tags files put owners first
+    pub services: Vec<Vec<serde_json::Value>>,
+}
+
+impl Bootstrap {
+    /// Parse a registry file
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Servers for a domain, from its longest delegated suffix
+    ///
+    /// Unicode names are matched by their A-labels.
+    pub fn match_domain(&self, domain: &str) -> Vec<Url> {
+        // IDN TLDs are listed as A-labels in the registry
+        let domain = crate::request::to_ascii_domain(domain)
+            .map_or_else(|_| domain.to_lowercase(), |d| d.to_lowercase());
+        let domain = domain.trim_end_matches('.');
+
+        // Build lookup map
+        let mut map: HashMap<String, &[serde_json::Value]> = HashMap::new();
+        for (entries, urls) in self.service_pairs() {
+            for entry in entries {
+                if let Some(tld) = entry.as_str() {
+                    map.insert(tld.to_lowercase(), urls);
+                }
+            }
+        }
+
+        // Try to match from most specific to least specific
+        let mut parts: Vec<&str> = domain.split('.').collect();
+
+        while !parts.is_empty() {
+            let test_domain = parts.join(".");
+            if let Some(urls) = map.get(&test_domain) {
+                return parse_urls(urls);
+            }
+            parts.remove(0);
+        }
+
+        vec![]
+    }
+
+    /// Servers for the first prefix containing `addr`
+    pub fn match_ip(&self, addr: IpAddr) -> Vec<Url> {
+        self.match_entry(|cidr| net::ip_in_cidr(addr, cidr))
+    }
+
+    /// Servers for the first range containing `asn`
+    ///
+    /// Malformed ranges are skipped with a warning.
+    pub fn match_asn(&self, asn: u32) -> Vec<Url> {
+        self.match_entry(|range| match net::parse_asn_range(range) {
+            Ok((start, end)) => (start..=end).contains(&asn),
+            Err(e) => {
+                log::warn!("{e}");
+                false
+            }
+        })
+    }
+
+    /// URLs of the first service with an entry accepted by `matches`
+    fn match_entry(&self, mut matches: impl FnMut(&str) -> bool) -> Vec<Url> {
+        self.service_pairs()
+            .find(|(entries, _)| entries.iter().filter_map(|e| e.as_str()).any(&mut matches))
+            .map(|(_, urls)| parse_urls(urls))
+            .unwrap_or_default()
+    }
+
+    fn service_pairs(&self) -> impl Iterator<Item = (&[serde_json::Value], &[serde_json::Value])> {
+        self.services.iter().filter_map(|service| {
+            match (service.first()?.as_array(), service.get(1)?.as_array()) {
+                (Some(entries), Some(urls)) => Some((entries.as_slice(), urls.as_slice())),
+                _ => None,
+            }
+        })
+    }
+}
+
+fn parse_urls(urls: &[serde_json::Value]) -> Vec<Url> {
+    urls.iter()
+        .filter_map(|v| v.as_str().and_then(|s| Url::parse(s).ok()))
+        .collect()
+}
+
+/// Pre-resolved delegation map used instead of fetching the IANA registries
+///
+/// The JSON form has one optional key per registry (`dns`, `ipv4`, `ipv6`,
+/// `asn`), each holding a document in the IANA bootstrap format:
+///
+/// ```json
+/// { "dns": { "services": [[["com", "net"], ["https://rdap.verisign.com/com/v1/"]]] } }
+/// ```
+///
+/// Registries left out of the map have no delegations.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BootstrapMap {
+    #[serde(default)]
+    dns: Bootstrap,
+    #[serde(default)]
+    ipv4: Bootstrap,
+    #[serde(default)]
+    ipv6: Bootstrap,
+    #[serde(default)]
+    asn: Bootstrap,
+}
+
+impl BootstrapMap {
+    /// Create an empty map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a map from JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Load a map from a JSON file
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Delegate domain suffixes (e.g. "com", "co.uk") to RDAP servers
+    pub fn with_dns(mut self, entries: &[&str], urls: &[&str]) -> Self {
+        Self::add_service(&mut self.dns, entries, urls);
+        self
+    }
+
+    /// Delegate IPv4 prefixes (e.g. "192.0.2.0/24") to RDAP servers
+    pub fn with_ipv4(mut self, entries: &[&str], urls: &[&str]) -> Self {
+        Self::add_service(&mut self.ipv4, entries, urls);
+        self
+    }
+
+    /// Delegate IPv6 prefixes (e.g. `2001:db8::/32`) to RDAP servers
+    pub fn with_ipv6(mut self, entries: &[&str], urls: &[&str]) -> Self {
+        Self::add_service(&mut self.ipv6, entries, urls);
+        self
+    }
+
+    /// Delegate AS number ranges (e.g. "64496-64511") to RDAP servers
+    pub fn with_asn(mut self, entries: &[&str], urls: &[&str]) -> Self {
+        Self::add_service(&mut self.asn, entries, urls);
+        self
+    }
+
+    fn add_service(registry: &mut Bootstrap, entries: &[&str], urls: &[&str]) {
+        registry
+            .services
+            .push(vec![serde_json::json!(entries), serde_json::json!(urls)]);
+    }
+}
+
+/// Dated copy of every IANA bootstrap registry, for reproducible lookups
+///
+/// Written by [`crate::RdapClient::export_bootstrap_snapshot`]. The JSON form
+/// is a [`BootstrapMap`] plus `created` (RFC 3339) and `object_tags` keys, so
+/// a snapshot can also be loaded as a plain map.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct BootstrapRegistry {
-    version: String,
-    publication: Option<String>,
-    description: Option<String>,
-    services: Vec<Vec<serde_json::Value>>,
+pub struct BootstrapSnapshot {
+    /// When the registries were fetched
+    pub created: String,
+    #[serde(flatten)]
+    pub map: BootstrapMap,
+    /// Object tags registry (RFC 8521), mapping handle suffixes to servers
+    #[serde(default)]
+    pub object_tags: Bootstrap,
+}
+
+impl BootstrapSnapshot {
+    /// Load a snapshot from a JSON file
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    /// Write the snapshot as JSON
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_to(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
 }
 
 /// Bootstrap client for service discovery
@@ -24,6 +231,37 @@ pub struct BootstrapClient {
     http_client: reqwest::Client,
     config: Config,
     tld_overrides: TldOverrides,
+    /// Registry files fetched so far, keyed by URL, with their fetch time
+    /// (`None` for pre-built maps, which never expire)
+    registries: Mutex<HashMap<String, CachedRegistry>>,
+    /// Per-URL locks so concurrent lookups share a single registry fetch
+    fetching: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    /// Disk cache keeping registry files and their validators across runs
+    #[cfg(not(target_arch = "wasm32"))]
+    cache: Option<Arc<Cache>>,
+}
+
+/// Registry file with the time it was fetched and its HTTP validators
+#[derive(Clone)]
+struct CachedRegistry {
+    /// `None` for pre-built maps, which never expire
+    fetched: Option<Instant>,
+    registry: Arc<Bootstrap>,
+    /// `ETag` and `Last-Modified` from the response, sent back when the
+    /// entry expires so an unchanged file costs a 304 instead of a download
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl CachedRegistry {
+    fn prebuilt(registry: Bootstrap) -> Self {
+        Self {
+            fetched: None,
+            registry: Arc::new(registry),
+            etag: None,
+            last_modified: None,
+        }
+    }
 }
 
 impl BootstrapClient {
@@ -36,6 +274,81 @@ impl BootstrapClient {
             http_client: reqwest::Client::new(),
             config,
             tld_overrides,
+            registries: Mutex::new(HashMap::new()),
+            fetching: Mutex::new(HashMap::new()),
+            #[cfg(not(target_arch = "wasm32"))]
+            cache: None,
+        })
+    }
+
+    /// Keep registry files on disk, so a new process reuses them within the
+    /// bootstrap TTL and afterwards revalidates them with `If-None-Match` /
+    /// `If-Modified-Since` instead of downloading them again
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_cache(mut self, cache: Arc<Cache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Use a pre-resolved delegation map instead of fetching the IANA registries
+    pub fn with_map(self, map: BootstrapMap) -> Self {
+        let bootstrap = &self.config.bootstrap;
+        if let Ok(mut registries) = self.registries.lock() {
+            registries.insert(bootstrap.dns.clone(), CachedRegistry::prebuilt(map.dns));
+            registries.insert(bootstrap.ipv4.clone(), CachedRegistry::prebuilt(map.ipv4));
+            registries.insert(bootstrap.ipv6.clone(), CachedRegistry::prebuilt(map.ipv6));
+            registries.insert(bootstrap.asn.clone(), CachedRegistry::prebuilt(map.asn));
+        }
+        self
+    }
+
+    /// Use a pre-fetched object tags registry instead of fetching it
+    pub fn with_object_tags(self, object_tags: Bootstrap) -> Self {
+        if let Ok(mut registries) = self.registries.lock() {
+            registries.insert(
+                self.config.bootstrap.object_tags.clone(),
+                CachedRegistry::prebuilt(object_tags),
+            );
+        }
+        self
+    }
+
+    /// Concurrently fetch all bootstrap registry files (object tags included)
+    /// so later lookups are cache hits
+    pub async fn prefetch(&self) -> Result<()> {
+        let bootstrap = &self.config.bootstrap;
+        tokio::try_join!(
+            self.fetch_registry(&bootstrap.dns),
+            self.fetch_registry(&bootstrap.ipv4),
+            self.fetch_registry(&bootstrap.ipv6),
+            self.fetch_registry(&bootstrap.asn),
+            self.fetch_registry(&bootstrap.object_tags),
+        )?;
+        Ok(())
+    }
+
+    /// Fetch every registry (plus object tags) into a dated snapshot
+    ///
+    /// Registries already cached are reused.
+    pub async fn snapshot(&self) -> Result<BootstrapSnapshot> {
+        let bootstrap = &self.config.bootstrap;
+        let (dns, ipv4, ipv6, asn, object_tags) = tokio::try_join!(
+            self.fetch_registry(&bootstrap.dns),
+            self.fetch_registry(&bootstrap.ipv4),
+            self.fetch_registry(&bootstrap.ipv6),
+            self.fetch_registry(&bootstrap.asn),
+            self.fetch_registry(&bootstrap.object_tags),
+        )?;
+
+        Ok(BootstrapSnapshot {
+            created: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            map: BootstrapMap {
+                dns: (*dns).clone(),
+                ipv4: (*ipv4).clone(),
+                ipv6: (*ipv6).clone(),
+                asn: (*asn).clone(),
+            },
+            object_tags: (*object_tags).clone(),
         })
     }
 
@@ -48,17 +361,12 @@ impl BootstrapClient {
                     .map_err(|e| RdapError::Bootstrap(format!("Invalid IANA RDAP URL: {e}")))?;
                 Ok(vec![url])
             }
-            QueryType::Domain => {
-                // Priority: tlds.json first, then bootstrap
-                if let Some(url) = config::lookup_tld_override(&self.tld_overrides, &request.query)
-                {
-                    log::debug!("Found TLD override for {}: {url}", request.query);
-                    return Ok(vec![url]);
-                }
-
-                // Fall back to IANA bootstrap
-                let registry = self.fetch_registry(&self.config.bootstrap.dns).await?;
-                Ok(self.match_domain(&registry, &request.query))
+            QueryType::Domain => self.lookup_domain(&request.query).await,
+            QueryType::Nameserver => {
+                // A nameserver is served by its parent domain's registry
+                let name = request.query.trim_end_matches('.');
+                let parent = name.split_once('.').map_or(name, |(_, parent)| parent);
+                self.lookup_domain(parent).await
             }
             QueryType::Ip => {
                 let bootstrap_url = if request.query.contains(':') {
@@ -67,11 +375,11 @@ impl BootstrapClient {
                     &self.config.bootstrap.ipv4
                 };
                 let registry = self.fetch_registry(bootstrap_url).await?;
-                self.match_ip(&registry, &request.query)
+                Self::match_ip(&registry, &request.query)
             }
             QueryType::Autnum => {
                 let registry = self.fetch_registry(&self.config.bootstrap.asn).await?;
-                self.match_asn(&registry, &request.query)
+                Self::match_asn(&registry, &request.query)
             }
             QueryType::Entity => Err(RdapError::Bootstrap(
                 "Entity queries require explicit server (-s/--server)".to_owned(),
@@ -82,11 +390,91 @@ impl BootstrapClient {
         }
     }
 
-    /// Fetch bootstrap registry file from URL
-    async fn fetch_registry(&self, url: &str) -> Result<BootstrapRegistry> {
+    /// Lookup RDAP servers for a domain name
+    async fn lookup_domain(&self, domain: &str) -> Result<Vec<Url>> {
+        // Priority: tlds.json first, then bootstrap
+        if let Some(url) = config::lookup_tld_override(&self.tld_overrides, domain) {
+            log::debug!("Found TLD override for {domain}: {url}");
+            return Ok(vec![url]);
+        }
+
+        // Fall back to IANA bootstrap
+        let registry = self.fetch_registry(&self.config.bootstrap.dns).await?;
+        Ok(registry.match_domain(domain))
+    }
+
+    /// Configuration in use
+    pub const fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Fetch bootstrap registry file from URL (cached for the bootstrap TTL)
+    async fn fetch_registry(&self, url: &str) -> Result<Arc<Bootstrap>> {
+        if let Some(registry) = self.cached_registry(url) {
+            log::debug!("Using cached bootstrap registry: {url}");
+            return Ok(registry);
+        }
+
+        // Wait for a fetch of the same file already in flight (e.g. a batch
+        // of `.com` domains), then reuse its result
+        let lock = self
+            .fetching
+            .lock()
+            .ok()
+            .map(|mut fetching| Arc::clone(fetching.entry(url.to_string()).or_default()));
+        let _guard = match &lock {
+            Some(lock) => Some(lock.lock().await),
+            None => None,
+        };
+        if let Some(registry) = self.cached_registry(url) {
+            log::debug!("Using bootstrap registry fetched concurrently: {url}");
+            return Ok(registry);
+        }
+        if self.load_from_disk(url)
+            && let Some(registry) = self.cached_registry(url)
+        {
+            log::debug!("Using bootstrap registry from the disk cache: {url}");
+            return Ok(registry);
+        }
+
+        // Revalidate an expired copy instead of downloading it again
+        let stale = self.registries.lock().ok().and_then(|r| {
+            r.get(url).map(|cached| {
+                (
+                    Arc::clone(&cached.registry),
+                    cached.etag.clone(),
+                    cached.last_modified.clone(),
+                )
+            })
+        });
+
         log::debug!("Fetching bootstrap registry: {url}");
 
-        let response = self.http_client.get(url).send().await?;
+        let mut request = self.http_client.get(url);
+        if let Some((_, etag, last_modified)) = &stale {
+            if let Some(etag) = etag {
+                request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = last_modified {
+                request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        let response = request.send().await?;
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED
+            && let Some((registry, _, _)) = stale
+        {
+            log::debug!("Bootstrap registry unchanged: {url}");
+            let revalidated = self.registries.lock().ok().and_then(|mut registries| {
+                let cached = registries.get_mut(url)?;
+                cached.fetched = Some(Instant::now());
+                Some(cached.clone())
+            });
+            if let Some(cached) = revalidated {
+                self.save_to_disk(url, &cached);
+            }
+            return Ok(registry);
+        }
 
         if !response.status().is_success() {
             let status = response.status();
@@ -95,49 +483,108 @@ impl BootstrapClient {
             )));
         }
 
-        let registry: BootstrapRegistry = response.json().await?;
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(reqwest::header::ETAG);
+        let last_modified = header(reqwest::header::LAST_MODIFIED);
+
+        let registry: Arc<Bootstrap> = Arc::new(response.json().await?);
+        let cached = CachedRegistry {
+            fetched: Some(Instant::now()),
+            registry: Arc::clone(&registry),
+            etag,
+            last_modified,
+        };
+        self.save_to_disk(url, &cached);
+        if let Ok(mut registries) = self.registries.lock() {
+            registries.insert(url.to_string(), cached);
+        }
         Ok(registry)
     }
 
-    /// Match domain name
-    fn match_domain(&self, registry: &BootstrapRegistry, domain: &str) -> Vec<Url> {
-        let domain = domain.trim_end_matches('.').to_lowercase();
-
-        // Build lookup map
-        let mut map: HashMap<String, Vec<String>> = HashMap::new();
-        for service in &registry.services {
-            if service.len() >= 2
-                && let (Some(entries), Some(urls)) = (service[0].as_array(), service[1].as_array())
-            {
-                let url_strings: Vec<String> = urls
-                    .iter()
-                    .filter_map(|v| v.as_str().map(std::borrow::ToOwned::to_owned))
-                    .collect();
-
-                for entry in entries {
-                    if let Some(tld) = entry.as_str() {
-                        map.insert(tld.to_lowercase(), url_strings.clone());
-                    }
+    /// Load the registry file for `url` from the disk cache into memory,
+    /// returning whether there was one
+    ///
+    /// A file older than the bootstrap TTL is loaded as expired, so it is
+    /// revalidated with its `ETag`/`Last-Modified` rather than used as is.
+    fn load_from_disk(&self, url: &str) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let Some(file) = self.cache.as_ref().and_then(|c| c.get_registry(url)) else {
+                return false;
+            };
+            let registry = match Bootstrap::from_json(&file.body) {
+                Ok(registry) => registry,
+                Err(e) => {
+                    log::debug!("Ignoring unparsable cached bootstrap registry {url}: {e}");
+                    return false;
                 }
+            };
+            let ttl = self.config.cache.bootstrap_ttl();
+            let age = file.age();
+            let now = Instant::now();
+            let fetched = if age < ttl {
+                now.checked_sub(age).unwrap_or(now)
+            } else {
+                now.checked_sub(ttl).unwrap_or(now)
+            };
+            if let Ok(mut registries) = self.registries.lock() {
+                registries.insert(
+                    url.to_string(),
+                    CachedRegistry {
+                        fetched: Some(fetched),
+                        registry: Arc::new(registry),
+                        etag: file.etag,
+                        last_modified: file.last_modified,
+                    },
+                );
             }
+            true
         }
+        #[cfg(target_arch = "wasm32")]
+        false
+    }
 
-        // Try to match from most specific to least specific
-        let mut parts: Vec<&str> = domain.split('.').collect();
-
-        while !parts.is_empty() {
-            let test_domain = parts.join(".");
-            if let Some(urls) = map.get(&test_domain) {
-                return urls.iter().filter_map(|s| Url::parse(s).ok()).collect();
+    /// Store a fetched or revalidated registry file in the disk cache
+    #[cfg_attr(target_arch = "wasm32", allow(clippy::unused_self, unused_variables))]
+    fn save_to_disk(&self, url: &str, cached: &CachedRegistry) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(cache) = &self.cache {
+            let file = serde_json::to_string(&*cached.registry).map(|body| CachedRegistryFile {
+                url: url.to_string(),
+                fetched: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs()),
+                etag: cached.etag.clone(),
+                last_modified: cached.last_modified.clone(),
+                body,
+            });
+            if let Err(e) = file
+                .map_err(RdapError::from)
+                .and_then(|f| cache.set_registry(&f))
+            {
+                log::warn!("Failed to cache bootstrap registry {url}: {e}");
             }
-            parts.remove(0);
         }
+    }
 
-        vec![]
+    /// Registry file for `url` if cached and still within the bootstrap TTL
+    fn cached_registry(&self, url: &str) -> Option<Arc<Bootstrap>> {
+        let ttl = self.config.cache.bootstrap_ttl();
+        self.registries.lock().ok().and_then(|r| {
+            r.get(url)
+                .filter(|cached| cached.fetched.is_none_or(|at| at.elapsed() < ttl))
+                .map(|cached| Arc::clone(&cached.registry))
+        })
     }
 
     /// Match IP address (supports standard IPs, shorthand IPs, and CIDR)
-    fn match_ip(&self, registry: &BootstrapRegistry, ip_query: &str) -> Result<Vec<Url>> {
+    fn match_ip(registry: &Bootstrap, ip_query: &str) -> Result<Vec<Url>> {
         // Normalize the IP (handles shorthand like 1.1 -> 1.0.0.1)
         let normalized = ip::normalize_ip(ip_query)
             .ok_or_else(|| RdapError::InvalidQuery(format!("Invalid IP address: {ip_query}")))?;
@@ -149,84 +596,467 @@ impl BootstrapClient {
             .parse()
             .map_err(|_| RdapError::InvalidQuery(format!("Invalid IP address: {ip_str}")))?;
 
-        for service in &registry.services {
-            if service.len() >= 2
-                && let (Some(entries), Some(urls)) = (service[0].as_array(), service[1].as_array())
-            {
-                for entry in entries {
-                    if let Some(cidr) = entry.as_str()
-                        && Self::ip_in_network(&addr, cidr)
-                    {
-                        let url_list: Vec<Url> = urls
-                            .iter()
-                            .filter_map(|v| v.as_str().and_then(|s| Url::parse(s).ok()))
-                            .collect();
-                        return Ok(url_list);
-                    }
-                }
-            }
-        }
+        Ok(registry.match_ip(addr))
+    }
 
-        Ok(vec![])
+    /// Match AS number
+    fn match_asn(registry: &Bootstrap, asn_str: &str) -> Result<Vec<Url>> {
+        Ok(registry.match_asn(crate::request::parse_asn(asn_str)?))
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    /// Check if IP is in CIDR network using ipnet
-    fn ip_in_network(addr: &IpAddr, cidr: &str) -> bool {
-        if let Ok(network) = cidr.parse::<IpNet>() {
-            return network.contains(addr);
+    /// Client with `config` and no TLD overrides or cached registries
+    fn test_client(config: Config) -> BootstrapClient {
+        BootstrapClient {
+            http_client: reqwest::Client::new(),
+            config,
+            tld_overrides: TldOverrides::new(),
+            registries: Mutex::new(HashMap::new()),
+            fetching: Mutex::new(HashMap::new()),
+            cache: None,
         }
-        false
     }
+    use crate::config::{BootstrapConfig, CacheConfig};
 
-    /// Match AS number
-    fn match_asn(&self, registry: &BootstrapRegistry, asn_str: &str) -> Result<Vec<Url>> {
-        // Case-insensitive strip of "AS" prefix
-        let asn_str = if asn_str.to_uppercase().starts_with("AS") {
-            &asn_str[2..]
-        } else {
-            asn_str
+    #[tokio::test]
+    async fn test_prefetch_fetches_all_registries_once() {
+        let mut server = mockito::Server::new_async().await;
+        let body = r#"{"version": "1.0", "services": []}"#;
+        let mut mocks = Vec::new();
+        for name in ["dns", "ipv4", "ipv6", "asn", "object-tags"] {
+            let mock = server
+                .mock("GET", format!("/{name}.json").as_str())
+                .with_body(body)
+                .expect(1)
+                .create_async()
+                .await;
+            mocks.push(mock);
+        }
+
+        let config = Config {
+            bootstrap: BootstrapConfig {
+                dns: format!("{}/dns.json", server.url()),
+                asn: format!("{}/asn.json", server.url()),
+                ipv4: format!("{}/ipv4.json", server.url()),
+                ipv6: format!("{}/ipv6.json", server.url()),
+                object_tags: format!("{}/object-tags.json", server.url()),
+            },
+            cache: CacheConfig::default(),
+            theme: None,
         };
-        let asn: u32 = asn_str
-            .parse()
-            .map_err(|_| RdapError::InvalidQuery(format!("Invalid AS number: {asn_str}")))?;
+        let client = test_client(config);
 
-        for service in &registry.services {
-            if service.len() >= 2
-                && let (Some(entries), Some(urls)) = (service[0].as_array(), service[1].as_array())
-            {
-                for entry in entries {
-                    if let Some(range_str) = entry.as_str()
-                        && Self::asn_in_range(asn, range_str)
-                    {
-                        let url_list: Vec<Url> = urls
-                            .iter()
-                            .filter_map(|v| v.as_str().and_then(|s| Url::parse(s).ok()))
-                            .collect();
-                        return Ok(url_list);
-                    }
-                }
-            }
+        client.prefetch().await.unwrap();
+
+        // Later lookups are served from the cache
+        let request = RdapRequest::new(QueryType::Autnum, "AS64500");
+        assert!(client.lookup(&request).await.unwrap().is_empty());
+        let request = RdapRequest::new(QueryType::Domain, "example.invalid");
+        assert!(client.lookup(&request).await.unwrap().is_empty());
+
+        for mock in &mocks {
+            mock.assert_async().await;
+        }
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        let mut server = mockito::Server::new_async().await;
+        let registries = [
+            (
+                "dns",
+                r#"[[["com"], ["https://rdap.verisign.example/com/v1/"]]]"#,
+            ),
+            (
+                "ipv4",
+                r#"[[["192.0.2.0/24"], ["https://rdap.arin.example/"]]]"#,
+            ),
+            ("ipv6", "[]"),
+            (
+                "asn",
+                r#"[[["64496-64511"], ["https://rdap.ripe.example/"]]]"#,
+            ),
+            (
+                "object-tags",
+                r#"[[["ops@example.net"], ["ARIN"], ["https://rdap.arin.example/"]]]"#,
+            ),
+        ];
+        let mut mocks = Vec::new();
+        for (name, services) in registries {
+            let mock = server
+                .mock("GET", format!("/{name}.json").as_str())
+                .with_body(format!(r#"{{"version": "1.0", "services": {services}}}"#))
+                .expect(1)
+                .create_async()
+                .await;
+            mocks.push(mock);
+        }
+
+        let url = |name: &str| format!("{}/{name}.json", server.url());
+        let client = test_client(Config {
+            bootstrap: BootstrapConfig {
+                dns: url("dns"),
+                asn: url("asn"),
+                ipv4: url("ipv4"),
+                ipv6: url("ipv6"),
+                object_tags: url("object-tags"),
+            },
+            cache: CacheConfig::default(),
+            theme: None,
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshot.json");
+        client.snapshot().await.unwrap().write_to(&path).unwrap();
+        for mock in &mocks {
+            mock.assert_async().await;
+        }
+        drop(server);
+
+        // Loading works without the registries being reachable
+        let snapshot = BootstrapSnapshot::from_file(&path).unwrap();
+        assert!(chrono::DateTime::parse_from_rfc3339(&snapshot.created).is_ok());
+        assert_eq!(snapshot.object_tags.services.len(), 1);
+        let offline = test_client(Config::default())
+            .with_map(snapshot.map)
+            .with_object_tags(snapshot.object_tags);
+
+        let request = RdapRequest::new(QueryType::Domain, "example.com");
+        assert_eq!(
+            offline.lookup(&request).await.unwrap(),
+            vec![Url::parse("https://rdap.verisign.example/com/v1/").unwrap()]
+        );
+        let request = RdapRequest::new(QueryType::Autnum, "AS64500");
+        assert_eq!(
+            offline.lookup(&request).await.unwrap(),
+            vec![Url::parse("https://rdap.ripe.example/").unwrap()]
+        );
+        // Re-exporting needs no network either, object tags included
+        let copy = offline.snapshot().await.unwrap();
+        assert_eq!(copy.object_tags.services.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_lookups_share_fetch() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/dns.json")
+            .with_body(
+                r#"{"version": "1.0", "services": [[["com"], ["https://rdap.verisign.example/com/v1/"]]]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let url = format!("{}/dns.json", server.url());
+        let client = test_client(
+            serde_json::from_value(serde_json::json!({
+                "bootstrap": {"dns": url, "asn": url, "ipv4": url, "ipv6": url},
+                "cache": {"ttl_seconds": 86400}
+            }))
+            .unwrap(),
+        );
+
+        let requests: Vec<RdapRequest> = ["a.com", "b.com", "c.com", "d.com"]
+            .iter()
+            .map(|name| RdapRequest::new(QueryType::Domain, *name))
+            .collect();
+        let results =
+            futures_util::future::join_all(requests.iter().map(|r| client.lookup(r))).await;
+        for urls in results {
+            assert_eq!(urls.unwrap().len(), 1);
         }
 
-        Ok(vec![])
+        mock.assert_async().await;
+        drop(server);
     }
 
-    /// Check if AS number is in range
-    fn asn_in_range(asn: u32, range_str: &str) -> bool {
-        if let Some(dash_pos) = range_str.find('-') {
-            // Range: "1000-2000"
-            if let (Ok(start), Ok(end)) = (
-                range_str[..dash_pos].parse::<u32>(),
-                range_str[dash_pos + 1..].parse::<u32>(),
-            ) {
-                return asn >= start && asn <= end;
-            }
-        } else {
-            // Single AS: "1000"
-            if let Ok(single) = range_str.parse::<u32>() {
-                return asn == single;
-            }
+    #[tokio::test]
+    async fn test_nameserver_routes_via_parent_domain() {
+        let map = BootstrapMap::new()
+            .with_dns(&["com"], &["https://rdap.verisign.example/com/v1/"])
+            .with_dns(&["example.co.uk"], &["https://rdap.example-sld.test/"]);
+        let client = test_client(Config::default()).with_map(map);
+
+        let domain = RdapRequest::new(QueryType::Domain, "example.com");
+        let nameserver = RdapRequest::new(QueryType::Nameserver, "ns1.example.com");
+        let expected = client.lookup(&domain).await.unwrap();
+        assert_eq!(
+            expected,
+            vec![Url::parse("https://rdap.verisign.example/com/v1/").unwrap()]
+        );
+        assert_eq!(client.lookup(&nameserver).await.unwrap(), expected);
+
+        // The parent domain, not the host label, picks the delegation
+        let nameserver = RdapRequest::new(QueryType::Nameserver, "ns.example.co.uk.");
+        assert_eq!(
+            client.lookup(&nameserver).await.unwrap(),
+            vec![Url::parse("https://rdap.example-sld.test/").unwrap()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unicode_tld_matches_a_label() {
+        let map = BootstrapMap::new().with_dns(&["xn--p1ai"], &["https://rdap.cctld.example/"]);
+        let client = test_client(Config::default()).with_map(map);
+
+        let request = RdapRequest::new(QueryType::Domain, "пример.рф");
+        assert_eq!(
+            client.lookup(&request).await.unwrap(),
+            vec![Url::parse("https://rdap.cctld.example/").unwrap()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expired_registry_revalidated() {
+        let mut server = mockito::Server::new_async().await;
+        let unchanged = server
+            .mock("GET", "/asn.json")
+            .match_header("if-none-match", "\"v1\"")
+            .match_header("if-modified-since", "Tue, 01 Oct 2024 00:00:00 GMT")
+            .with_status(304)
+            .expect(2)
+            .create_async()
+            .await;
+        let full = server
+            .mock("GET", "/asn.json")
+            .with_header("etag", "\"v1\"")
+            .with_header("last-modified", "Tue, 01 Oct 2024 00:00:00 GMT")
+            .with_body(
+                r#"{"version": "1.0", "services": [[["64496-64511"], ["https://rdap.example/"]]]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let url = format!("{}/asn.json", server.url());
+        let client = test_client(
+            serde_json::from_value(serde_json::json!({
+                "bootstrap": {"dns": url, "asn": url, "ipv4": url, "ipv6": url},
+                "cache": {"ttl_seconds": 86400, "bootstrap_ttl_seconds": 0}
+            }))
+            .unwrap(),
+        );
+
+        // Every lookup revalidates (zero TTL), but only the first downloads
+        let request = RdapRequest::new(QueryType::Autnum, "AS64500");
+        for _ in 0..3 {
+            assert_eq!(
+                client.lookup(&request).await.unwrap(),
+                vec![Url::parse("https://rdap.example/").unwrap()]
+            );
         }
-        false
+
+        full.assert_async().await;
+        unchanged.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_registry_revalidated_across_clients() {
+        let mut server = mockito::Server::new_async().await;
+        let unchanged = server
+            .mock("GET", "/asn.json")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .expect(1)
+            .create_async()
+            .await;
+        let full = server
+            .mock("GET", "/asn.json")
+            .with_header("etag", "\"v1\"")
+            .with_body(
+                r#"{"version": "1.0", "services": [[["64496-64511"], ["https://rdap.example/"]]]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("{}/asn.json", server.url());
+        let config: Config = serde_json::from_value(serde_json::json!({
+            "bootstrap": {"dns": url, "asn": url, "ipv4": url, "ipv6": url},
+            "cache": {"ttl_seconds": 86400, "bootstrap_ttl_seconds": 0}
+        }))
+        .unwrap();
+        let request = RdapRequest::new(QueryType::Autnum, "AS64500");
+
+        // Two clients standing in for two runs of the CLI: the second one
+        // revalidates the file the first one stored instead of downloading it
+        for _ in 0..2 {
+            let client = test_client(config.clone())
+                .with_cache(Arc::new(Cache::in_dir(dir.path()).unwrap()));
+            assert_eq!(
+                client.lookup(&request).await.unwrap(),
+                vec![Url::parse("https://rdap.example/").unwrap()]
+            );
+        }
+
+        full.assert_async().await;
+        unchanged.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_ttl_from_config() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/asn.json")
+            .with_body(r#"{"version": "1.0", "services": []}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let url = format!("{}/asn.json", server.url());
+        let mut config: Config = serde_json::from_value(serde_json::json!({
+            "bootstrap": {"dns": url, "asn": url, "ipv4": url, "ipv6": url},
+            "cache": {"ttl_seconds": 86400, "bootstrap_ttl_seconds": 0}
+        }))
+        .unwrap();
+        assert!(config.cache.response_ttl().is_zero());
+
+        // A zero bootstrap TTL refetches on every lookup
+        let client = test_client(config.clone());
+        let request = RdapRequest::new(QueryType::Autnum, "AS64500");
+        client.lookup(&request).await.unwrap();
+        client.lookup(&request).await.unwrap();
+        mock.assert_async().await;
+
+        // The shared TTL keeps it for the second lookup
+        config.cache.bootstrap_ttl_seconds = None;
+        config.bootstrap.asn = format!("{}/asn-shared.json", server.url());
+        let mock = server
+            .mock("GET", "/asn-shared.json")
+            .with_body(r#"{"version": "1.0", "services": []}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let client = test_client(config);
+        client.lookup(&request).await.unwrap();
+        client.lookup(&request).await.unwrap();
+        mock.assert_async().await;
+        drop(server);
+    }
+
+    fn asn_registry(entries: &[(&[&str], &str)]) -> Bootstrap {
+        let services = entries
+            .iter()
+            .map(|(ranges, url)| vec![serde_json::json!(ranges), serde_json::json!([url])])
+            .collect();
+        Bootstrap {
+            version: "1.0".to_string(),
+            publication: None,
+            description: None,
+            services,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_map_from_json() {
+        let map = BootstrapMap::from_json(
+            r#"{
+                "dns": {"services": [[["com"], ["https://rdap.example.com/"]]]},
+                "ipv4": {"services": [[["192.0.2.0/24"], ["https://rdap.example.net/"]]]}
+            }"#,
+        )
+        .unwrap();
+        let client = BootstrapClient::new().unwrap().with_map(map);
+
+        let request = RdapRequest::new(QueryType::Domain, "example.com");
+        let urls = client.lookup(&request).await.unwrap();
+        assert_eq!(urls, vec![Url::parse("https://rdap.example.com/").unwrap()]);
+
+        let request = RdapRequest::new(QueryType::Ip, "192.0.2.1");
+        let urls = client.lookup(&request).await.unwrap();
+        assert_eq!(urls, vec![Url::parse("https://rdap.example.net/").unwrap()]);
+
+        // Registries missing from the map resolve to nothing
+        let request = RdapRequest::new(QueryType::Autnum, "AS64500");
+        assert!(client.lookup(&request).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bootstrap_matches_without_client() {
+        let dns = Bootstrap::from_json(
+            r#"{"services": [
+                [["uk"], ["https://rdap.uk.example/"]],
+                [["co.uk"], ["https://rdap.co-uk.example/"]],
+                [["xn--p1ai"], ["https://rdap.rf.example/"]]
+            ]}"#,
+        )
+        .unwrap();
+        let first = |urls: Vec<Url>| urls.first().map(Url::to_string);
+        assert_eq!(
+            first(dns.match_domain("Example.CO.UK.")).as_deref(),
+            Some("https://rdap.co-uk.example/")
+        );
+        assert_eq!(
+            first(dns.match_domain("example.org.uk")).as_deref(),
+            Some("https://rdap.uk.example/")
+        );
+        assert_eq!(
+            first(dns.match_domain("пример.рф")).as_deref(),
+            Some("https://rdap.rf.example/")
+        );
+        assert!(dns.match_domain("example.dev").is_empty());
+
+        let ipv6 = Bootstrap::from_json(
+            r#"{"services": [[["2001:db8::/32"], ["https://rdap.v6.example/"]]]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            first(ipv6.match_ip("2001:db8::1".parse().unwrap())).as_deref(),
+            Some("https://rdap.v6.example/")
+        );
+        assert!(ipv6.match_ip("2001:db9::1".parse().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_match_asn_skips_malformed_entry() {
+        let registry = asn_registry(&[
+            (&["1-4294967296"], "https://bad.example/"),
+            (&["64496-64511", "4294967296"], "https://also-bad.example/"),
+            (&["64000-65000"], "https://good.example/"),
+        ]);
+
+        let urls = BootstrapClient::match_asn(&registry, "AS64500").unwrap();
+        assert_eq!(urls, vec![Url::parse("https://also-bad.example/").unwrap()]);
+
+        let urls = BootstrapClient::match_asn(&registry, "64900").unwrap();
+        assert_eq!(urls, vec![Url::parse("https://good.example/").unwrap()]);
+    }
+
+    #[test]
+    fn test_match_asn_32_bit_boundaries() {
+        let registry = asn_registry(&[
+            (&["65000-131071"], "https://spanning.example/"),
+            (&["4200000000-4294967294"], "https://private.example/"),
+            (&["4294967295"], "https://last.example/"),
+        ]);
+
+        for (asn, expected) in [
+            ("AS65535", "https://spanning.example/"),
+            ("AS65536", "https://spanning.example/"),
+            ("AS131071", "https://spanning.example/"),
+            ("AS4200000000", "https://private.example/"),
+            ("4294967294", "https://private.example/"),
+            ("4294967295", "https://last.example/"),
+        ] {
+            let urls = BootstrapClient::match_asn(&registry, asn).unwrap();
+            assert_eq!(urls, vec![Url::parse(expected).unwrap()], "{asn}");
+        }
+        assert!(
+            BootstrapClient::match_asn(&registry, "AS131072")
+                .unwrap()
+                .is_empty()
+        );
+
+        let err = BootstrapClient::match_asn(&registry, "AS4294967296").unwrap_err();
+        assert!(matches!(err, RdapError::InvalidQuery(_)));
     }
 }
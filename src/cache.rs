@@ -1,14 +1,64 @@
-//! Cache implementation for bootstrap files
+//! Cache implementation for bootstrap files and RDAP responses
 
 use crate::error::Result;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
-use std::time::{Duration, SystemTime};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use url::Url;
 
-/// Bootstrap cache manager
+/// Subdirectory holding cached RDAP responses
+const RESPONSES_DIR: &str = "responses";
+
+/// Subdirectory holding bootstrap registry files with their validators
+const REGISTRIES_DIR: &str = "bootstrap";
+
+/// Extension of files still being written
+const TMP_EXTENSION: &str = "tmp";
+
+/// On-disk RDAP response with its expiry time
+#[derive(Serialize, Deserialize)]
+struct CachedResponse {
+    /// Request URL and headers the response was fetched with
+    key: String,
+    /// Seconds since the Unix epoch
+    expires: u64,
+    body: String,
+}
+
+/// On-disk bootstrap registry file with the HTTP validators it was served
+/// with, so a later run can revalidate it instead of downloading it again
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedRegistryFile {
+    /// Registry URL
+    pub url: String,
+    /// When the file was last fetched or revalidated, in seconds since the
+    /// Unix epoch
+    pub fetched: u64,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// Registry JSON
+    pub body: String,
+}
+
+impl CachedRegistryFile {
+    /// Time since the file was last fetched or revalidated
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(unix_now().saturating_sub(self.fetched))
+    }
+}
+
+/// Bootstrap and response cache manager
 pub struct Cache {
+    #[allow(clippy::struct_field_names)]
     cache_dir: PathBuf,
     ttl: Duration,
+    /// Size limit, enforced when a write takes the estimated size past it
+    max_bytes: Option<u64>,
+    /// Size as of the last prune plus the bytes written since; `u64::MAX`
+    /// until the first write, so that one measures the cache
+    estimated_bytes: AtomicU64,
 }
 
 impl Cache {
@@ -19,11 +69,19 @@ impl Cache {
             |h| PathBuf::from(h).join(".cache/rdap"),
         );
 
+        Self::in_dir(cache_dir)
+    }
+
+    /// Create a cache in a specific directory
+    pub fn in_dir(cache_dir: impl Into<PathBuf>) -> Result<Self> {
+        let cache_dir = cache_dir.into();
         fs::create_dir_all(&cache_dir)?;
 
         Ok(Self {
             cache_dir,
-            ttl: Duration::from_secs(24 * 3600), // 24 hours
+            ttl: Duration::from_hours(24),
+            max_bytes: None,
+            estimated_bytes: AtomicU64::new(u64::MAX),
         })
     }
 
@@ -33,6 +91,16 @@ impl Cache {
         self
     }
 
+    /// Prune the oldest entries once writes take the cache past `max_bytes`
+    /// (default: unbounded)
+    ///
+    /// The directory is only scanned on the first write and when the size
+    /// estimate kept since the last scan crosses the limit.
+    pub const fn with_max_size(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
     /// Get cached file if valid
     pub fn get(&self, key: &str) -> Option<Vec<u8>> {
         let path = self.cache_dir.join(key);
@@ -57,25 +125,371 @@ impl Cache {
 
     /// Save to cache
     pub fn set(&self, key: &str, data: &[u8]) -> Result<()> {
-        let path = self.cache_dir.join(key);
-        fs::write(&path, data)?;
+        self.write(&self.cache_dir.join(key), data)
+    }
+
+    /// Get the cached response body for `url` fetched with `headers`,
+    /// removing it if expired
+    pub fn get_response(&self, url: &Url, headers: &[(&str, &str)]) -> Option<String> {
+        let key = response_key(url, headers);
+        let path = self.response_path(&key);
+        let entry: CachedResponse = serde_json::from_slice(&fs::read(&path).ok()?).ok()?;
+        if entry.key != key {
+            return None;
+        }
+
+        if entry.expires <= unix_now() {
+            log::debug!("Cached response expired for {url}");
+            let _ = fs::remove_file(&path);
+            return None;
+        }
+
+        Some(entry.body)
+    }
+
+    /// Save a response body for `url` fetched with `headers`, valid for `ttl`
+    ///
+    /// Headers that change the answer (`Accept`, `Accept-Language`, `Host`)
+    /// are part of the key; responses to credentialed requests must not be
+    /// stored at all.
+    pub fn set_response(
+        &self,
+        url: &Url,
+        headers: &[(&str, &str)],
+        body: &str,
+        ttl: Duration,
+    ) -> Result<()> {
+        let key = response_key(url, headers);
+        let entry = CachedResponse {
+            expires: unix_now().saturating_add(ttl.as_secs()),
+            body: body.to_string(),
+            key,
+        };
+        fs::create_dir_all(self.cache_dir.join(RESPONSES_DIR))?;
+        self.write(
+            &self.response_path(&entry.key),
+            &serde_json::to_vec(&entry)?,
+        )
+    }
+
+    /// Get the stored registry file for `url`, however old
+    ///
+    /// Expiry is up to the caller, which can still revalidate a stale file
+    /// with its `ETag`/`Last-Modified`.
+    pub fn get_registry(&self, url: &str) -> Option<CachedRegistryFile> {
+        let entry: CachedRegistryFile =
+            serde_json::from_slice(&fs::read(self.registry_path(url)).ok()?).ok()?;
+        (entry.url == url).then_some(entry)
+    }
+
+    /// Store a registry file with its validators
+    pub fn set_registry(&self, entry: &CachedRegistryFile) -> Result<()> {
+        fs::create_dir_all(self.cache_dir.join(REGISTRIES_DIR))?;
+        self.write(&self.registry_path(&entry.url), &serde_json::to_vec(entry)?)
+    }
+
+    /// Write a cache file, then prune if it takes the cache past the size limit
+    ///
+    /// Data goes to a temporary file that is renamed into place, so an
+    /// interrupted write never leaves a truncated entry behind.
+    fn write(&self, path: &Path, data: &[u8]) -> Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(format!(".{}.{TMP_EXTENSION}", std::process::id()));
+        let tmp = PathBuf::from(tmp);
+        fs::write(&tmp, data)?;
+        if let Err(e) = fs::rename(&tmp, path) {
+            let _ = fs::remove_file(&tmp);
+            return Err(e.into());
+        }
+
+        if let Some(max_bytes) = self.max_bytes {
+            // Overwritten entries are counted again, so this errs toward
+            // pruning early
+            let len = data.len() as u64;
+            let before = self
+                .estimated_bytes
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                    Some(n.saturating_add(len))
+                })
+                .unwrap_or(u64::MAX);
+            if before.saturating_add(len) > max_bytes {
+                self.prune(max_bytes)?;
+            }
+        }
         Ok(())
     }
 
+    /// Remove the least recently written entries until the cache takes at
+    /// most `max_bytes`, returning the number of bytes freed
+    pub fn prune(&self, max_bytes: u64) -> Result<u64> {
+        let mut entries = self.entries()?;
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        if total <= max_bytes {
+            self.estimated_bytes.store(total, Ordering::Relaxed);
+            return Ok(0);
+        }
+
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        let mut freed = 0;
+        for (path, len, _) in entries {
+            if total <= max_bytes {
+                break;
+            }
+            match fs::remove_file(&path) {
+                Ok(()) => {
+                    log::debug!("Pruned cache entry {}", path.display());
+                    total -= len;
+                    freed += len;
+                }
+                // Already gone, e.g. removed by another process
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => total -= len,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        self.estimated_bytes.store(total, Ordering::Relaxed);
+        Ok(freed)
+    }
+
+    /// Cache files with their size and modification time, skipping files
+    /// still being written
+    fn entries(&self) -> Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let mut entries = Vec::new();
+        for dir in [
+            self.cache_dir.clone(),
+            self.cache_dir.join(RESPONSES_DIR),
+            self.cache_dir.join(REGISTRIES_DIR),
+        ] {
+            let read_dir = match fs::read_dir(&dir) {
+                Ok(read_dir) => read_dir,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            for entry in read_dir {
+                let path = entry?.path();
+                if path.extension().is_some_and(|ext| ext == TMP_EXTENSION) {
+                    continue;
+                }
+                let Ok(metadata) = fs::metadata(&path) else {
+                    continue;
+                };
+                if metadata.is_file() {
+                    let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+                    entries.push((path, metadata.len(), modified));
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// File holding the cached response for `key`
+    fn response_path(&self, key: &str) -> PathBuf {
+        self.cache_dir
+            .join(RESPONSES_DIR)
+            .join(format!("{:016x}.json", fnv1a(key.as_bytes())))
+    }
+
+    /// File holding the cached registry from `url`
+    fn registry_path(&self, url: &str) -> PathBuf {
+        self.cache_dir
+            .join(REGISTRIES_DIR)
+            .join(format!("{:016x}.json", fnv1a(url.as_bytes())))
+    }
+
     /// Clear cache
     pub fn clear(&self) -> Result<()> {
         for entry in fs::read_dir(&self.cache_dir)? {
             let entry = entry?;
             if entry.path().is_file() {
                 fs::remove_file(entry.path())?;
+            } else if entry.file_name() == RESPONSES_DIR || entry.file_name() == REGISTRIES_DIR {
+                fs::remove_dir_all(entry.path())?;
             }
         }
         Ok(())
     }
 }
 
+/// Cache key for `url` requested with `headers`, one `name: value` per line
+fn response_key(url: &Url, headers: &[(&str, &str)]) -> String {
+    let mut key = url.to_string();
+    for (name, value) in headers {
+        key.push('\n');
+        key.push_str(&name.to_ascii_lowercase());
+        key.push_str(": ");
+        key.push_str(value);
+    }
+    key
+}
+
+/// 64-bit FNV-1a hash, stable across Rust releases so file names stay valid
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Current time in seconds since the Unix epoch
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
 impl Default for Cache {
     fn default() -> Self {
         Self::new().expect("Failed to create cache")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_round_trip_and_expiry() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::in_dir(dir.path()).unwrap();
+        let url = Url::parse("https://rdap.example/domain/example.com").unwrap();
+        let other = Url::parse("https://rdap.example/domain/example.net").unwrap();
+
+        let en = [("Accept-Language", "en")];
+
+        cache
+            .set_response(
+                &url,
+                &en,
+                r#"{"objectClassName": "domain"}"#,
+                Duration::from_mins(1),
+            )
+            .unwrap();
+        assert_eq!(
+            cache.get_response(&url, &en).as_deref(),
+            Some(r#"{"objectClassName": "domain"}"#)
+        );
+        assert!(cache.get_response(&other, &en).is_none());
+
+        // Requests with other headers don't share the entry
+        assert!(cache.get_response(&url, &[]).is_none());
+        assert!(
+            cache
+                .get_response(&url, &[("Accept-Language", "de")])
+                .is_none()
+        );
+
+        // Expired entries are removed on read
+        cache.set_response(&url, &[], "{}", Duration::ZERO).unwrap();
+        assert!(cache.get_response(&url, &[]).is_none());
+        assert!(!cache.response_path(&response_key(&url, &[])).exists());
+
+        cache
+            .set_response(&url, &[], "{}", Duration::from_mins(1))
+            .unwrap();
+        cache.clear().unwrap();
+        assert!(cache.get_response(&url, &[]).is_none());
+    }
+
+    #[test]
+    fn test_registry_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::in_dir(dir.path()).unwrap();
+        let url = "https://data.iana.org/rdap/dns.json";
+        assert!(cache.get_registry(url).is_none());
+
+        let entry = CachedRegistryFile {
+            url: url.to_string(),
+            fetched: unix_now(),
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            body: r#"{"services": []}"#.to_string(),
+        };
+        cache.set_registry(&entry).unwrap();
+        assert_eq!(cache.get_registry(url), Some(entry));
+        assert!(
+            cache
+                .get_registry("https://data.iana.org/rdap/asn.json")
+                .is_none()
+        );
+
+        // Cleared along with the responses
+        cache.clear().unwrap();
+        assert!(cache.get_registry(url).is_none());
+    }
+
+    #[test]
+    fn test_fnv1a_is_stable() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn test_prune_removes_oldest_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::in_dir(dir.path()).unwrap();
+        let data = [b'x'; 1000];
+
+        let base = SystemTime::now() - Duration::from_hours(1);
+        for i in 0..10u64 {
+            let key = format!("entry{i}.json");
+            cache.set(&key, &data).unwrap();
+            fs::File::options()
+                .write(true)
+                .open(dir.path().join(&key))
+                .unwrap()
+                .set_modified(base + Duration::from_secs(i))
+                .unwrap();
+        }
+        let url = Url::parse("https://rdap.example/domain/example.com").unwrap();
+        cache
+            .set_response(&url, &[], "{}", Duration::from_mins(1))
+            .unwrap();
+
+        let freed = cache.prune(5000).unwrap();
+        assert!(freed >= 5000);
+        let total: u64 = cache.entries().unwrap().iter().map(|(_, len, _)| len).sum();
+        assert!(total <= 5000);
+
+        // The newest entries survive
+        assert!(cache.get_response(&url, &[]).is_some());
+        assert!(cache.get("entry9.json").is_some());
+        assert!(cache.get("entry0.json").is_none());
+        assert_eq!(cache.prune(5000).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_writes_prune_past_max_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::in_dir(dir.path()).unwrap().with_max_size(2500);
+
+        for i in 0..10 {
+            cache.set(&format!("entry{i}.json"), &[b'x'; 1000]).unwrap();
+            let total: u64 = cache.entries().unwrap().iter().map(|(_, len, _)| len).sum();
+            assert!(total <= 2500);
+        }
+        assert!(cache.get("entry9.json").is_some());
+        // Writes under the limit only update the estimate
+        assert!(cache.estimated_bytes.load(Ordering::Relaxed) <= 2500);
+
+        // No temporary files are left behind
+        assert!(fs::read_dir(dir.path()).unwrap().all(|e| {
+            e.unwrap()
+                .path()
+                .extension()
+                .is_none_or(|ext| ext != TMP_EXTENSION)
+        }));
+    }
+
+    #[test]
+    fn test_first_write_measures_existing_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..5 {
+            fs::write(dir.path().join(format!("old{i}.json")), [b'x'; 1000]).unwrap();
+        }
+
+        let cache = Cache::in_dir(dir.path()).unwrap().with_max_size(2500);
+        cache.set("new.json", &[b'x'; 100]).unwrap();
+        let total: u64 = cache.entries().unwrap().iter().map(|(_, len, _)| len).sum();
+        assert!(total <= 2500);
+        assert_eq!(cache.estimated_bytes.load(Ordering::Relaxed), total);
+        assert!(cache.get("new.json").is_some());
+    }
+}
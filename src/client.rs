@@ -1,14 +1,34 @@
 //! RDAP client implementation
 
-use crate::bootstrap::BootstrapClient;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::bootstrap::BootstrapSnapshot;
+use crate::bootstrap::{BootstrapClient, BootstrapMap};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::cache::Cache;
+use crate::config::{self, WhoisServers};
+use crate::diff::{self, FieldDiff};
+#[cfg(feature = "cli")]
+use crate::display::{DisplayOptions, RdapDisplayWithQuery, Verbosity};
 use crate::error::{RdapError, Result};
 use crate::ip;
-use crate::models::{Domain, RdapObject};
+use crate::models::{Autnum, Domain, Entity, IpNetwork, Link, ParseOptions, RdapObject};
+#[cfg(feature = "cli")]
+use crate::report::Report;
 use crate::request::{QueryType, RdapRequest};
+use crate::span;
+use futures_util::stream::{self, StreamExt};
 use reqwest::Client;
+use std::collections::{HashMap, HashSet};
 use std::net::IpAddr;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+#[cfg(not(feature = "wasm"))]
+use std::time::Instant;
 use url::Url;
+#[cfg(feature = "wasm")]
+use web_time::Instant;
 
 /// RDAP query result with optional registrar data
 #[derive(Debug, Clone)]
@@ -23,36 +43,233 @@ pub struct RdapQueryResult {
     pub registrar_url: Option<Url>,
 }
 
+/// Response as the server sent it, for [`RdapClient::query_raw`]
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+    /// URL that answered, after redirects
+    pub url: Url,
+    /// HTTP status of the answer
+    pub status: reqwest::StatusCode,
+    /// Body bytes, undecoded
+    pub body: Vec<u8>,
+}
+
+impl RdapQueryResult {
+    /// Shareable URL of the displayed record
+    ///
+    /// Prefers the registrar answer over the registry one, and the object's
+    /// `self` link over the URL that was requested.
+    pub fn source_url(&self) -> &str {
+        if let (Some(registrar), Some(registrar_url)) = (&self.registrar, &self.registrar_url) {
+            return registrar.source_url().unwrap_or(registrar_url.as_str());
+        }
+        self.registry
+            .source_url()
+            .unwrap_or(self.registry_url.as_str())
+    }
+}
+
+/// Entity with its referenced networks and autnums fetched in full
+#[derive(Debug, Clone)]
+pub struct ExpandedEntity {
+    /// The entity itself
+    pub entity: Entity,
+    /// Full network objects (or the embedded summary if fetching failed)
+    pub networks: Vec<IpNetwork>,
+    /// Full autnum objects (or the embedded summary if fetching failed)
+    pub autnums: Vec<Autnum>,
+}
+
+/// Maximum number of referenced objects fetched by `expand_entity`
+pub const MAX_EXPANDED_OBJECTS: usize = 50;
+
+/// Number of referenced objects fetched concurrently by `expand_entity`
+const EXPAND_CONCURRENCY: usize = 4;
+
+/// HTTP protocol version selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HttpVersion {
+    /// Negotiate the version with the server (default)
+    #[default]
+    Auto,
+    /// Only use HTTP/1.1 (for servers with broken HTTP/2 support)
+    Http1Only,
+    /// Use HTTP/2 without negotiation
+    Http2PriorKnowledge,
+}
+
+/// Default cap on the number of candidate servers tried per query
+pub const DEFAULT_MAX_SERVERS: usize = 5;
+
+/// RDAP base URLs of the five Regional Internet Registries
+pub const RIR_RDAP_SERVERS: [&str; 5] = [
+    "https://rdap.arin.net/registry/",
+    "https://rdap.db.ripe.net/",
+    "https://rdap.apnic.net/",
+    "https://rdap.lacnic.net/rdap/",
+    "https://rdap.afrinic.net/rdap/",
+];
+
+/// Default `Accept` header: RDAP JSON, with plain JSON as a fallback
+pub const DEFAULT_ACCEPT: &str = "application/rdap+json, application/json";
+
+/// `Accept` header asking for the ICANN gTLD response profile, using the
+/// `extensions` media type parameter (see [`RdapClient::with_icann_profile`])
+pub const ICANN_PROFILE_ACCEPT: &str = "application/rdap+json;extensions=\"rdap_level_0 icann_rdap_response_profile_1 icann_rdap_technical_implementation_guide_1\", application/json";
+
+/// Most responses kept in the in-memory response cache
+const MAX_CACHED_RESPONSES: usize = 1024;
+
+/// Default cap on the number of redirects followed per request
+pub const DEFAULT_MAX_REDIRECTS: usize = 5;
+
+/// Default number of retries for rate-limited (429) and 5xx responses
+pub const DEFAULT_MAX_RETRIES: u32 = 0;
+
+/// Default delay before the first retry, doubled for each further attempt
+pub const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest `Retry-After` the client is willing to wait for
+const MAX_RETRY_AFTER: Duration = Duration::from_mins(1);
+
+/// Log target for raw HTTP exchange tracing (`RUST_LOG=rdap::http=trace`)
+pub const HTTP_LOG_TARGET: &str = "rdap::http";
+
+/// Maximum number of response body bytes written to the HTTP trace log
+const MAX_LOGGED_BODY: usize = 4096;
+
+/// Computes extra headers (e.g. an HMAC signature) for a request URL
+pub type RequestSigner =
+    dyn Fn(&Url) -> Vec<(reqwest::header::HeaderName, reqwest::header::HeaderValue)> + Send + Sync;
+
 /// RDAP client
 pub struct RdapClient {
     http_client: Client,
     bootstrap: BootstrapClient,
     timeout: Duration,
+    http_version: HttpVersion,
     follow_referral: bool,
+    follow_ip_referral: bool,
+    max_servers: usize,
+    max_redirects: usize,
+    /// Retries for transient (429, 5xx) responses
+    max_retries: u32,
+    /// Backoff before the first retry
+    retry_base_delay: Duration,
+    /// `Host` header values to send, keyed by URL host
+    host_overrides: HashMap<String, String>,
+    /// Request signer and the hosts it may sign for
+    signer: Option<(HashSet<String>, Arc<RequestSigner>)>,
+    /// Servers asked concurrently when IP bootstrap finds no delegation
+    rir_fallback: Option<Vec<Url>>,
+    /// How long successful responses are reused in memory (zero disables
+    /// the in-memory cache)
+    response_ttl: Duration,
+    /// Successful responses, keyed by request URL, with their expiry time
+    /// (at most [`MAX_CACHED_RESPONSES`])
+    responses: Mutex<HashMap<Url, (Instant, RdapObject)>>,
+    /// On-disk response cache shared across runs
+    #[cfg(not(target_arch = "wasm32"))]
+    response_cache: Option<Arc<Cache>>,
+    /// Neither read nor write any response cache
+    cache_bypass: bool,
+    /// `Accept` header value sent with every request
+    accept: String,
+    /// `Accept-Language` header value sent with every request
+    accept_language: Option<String>,
+    /// WHOIS hosts reported when a domain has no RDAP server
+    whois_servers: Option<WhoisServers>,
+    /// Never contact the bootstrap registries; requests need a server
+    bootstrap_disabled: bool,
+    /// Strictness and entity depth limit for parsing responses
+    parse_options: ParseOptions,
 }
 
 impl RdapClient {
     /// Create a new RDAP client
     pub fn new() -> Result<Self> {
-        let http_client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .user_agent(concat!("rdap-rust/", env!("CARGO_PKG_VERSION")))
-            .build()?;
+        let timeout = Duration::from_secs(30);
+        let http_version = HttpVersion::default();
+        let http_client = Self::build_http_client(timeout, http_version)?;
 
         let bootstrap = BootstrapClient::new()?;
+        let response_ttl = bootstrap.config().cache.response_ttl();
 
         Ok(Self {
             http_client,
             bootstrap,
-            timeout: Duration::from_secs(30),
+            timeout,
+            http_version,
             follow_referral: true, // Enable by default
+            follow_ip_referral: false,
+            max_servers: DEFAULT_MAX_SERVERS,
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+            host_overrides: HashMap::new(),
+            signer: None,
+            rir_fallback: None,
+            response_ttl,
+            responses: Mutex::new(HashMap::new()),
+            #[cfg(not(target_arch = "wasm32"))]
+            response_cache: None,
+            cache_bypass: false,
+            accept: DEFAULT_ACCEPT.to_string(),
+            accept_language: None,
+            whois_servers: None,
+            bootstrap_disabled: false,
+            parse_options: ParseOptions::default(),
         })
     }
 
+    /// Build the underlying HTTP client
+    #[cfg(not(target_arch = "wasm32"))]
+    fn build_http_client(timeout: Duration, http_version: HttpVersion) -> Result<Client> {
+        let builder = Client::builder()
+            .timeout(timeout)
+            .user_agent(concat!("rdap-rust/", env!("CARGO_PKG_VERSION")))
+            // Redirects are followed in `fetch_rdap`
+            .redirect(reqwest::redirect::Policy::none());
+
+        let builder = match http_version {
+            HttpVersion::Auto => builder,
+            HttpVersion::Http1Only => builder.http1_only(),
+            HttpVersion::Http2PriorKnowledge => builder.http2_prior_knowledge(),
+        };
+
+        Ok(builder.build()?)
+    }
+
+    /// Build the underlying HTTP client
+    ///
+    /// The browser's fetch API picks the protocol version and follows
+    /// redirects itself, and the timeout is applied per request instead.
+    #[cfg(target_arch = "wasm32")]
+    fn build_http_client(_timeout: Duration, _http_version: HttpVersion) -> Result<Client> {
+        Ok(Client::builder()
+            .user_agent(concat!("rdap-rust/", env!("CARGO_PKG_VERSION")))
+            .build()?)
+    }
+
+    /// Rebuild the HTTP client after a transport setting changed
+    fn rebuild_http_client(mut self) -> Self {
+        match Self::build_http_client(self.timeout, self.http_version) {
+            Ok(client) => self.http_client = client,
+            Err(e) => log::warn!("Failed to rebuild HTTP client: {e}"),
+        }
+        self
+    }
+
     /// Set timeout
-    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
-        self
+        self.rebuild_http_client()
+    }
+
+    /// Select the HTTP protocol version (default: automatic negotiation)
+    pub fn with_http_version(mut self, http_version: HttpVersion) -> Self {
+        self.http_version = http_version;
+        self.rebuild_http_client()
     }
 
     /// Enable or disable following registrar referrals (default: enabled)
@@ -61,6 +278,236 @@ impl RdapClient {
         self
     }
 
+    /// Follow an IP network's `related` link to the RIR managing transferred
+    /// (ERX) space, using that answer instead (default: disabled)
+    pub const fn with_follow_ip_referral(mut self, follow: bool) -> Self {
+        self.follow_ip_referral = follow;
+        self
+    }
+
+    /// Follow at most `max` HTTP redirects per request (default: 5)
+    pub const fn with_max_redirects(mut self, max: usize) -> Self {
+        self.max_redirects = max;
+        self
+    }
+
+    /// Retry 429, 500, 502, 503 and 504 responses up to `max_retries` times
+    ///
+    /// Waits `base_delay` before the first retry and doubles it for each
+    /// further one, unless the server sends a `Retry-After` header
+    /// (default: no retries).
+    pub const fn with_retry_policy(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_base_delay = base_delay;
+        self
+    }
+
+    /// Reuse successful responses in memory for `ttl`, or for the server's
+    /// `Cache-Control`/`Expires` lifetime when it sends one (default: the
+    /// configured `response_ttl_seconds`, otherwise zero, which disables
+    /// the in-memory cache)
+    pub const fn with_response_ttl(mut self, ttl: Duration) -> Self {
+        self.response_ttl = ttl;
+        self
+    }
+
+    /// Also cache parsed responses on disk, so they survive across runs
+    ///
+    /// Bootstrap registry files are kept in the same cache, as with
+    /// [`Self::with_bootstrap_cache`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_response_cache(mut self, cache: Cache) -> Self {
+        let cache = Arc::new(cache);
+        self.bootstrap = self.bootstrap.with_cache(Arc::clone(&cache));
+        self.response_cache = Some(cache);
+        self
+    }
+
+    /// Keep bootstrap registry files on disk, so later runs reuse them or
+    /// revalidate them cheaply instead of downloading them again
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_bootstrap_cache(mut self, cache: Cache) -> Self {
+        self.bootstrap = self.bootstrap.with_cache(Arc::new(cache));
+        self
+    }
+
+    /// Send `accept` as the `Accept` header instead of [`DEFAULT_ACCEPT`]
+    pub fn with_accept(mut self, accept: impl Into<String>) -> Self {
+        self.accept = accept.into();
+        self
+    }
+
+    /// Ask for the ICANN gTLD response profile ([`ICANN_PROFILE_ACCEPT`])
+    ///
+    /// Some gTLD servers tailor redaction and field content to the profile
+    /// a client signals; others ignore the media type parameters.
+    pub fn with_icann_profile(self) -> Self {
+        self.with_accept(ICANN_PROFILE_ACCEPT)
+    }
+
+    /// Ask servers for answers (and notices) in `lang`, e.g. `de` or `en-US`
+    pub fn with_accept_language(mut self, lang: impl Into<String>) -> Self {
+        self.accept_language = Some(lang.into());
+        self
+    }
+
+    /// Skip every response cache, always asking the server (default: disabled)
+    pub const fn with_cache_bypass(mut self, bypass: bool) -> Self {
+        self.cache_bypass = bypass;
+        self
+    }
+
+    /// Limit how many candidate servers a single query will try (default: 5)
+    pub const fn with_max_servers(mut self, max: usize) -> Self {
+        self.max_servers = max;
+        self
+    }
+
+    /// Send `Host: actual_host` for requests whose URL host is `url_host`
+    ///
+    /// The connection still goes to the address `url_host` resolves to;
+    /// only the HTTP `Host` header changes (e.g. for mirrors behind a CDN).
+    pub fn with_host_override(
+        mut self,
+        url_host: impl Into<String>,
+        actual_host: impl Into<String>,
+    ) -> Self {
+        self.host_overrides
+            .insert(url_host.into().to_lowercase(), actual_host.into());
+        self
+    }
+
+    /// Add headers computed by `signer` to every request sent to `hosts`
+    ///
+    /// Requests to any other host (such as registrar referral targets) are
+    /// never signed, so signatures don't leak outside the gateway.
+    pub fn with_request_signer<F>(
+        mut self,
+        hosts: impl IntoIterator<Item = impl Into<String>>,
+        signer: F,
+    ) -> Self
+    where
+        F: Fn(&Url) -> Vec<(reqwest::header::HeaderName, reqwest::header::HeaderValue)>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let hosts = hosts.into_iter().map(|h| h.into().to_lowercase()).collect();
+        self.signer = Some((hosts, Arc::new(signer)));
+        self
+    }
+
+    /// Query all five RIRs when IP bootstrap yields no delegation (default: disabled)
+    ///
+    /// Useful for legacy address space the IANA registry doesn't delegate
+    /// cleanly; the first non-404 answer wins.
+    pub fn with_rir_fallback(mut self, enabled: bool) -> Self {
+        self.rir_fallback = enabled.then(|| {
+            RIR_RDAP_SERVERS
+                .iter()
+                .filter_map(|s| Url::parse(s).ok())
+                .collect()
+        });
+        self
+    }
+
+    /// Use `servers` instead of the RIRs for the IP bootstrap fallback
+    pub fn with_rir_fallback_servers(mut self, servers: Vec<Url>) -> Self {
+        self.rir_fallback = Some(servers);
+        self
+    }
+
+    /// Report a domain's WHOIS host from `servers` when it has no RDAP server
+    ///
+    /// Domain and nameserver queries that bootstrap can't place then fail
+    /// with [`RdapError::OnlyWhoisAvailable`] if the TLD is listed (see
+    /// [`crate::config::load_whois_servers`]).
+    pub fn with_whois_fallback(mut self, servers: WhoisServers) -> Self {
+        self.whois_servers = Some(servers);
+        self
+    }
+
+    /// Reject non-conformant responses instead of filling in what's missing
+    /// (default: disabled)
+    ///
+    /// Responses are parsed with [`RdapObject::from_json_strict`], and error
+    /// bodies without an `errorCode` are rejected too; both fail with
+    /// [`RdapError::NonConformant`]. Meant for validating a server's output.
+    pub const fn with_strict_parsing(mut self, strict: bool) -> Self {
+        self.parse_options.strict = strict;
+        self
+    }
+
+    /// Keep at most `depth` levels of nested entities in parsed responses,
+    /// cutting deeper ones off with a remark (default:
+    /// [`DEFAULT_MAX_ENTITY_DEPTH`](crate::models::DEFAULT_MAX_ENTITY_DEPTH))
+    pub const fn with_max_entity_depth(mut self, depth: usize) -> Self {
+        self.parse_options.max_entity_depth = depth;
+        self
+    }
+
+    /// Never contact the IANA bootstrap registries (default: enabled)
+    ///
+    /// Requests without an explicit server (other than [`QueryType::Url`])
+    /// then fail instead of being looked up, e.g. where policy only allows
+    /// an internal RDAP proxy. Registrar referrals are still followed unless
+    /// disabled with [`Self::with_follow_referral`].
+    pub const fn with_bootstrap_disabled(mut self, disabled: bool) -> Self {
+        self.bootstrap_disabled = disabled;
+        self
+    }
+
+    /// Resolve servers from a pre-built delegation map instead of IANA bootstrap
+    pub fn with_bootstrap_map(mut self, map: BootstrapMap) -> Self {
+        self.bootstrap = self.bootstrap.with_map(map);
+        self
+    }
+
+    /// Resolve servers from a snapshot written by
+    /// [`export_bootstrap_snapshot`](Self::export_bootstrap_snapshot)
+    ///
+    /// The snapshot's object tags registry is loaded too, so exporting
+    /// again needs no network access.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_bootstrap_snapshot(self, path: impl AsRef<Path>) -> Result<Self> {
+        let snapshot = BootstrapSnapshot::from_file(path)?;
+        log::debug!("Using bootstrap snapshot from {}", snapshot.created);
+        let mut client = self.with_bootstrap_map(snapshot.map);
+        client.bootstrap = client.bootstrap.with_object_tags(snapshot.object_tags);
+        Ok(client)
+    }
+
+    /// Fetch every bootstrap registry (plus object tags) into one dated JSON
+    /// snapshot at `path`, returning the file written
+    ///
+    /// If `path` is a directory, the file is named after the fetch time,
+    /// e.g. `bootstrap-20261015T120000Z.json`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn export_bootstrap_snapshot(&self, path: impl AsRef<Path>) -> Result<PathBuf> {
+        self.check_bootstrap_enabled()?;
+        let snapshot = self.bootstrap.snapshot().await?;
+        let path = path.as_ref();
+        let path = if path.is_dir() {
+            path.join(format!(
+                "bootstrap-{}.json",
+                snapshot.created.replace(['-', ':'], "")
+            ))
+        } else {
+            path.to_path_buf()
+        };
+        snapshot.write_to(&path)?;
+        Ok(path)
+    }
+
+    /// Fetch all bootstrap registry files up front
+    ///
+    /// Useful before a large batch of mixed queries, so each query type
+    /// doesn't pay the bootstrap latency on first use.
+    pub async fn prefetch_bootstrap(&self) -> Result<()> {
+        self.check_bootstrap_enabled()?;
+        self.bootstrap.prefetch().await
+    }
+
     /// Execute an RDAP request (simple query, returns single object)
     pub async fn query(&self, request: &RdapRequest) -> Result<RdapObject> {
         let result = self.query_with_referral(request).await?;
@@ -68,31 +515,468 @@ impl RdapClient {
         Ok(result.registrar.unwrap_or(result.registry))
     }
 
+    /// Query an object and write it to `w` as the CLI's text output does
+    ///
+    /// Contacts relevant to the query (such as the abuse contact) come first.
+    /// Returns the written object, which is the registrar's answer when a
+    /// referral was followed.
+    #[cfg(feature = "cli")]
+    pub async fn query_and_display(
+        &self,
+        request: &RdapRequest,
+        w: &mut (dyn std::io::Write + Send),
+        verbose: bool,
+    ) -> Result<RdapObject> {
+        let obj = self.query(request).await?;
+        let opts = DisplayOptions {
+            lang: self.accept_language.clone(),
+            ..DisplayOptions::at(Verbosity::from(verbose))
+        };
+        obj.write_for_query(w, &request.query, &opts)?;
+        Ok(obj)
+    }
+
+    /// Check whether the requested object exists, without parsing its body
+    ///
+    /// Returns `false` when the server answers 404 and propagates any other
+    /// failure. Registrar referrals are not followed.
+    pub async fn exists(&self, request: &RdapRequest) -> Result<bool> {
+        span::in_query(&request.query, self.exists_on_servers(request)).await
+    }
+
+    async fn exists_on_servers(&self, request: &RdapRequest) -> Result<bool> {
+        let request = &request.normalized()?;
+
+        let urls = span::in_stage("bootstrap", self.server_urls(request)).await?;
+        if urls.is_empty() {
+            return Err(self.no_servers_error(request));
+        }
+
+        let mut last_error = None;
+        for base_url in urls.iter().take(self.max_servers) {
+            let url = request.build_url(base_url)?;
+            match span::in_stage(&stage_name("server", &url), self.check_exists(&url)).await {
+                Ok(found) => return Ok(found),
+                Err(e) => {
+                    log::warn!("Server {url} failed: {e}");
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or(RdapError::NoWorkingServers))
+    }
+
+    /// Fetch the response for a request exactly as the server sent it
+    ///
+    /// The body is returned as bytes along with the status and the URL that
+    /// answered after redirects. Error statuses are not turned into
+    /// [`RdapError`]s, so their bodies are kept too. The response cache is
+    /// not used and referrals are not followed, so this is the registry's
+    /// own answer, e.g. as evidence for a bug report.
+    ///
+    /// Other servers are tried when one fails; if all of them answer with
+    /// an error status, the last answer is returned.
+    pub async fn query_raw(&self, request: &RdapRequest) -> Result<RawResponse> {
+        span::in_query(&request.query, self.fetch_raw_from_servers(request)).await
+    }
+
+    async fn fetch_raw_from_servers(&self, request: &RdapRequest) -> Result<RawResponse> {
+        let request = &request.normalized()?;
+        let urls = span::in_stage("bootstrap", self.server_urls(request)).await?;
+        if urls.is_empty() {
+            return Err(self.no_servers_error(request));
+        }
+
+        let mut last_error = None;
+        let mut last_answer = None;
+        for base_url in urls.iter().take(self.max_servers) {
+            let url = request.build_url(base_url)?;
+            match span::in_stage(&stage_name("server", &url), self.fetch_raw(&url)).await {
+                Ok(raw)
+                    if raw.status.is_success() || raw.status == reqwest::StatusCode::NOT_FOUND =>
+                {
+                    return Ok(raw);
+                }
+                Ok(raw) => {
+                    log::warn!("Server {url} failed: HTTP {}", raw.status);
+                    last_answer = Some(raw);
+                }
+                Err(e) => {
+                    log::warn!("Server {url} failed: {e}");
+                    last_error = Some(e);
+                }
+            }
+        }
+        last_answer.ok_or_else(|| last_error.unwrap_or(RdapError::NoWorkingServers))
+    }
+
+    /// Fetch the final response for `url` without decoding or checking it
+    async fn fetch_raw(&self, url: &Url) -> Result<RawResponse> {
+        let (final_url, response) = self.request_with_retry(url).await?;
+        let status = response.status();
+        log::trace!(target: HTTP_LOG_TARGET, "< {status} {final_url}");
+        let body = response.bytes().await?.to_vec();
+        log::trace!(
+            target: HTTP_LOG_TARGET,
+            "< body: {}",
+            truncate_body(&String::from_utf8_lossy(&body))
+        );
+        Ok(RawResponse {
+            url: final_url,
+            status,
+            body,
+        })
+    }
+
+    /// Fetch a request's raw body and parse it into a [`Report`]
+    ///
+    /// Like [`Self::query_raw`], this is the registry's answer from the
+    /// network, so the report's display and JSON describe the same response.
+    #[cfg(feature = "cli")]
+    pub async fn query_report(&self, request: &RdapRequest) -> Result<Report> {
+        let raw = self.query_raw(request).await?;
+        let body = String::from_utf8(raw.body)
+            .map_err(|e| RdapError::Other(format!("Response body is not UTF-8: {e}")))?;
+        if raw.status == reqwest::StatusCode::NOT_FOUND {
+            return Err(RdapError::NotFound);
+        }
+        if !raw.status.is_success() {
+            return Err(status_error(raw.status, &body));
+        }
+        let object = self.parse(&body)?;
+        Ok(Report {
+            query: request.query.clone(),
+            url: raw.url,
+            object,
+            body,
+        })
+    }
+
+    /// Existence check against a single URL; the body is only read on errors
+    async fn check_exists(&self, url: &Url) -> Result<bool> {
+        if self.cached_response(url).is_some() {
+            return Ok(true);
+        }
+
+        let (final_url, response) = self.request_with_retry(url).await?;
+        let status = response.status();
+        log::trace!(target: HTTP_LOG_TARGET, "< {status} {final_url}");
+
+        if status.is_success() {
+            let is_json = response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .is_none_or(is_json_media_type);
+            if final_url.host_str() != url.host_str() && !is_json {
+                return Err(login_redirect_error(&final_url));
+            }
+            Ok(true)
+        } else if status.as_u16() == 404 {
+            Ok(false)
+        } else {
+            let text = response.text().await?;
+            Err(status_error(status, &text))
+        }
+    }
+
     /// Execute an RDAP request with registrar referral support
+    ///
+    /// Runs in an `rdap_query` `tracing` span with a correlation id, so its
+    /// log lines can be told apart from those of concurrent queries.
     pub async fn query_with_referral(&self, request: &RdapRequest) -> Result<RdapQueryResult> {
+        span::in_query(&request.query, self.query_with_cidr_fallback(request)).await
+    }
+
+    async fn query_with_cidr_fallback(&self, request: &RdapRequest) -> Result<RdapQueryResult> {
+        // URLs already requested during this query, so no server is asked twice
+        let mut visited = HashSet::new();
+
         // Try the original query first
-        match self.query_servers(request).await {
+        let error = match self.query_servers(request, &mut visited).await {
             Ok(result) => return Ok(result),
-            Err(ref e) if self.should_retry_with_cidr(request, e) => {
-                // For IPv6 host queries that get 400, retry with CIDR prefixes
-                // Some RDAP servers (e.g., TWNIC) don't support host-level IPv6 queries
-                for prefix_len in &[64u8, 48, 32] {
-                    if let Some(cidr_query) = self.make_cidr_query(request, *prefix_len) {
-                        log::info!(
-                            "Retrying with CIDR prefix /{prefix_len}: {}",
-                            cidr_query.query
-                        );
-                        if let Ok(result) = self.query_servers(&cidr_query).await {
-                            return Ok(result);
-                        }
+            Err(e) => e,
+        };
+
+        if self.should_retry_with_cidr(request, &error) {
+            // For IPv6 host queries that get 400, retry with CIDR prefixes
+            // Some RDAP servers (e.g., TWNIC) don't support host-level IPv6 queries
+            for prefix_len in &[64u8, 48, 32] {
+                if let Some(cidr_query) = self.make_cidr_query(request, *prefix_len) {
+                    log::info!(
+                        "Retrying with CIDR prefix /{prefix_len}: {}",
+                        cidr_query.query
+                    );
+                    if let Ok(result) = self.query_servers(&cidr_query, &mut visited).await {
+                        return Ok(result);
                     }
                 }
             }
-            Err(_) => {}
         }
 
         // All attempts failed, return the original error
-        self.query_servers(request).await
+        Err(error)
+    }
+
+    /// Run many requests with at most `concurrency` in flight at once
+    ///
+    /// Results are returned in request order; a failed request doesn't stop
+    /// the others. Requests sharing a registry share one bootstrap fetch.
+    pub async fn query_batch(
+        &self,
+        requests: &[RdapRequest],
+        concurrency: usize,
+    ) -> Vec<(RdapRequest, Result<RdapObject>)> {
+        self.query_batch_with_progress(requests, concurrency, |_, _| {})
+            .await
+    }
+
+    /// Like [`query_batch`](Self::query_batch), reporting progress as
+    /// requests finish
+    ///
+    /// `on_progress(done, total)` is called once per request.
+    pub async fn query_batch_with_progress<F>(
+        &self,
+        requests: &[RdapRequest],
+        concurrency: usize,
+        mut on_progress: F,
+    ) -> Vec<(RdapRequest, Result<RdapObject>)>
+    where
+        F: FnMut(usize, usize),
+    {
+        let total = requests.len();
+        let mut done = 0;
+        stream::iter(requests)
+            .map(|request| async move { (request.clone(), self.query(request).await) })
+            .buffered(concurrency.max(1))
+            .inspect(|_| {
+                done += 1;
+                on_progress(done, total);
+            })
+            .collect()
+            .await
+    }
+
+    /// Like [`query_batch`](Self::query_batch), keeping registry and
+    /// registrar answers apart as [`query_with_referral`](Self::query_with_referral) does
+    pub async fn query_batch_with_referral(
+        &self,
+        requests: &[RdapRequest],
+        concurrency: usize,
+    ) -> Vec<(RdapRequest, Result<RdapQueryResult>)> {
+        self.query_batch_with_referral_and_progress(requests, concurrency, |_, _| {})
+            .await
+    }
+
+    /// Like [`query_batch_with_referral`](Self::query_batch_with_referral),
+    /// calling `on_progress(done, total)` once per finished request
+    pub async fn query_batch_with_referral_and_progress<F>(
+        &self,
+        requests: &[RdapRequest],
+        concurrency: usize,
+        mut on_progress: F,
+    ) -> Vec<(RdapRequest, Result<RdapQueryResult>)>
+    where
+        F: FnMut(usize, usize),
+    {
+        let total = requests.len();
+        let mut done = 0;
+        stream::iter(requests)
+            .map(
+                |request| async move { (request.clone(), self.query_with_referral(request).await) },
+            )
+            .buffered(concurrency.max(1))
+            .inspect(|_| {
+                done += 1;
+                on_progress(done, total);
+            })
+            .collect()
+            .await
+    }
+
+    /// Query the same object from two servers and compare the answers
+    ///
+    /// Registrar referrals are not followed; each server's own answer is used.
+    pub async fn compare_servers(
+        &self,
+        request: &RdapRequest,
+        left: &Url,
+        right: &Url,
+    ) -> Result<Vec<FieldDiff>> {
+        let request = request.normalized()?;
+        let left_url = request.build_url(left)?;
+        let right_url = request.build_url(right)?;
+        let (left, right) =
+            tokio::try_join!(self.fetch_rdap(&left_url), self.fetch_rdap(&right_url))?;
+        Ok(diff::diff_json(
+            &serde_json::to_value(&left)?,
+            &serde_json::to_value(&right)?,
+        ))
+    }
+
+    /// Look up an address at every RIR and follow each answer up its
+    /// delegation chain, returning every distinct network found
+    ///
+    /// Bootstrap stops at the one RIR IANA delegates to; this collects each
+    /// RIR's view of transferred space instead. The RIRs (or the servers
+    /// given to [`Self::with_rir_fallback_servers`]) are asked concurrently,
+    /// then `up` links and `related` RDAP links to other networks are
+    /// followed, at most [`MAX_EXPANDED_OBJECTS`] fetches in all.
+    ///
+    /// Networks are de-duplicated by their `self` link and listed broadest
+    /// first, each with the URL it was fetched from. Fails with
+    /// [`RdapError::NotFound`] if no server has a network for the address.
+    pub async fn query_ip_all_rirs(&self, addr: IpAddr) -> Result<Vec<(Url, IpNetwork)>> {
+        span::in_query(&addr.to_string(), self.collect_ip_networks(addr)).await
+    }
+
+    async fn collect_ip_networks(&self, addr: IpAddr) -> Result<Vec<(Url, IpNetwork)>> {
+        let request = RdapRequest::new(QueryType::Ip, addr.to_string());
+        let servers = self.rir_fallback.clone().unwrap_or_else(|| {
+            RIR_RDAP_SERVERS
+                .iter()
+                .filter_map(|s| Url::parse(s).ok())
+                .collect()
+        });
+
+        // URLs requested so far, and those still to fetch
+        let mut visited = HashSet::new();
+        let mut pending = Vec::new();
+        for base_url in &servers {
+            let url = request.build_url(base_url)?;
+            if visited.insert(url.clone()) {
+                pending.push(url);
+            }
+        }
+
+        let mut networks = Vec::new();
+        let mut seen = HashSet::new();
+        let mut fetched = 0;
+        let mut not_found = false;
+        let mut last_error = None;
+        while !pending.is_empty() && fetched < MAX_EXPANDED_OBJECTS {
+            let round: Vec<Url> = pending
+                .drain(..pending.len().min(MAX_EXPANDED_OBJECTS - fetched))
+                .collect();
+            fetched += round.len();
+            let results: Vec<_> = stream::iter(round)
+                .map(|url| async move {
+                    let fetch = self.fetch_rdap(&url);
+                    let result = span::in_stage(&stage_name("server", &url), fetch).await;
+                    (url, result)
+                })
+                .buffered(RIR_RDAP_SERVERS.len())
+                .collect()
+                .await;
+
+            for (url, result) in results {
+                let obj = match result {
+                    Ok(obj) => obj,
+                    Err(RdapError::NotFound) => {
+                        not_found = true;
+                        continue;
+                    }
+                    Err(e) => {
+                        log::warn!("Server {url} failed: {e}");
+                        last_error = Some(e);
+                        continue;
+                    }
+                };
+                let identity = obj.self_link().unwrap_or_else(|| url.clone());
+                let RdapObject::IpNetwork(network) = obj else {
+                    log::debug!("Ignoring non-network answer from {url}");
+                    continue;
+                };
+                if !seen.insert(identity) {
+                    continue;
+                }
+                for link in Self::delegation_links(&network) {
+                    if visited.insert(link.clone()) {
+                        log::debug!("Following delegation link: {link}");
+                        pending.push(link);
+                    }
+                }
+                networks.push((url, network));
+            }
+        }
+
+        if networks.is_empty() {
+            return Err(if not_found {
+                RdapError::NotFound
+            } else {
+                last_error.unwrap_or(RdapError::NoWorkingServers)
+            });
+        }
+        networks.sort_by_key(|(_, network)| std::cmp::Reverse(network.address_count()));
+        Ok(networks)
+    }
+
+    /// `up` links and `related` RDAP links of a network, towards the rest of
+    /// its delegation chain
+    fn delegation_links(network: &IpNetwork) -> impl Iterator<Item = Url> + '_ {
+        network
+            .links
+            .iter()
+            .filter(|link| match link.rel.as_deref() {
+                Some("up") => true,
+                Some("related") => {
+                    link.link_type
+                        .as_deref()
+                        .is_some_and(|t| t.contains("rdap") || t.contains("json"))
+                        || link.href.contains("/ip/")
+                }
+                _ => false,
+            })
+            .filter_map(Link::url)
+    }
+
+    /// Fetch the full objects behind an entity's `networks` and `autnums`
+    ///
+    /// Each summary is resolved through its `self` link, at most
+    /// `MAX_EXPANDED_OBJECTS` in total. Summaries without a usable link or
+    /// whose fetch fails are kept as-is.
+    pub async fn expand_entity(&self, entity: &Entity) -> Result<ExpandedEntity> {
+        let networks = stream::iter(entity.networks.iter().take(MAX_EXPANDED_OBJECTS))
+            .map(|net| async move {
+                match self.fetch_self_link(&net.links).await {
+                    Some(RdapObject::IpNetwork(full)) => full,
+                    _ => net.clone(),
+                }
+            })
+            .buffered(EXPAND_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await;
+
+        let remaining = MAX_EXPANDED_OBJECTS.saturating_sub(networks.len());
+        let autnums = stream::iter(entity.autnums.iter().take(remaining))
+            .map(|asn| async move {
+                match self.fetch_self_link(&asn.links).await {
+                    Some(RdapObject::Autnum(full)) => full,
+                    _ => asn.clone(),
+                }
+            })
+            .buffered(EXPAND_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await;
+
+        Ok(ExpandedEntity {
+            entity: entity.clone(),
+            networks,
+            autnums,
+        })
+    }
+
+    /// Fetch the object behind a `self` link, if there is one
+    async fn fetch_self_link(&self, links: &[Link]) -> Option<RdapObject> {
+        let link = links.iter().find(|l| l.rel.as_deref() == Some("self"))?;
+        let url = Url::parse(&link.href).ok()?;
+        match self.fetch_rdap(&url).await {
+            Ok(obj) => Some(obj),
+            Err(e) => {
+                log::warn!("Failed to expand {url}: {e}");
+                None
+            }
+        }
     }
 
     /// Check if we should retry an IPv6 query with CIDR notation
@@ -126,51 +1010,210 @@ impl RdapClient {
         }
     }
 
+    /// Candidate servers for a request: the explicit server, the URL itself
+    /// for [`QueryType::Url`], or the bootstrap registry's answer
+    async fn server_urls(&self, request: &RdapRequest) -> Result<Vec<Url>> {
+        if let Some(server) = &request.server {
+            Ok(vec![server.clone()])
+        } else if request.query_type == QueryType::Url {
+            Ok(vec![Url::parse(&request.query)?])
+        } else {
+            self.check_bootstrap_enabled()?;
+            self.bootstrap.lookup(request).await
+        }
+    }
+
+    fn check_bootstrap_enabled(&self) -> Result<()> {
+        if self.bootstrap_disabled {
+            return Err(RdapError::Bootstrap(
+                "Bootstrap is disabled; set an RDAP server for the request".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Error for a request bootstrap found no server for, naming the WHOIS
+    /// host instead when one is known
+    fn no_servers_error(&self, request: &RdapRequest) -> RdapError {
+        if matches!(
+            request.query_type,
+            QueryType::Domain | QueryType::Nameserver
+        ) && let Some(host) = self
+            .whois_servers
+            .as_ref()
+            .and_then(|servers| config::lookup_whois_server(servers, &request.query))
+        {
+            return RdapError::OnlyWhoisAvailable {
+                host: host.to_string(),
+            };
+        }
+        RdapError::Bootstrap("No RDAP servers found".to_string())
+    }
+
     /// Try querying all available servers for a request
-    async fn query_servers(&self, request: &RdapRequest) -> Result<RdapQueryResult> {
+    async fn query_servers(
+        &self,
+        request: &RdapRequest,
+        visited: &mut HashSet<Url>,
+    ) -> Result<RdapQueryResult> {
+        let request = &request.normalized()?;
+
         // Determine RDAP servers
-        let urls = if let Some(server) = &request.server {
-            vec![server.clone()]
-        } else {
-            self.bootstrap.lookup(request).await?
-        };
+        let urls = span::in_stage("bootstrap", self.server_urls(request)).await?;
 
         if urls.is_empty() {
-            return Err(RdapError::Bootstrap("No RDAP servers found".to_string()));
+            if request.query_type == QueryType::Ip
+                && let Some(servers) = &self.rir_fallback
+            {
+                log::info!("No IP delegation found, asking all RIRs");
+                return self.query_any(request, servers, visited).await;
+            }
+            return Err(self.no_servers_error(request));
         }
 
-        // Try each server
-        let mut last_error = None;
+        self.query_urls(request, &urls, visited).await
+    }
 
-        for base_url in &urls {
+    /// Query all `servers` concurrently and return the first successful answer
+    ///
+    /// Fails with [`RdapError::NotFound`] if any server answered 404 and none
+    /// had the object.
+    async fn query_any(
+        &self,
+        request: &RdapRequest,
+        servers: &[Url],
+        visited: &mut HashSet<Url>,
+    ) -> Result<RdapQueryResult> {
+        let mut urls = Vec::new();
+        for base_url in servers {
             let url = request.build_url(base_url)?;
+            if visited.insert(url.clone()) {
+                urls.push(url);
+            }
+        }
 
-            log::debug!("Querying RDAP server: {url}");
+        let mut pending: stream::FuturesUnordered<_> = urls
+            .into_iter()
+            .map(|url| async move {
+                let result = self.fetch_rdap(&url).await;
+                (url, result)
+            })
+            .collect();
 
-            match self.fetch_rdap(&url).await {
+        let mut not_found = false;
+        let mut last_error = None;
+        while let Some((url, result)) = pending.next().await {
+            match result {
                 Ok(obj) => {
-                    // For domain queries (not TLD), try to follow registrar referral
-                    if self.follow_referral
-                        && request.query_type == QueryType::Domain
-                        && let RdapObject::Domain(ref domain) = obj
-                        && let Some(registrar_rdap_url) = self.extract_registrar_rdap_url(domain)
-                    {
-                        // Skip if referral points to the same server (same host)
-                        if Self::is_same_server(&url, &registrar_rdap_url) {
-                            log::debug!(
-                                "Skipping referral: same server as registry ({})",
-                                registrar_rdap_url.host_str().unwrap_or("unknown")
-                            );
-                            return Ok(RdapQueryResult {
-                                registry: obj,
-                                registry_url: url,
-                                registrar: None,
+                    return Ok(RdapQueryResult {
+                        registry: obj,
+                        registry_url: url,
+                        registrar: None,
+                        registrar_url: None,
+                    });
+                }
+                Err(RdapError::NotFound) => not_found = true,
+                Err(e) => {
+                    log::warn!("Server {url} failed: {e}");
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        if not_found {
+            return Err(RdapError::NotFound);
+        }
+        Err(last_error.unwrap_or(RdapError::NoWorkingServers))
+    }
+
+    /// Try querying the given candidate servers in order, up to `max_servers`
+    ///
+    /// URLs already in `visited` (duplicate candidates or referrals back to a
+    /// server tried earlier in the same query) are skipped.
+    async fn query_urls(
+        &self,
+        request: &RdapRequest,
+        urls: &[Url],
+        visited: &mut HashSet<Url>,
+    ) -> Result<RdapQueryResult> {
+        let truncated = urls.len() > self.max_servers;
+        if truncated {
+            log::debug!(
+                "Limiting query to {} of {} candidate servers",
+                self.max_servers,
+                urls.len()
+            );
+        }
+
+        // Try each server
+        let mut last_error = None;
+
+        for base_url in urls.iter().take(self.max_servers) {
+            let url = request.build_url(base_url)?;
+
+            if !visited.insert(url.clone()) {
+                log::debug!("Skipping already visited URL: {url}");
+                continue;
+            }
+
+            log::debug!("Querying RDAP server: {url}");
+
+            match span::in_stage(&stage_name("server", &url), self.fetch_rdap(&url)).await {
+                Ok(obj) => {
+                    // For IP queries, chase transferred space to the managing RIR
+                    if self.follow_ip_referral
+                        && request.query_type == QueryType::Ip
+                        && let RdapObject::IpNetwork(ref network) = obj
+                        && let Some(referral_url) = Self::extract_ip_referral_url(network)
+                        && !Self::is_same_server(&url, &referral_url)
+                        && visited.insert(referral_url.clone())
+                    {
+                        log::debug!("Following IP referral to managing RIR: {referral_url}");
+                        let referral = self.fetch_rdap(&referral_url);
+                        match span::in_stage(&stage_name("referral", &referral_url), referral).await
+                        {
+                            Ok(authoritative) => {
+                                return Ok(RdapQueryResult {
+                                    registry: authoritative,
+                                    registry_url: referral_url,
+                                    registrar: None,
+                                    registrar_url: None,
+                                });
+                            }
+                            Err(e) => log::warn!("Failed to fetch referred IP data: {e}"),
+                        }
+                    }
+
+                    // For domain queries (not TLD), try to follow registrar referral
+                    if self.follow_referral
+                        && request.query_type == QueryType::Domain
+                        && let RdapObject::Domain(ref domain) = obj
+                        && let Some(registrar_rdap_url) = self.extract_registrar_rdap_url(domain)
+                    {
+                        // Skip if referral points to the same server (same host)
+                        // or to a URL already requested during this query
+                        if Self::is_same_server(&url, &registrar_rdap_url)
+                            || !visited.insert(registrar_rdap_url.clone())
+                        {
+                            log::debug!(
+                                "Skipping referral: same server as registry or already visited ({registrar_rdap_url})"
+                            );
+                            return Ok(RdapQueryResult {
+                                registry: obj,
+                                registry_url: url,
+                                registrar: None,
                                 registrar_url: None,
                             });
                         }
 
                         log::debug!("Following registrar referral: {registrar_rdap_url}");
-                        match self.fetch_rdap(&registrar_rdap_url).await {
+                        let registrar = self.fetch_rdap(&registrar_rdap_url);
+                        match span::in_stage(
+                            &stage_name("registrar", &registrar_rdap_url),
+                            registrar,
+                        )
+                        .await
+                        {
                             Ok(registrar_obj) => {
                                 return Ok(RdapQueryResult {
                                     registry: obj,
@@ -200,6 +1243,9 @@ impl RdapClient {
             }
         }
 
+        if truncated {
+            return Err(RdapError::NoWorkingServers);
+        }
         Err(last_error.unwrap_or(RdapError::NoWorkingServers))
     }
 
@@ -208,6 +1254,21 @@ impl RdapClient {
         url1.host() == url2.host()
     }
 
+    /// Extract the RDAP URL of the RIR managing a transferred network
+    fn extract_ip_referral_url(network: &IpNetwork) -> Option<Url> {
+        network
+            .links
+            .iter()
+            .filter(|link| link.rel.as_deref() == Some("related"))
+            .find(|link| {
+                link.link_type
+                    .as_deref()
+                    .is_some_and(|t| t.contains("rdap") || t.contains("json"))
+                    || link.href.contains("/ip/")
+            })
+            .and_then(|link| Url::parse(&link.href).ok())
+    }
+
     /// Extract registrar RDAP URL from domain response
     fn extract_registrar_rdap_url(&self, domain: &Domain) -> Option<Url> {
         // Look for a link with rel="related" and type containing "rdap"
@@ -252,85 +1313,469 @@ impl RdapClient {
         None
     }
 
-    /// Fetch RDAP response from URL
+    /// Fetch RDAP response from URL (reusing a cached answer until it expires)
+    // Without the disk cache on wasm, the body goes unused
+    #[cfg_attr(
+        target_arch = "wasm32",
+        allow(unused_variables, clippy::collapsible_if)
+    )]
     pub async fn fetch_rdap(&self, url: &Url) -> Result<RdapObject> {
-        let response = self
-            .http_client
-            .get(url.as_str())
-            .header("Accept", "application/rdap+json, application/json")
-            .send()
-            .await?;
+        if let Some(obj) = self.cached_response(url) {
+            log::debug!("Using cached response: {url}");
+            return Ok(obj);
+        }
+
+        let (obj, text, ttl) = self.fetch_rdap_uncached(url).await?;
+        if !self.cache_bypass && !ttl.is_zero() {
+            if !self.response_ttl.is_zero()
+                && let Ok(mut responses) = self.responses.lock()
+            {
+                if responses.len() >= MAX_CACHED_RESPONSES {
+                    evict_responses(&mut responses);
+                }
+                responses.insert(url.clone(), (Instant::now() + ttl, obj.clone()));
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some((cache, headers)) = self.disk_cache_for(url)
+                && let Err(e) = cache.set_response(url, &headers, &text, ttl)
+            {
+                log::warn!("Failed to cache response for {url}: {e}");
+            }
+        }
+        Ok(obj)
+    }
+
+    /// Unexpired cached answer for `url`, from memory or disk
+    ///
+    /// Stale in-memory entries are evicted on read, as the disk cache does.
+    fn cached_response(&self, url: &Url) -> Option<RdapObject> {
+        if self.cache_bypass {
+            return None;
+        }
+
+        if !self.response_ttl.is_zero()
+            && let Ok(mut responses) = self.responses.lock()
+        {
+            match responses.get(url) {
+                Some((expires, obj)) if *expires > Instant::now() => return Some(obj.clone()),
+                Some(_) => {
+                    responses.remove(url);
+                }
+                None => {}
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let (cache, headers) = self.disk_cache_for(url)?;
+            let text = cache.get_response(url, &headers)?;
+            self.parse(&text)
+                .inspect_err(|e| log::debug!("Ignoring unparsable cached response for {url}: {e}"))
+                .ok()
+        }
+        #[cfg(target_arch = "wasm32")]
+        None
+    }
+
+    /// Disk cache and the request headers that key its entries for `url`
+    ///
+    /// `None` when there is no disk cache or the request carries
+    /// credentials (a signature or URL userinfo), whose answers must not be
+    /// replayed to later runs.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn disk_cache_for(&self, url: &Url) -> Option<(&Cache, Vec<(&str, &str)>)> {
+        let cache = self.response_cache.as_ref()?;
+        let host = url.host_str().map(str::to_lowercase);
+        let signed = self
+            .signer
+            .as_ref()
+            .zip(host.as_ref())
+            .is_some_and(|((hosts, _), host)| hosts.contains(host));
+        if signed || !url.username().is_empty() || url.password().is_some() {
+            return None;
+        }
+
+        let mut headers = vec![("accept", self.accept.as_str())];
+        if let Some(lang) = &self.accept_language {
+            headers.push(("accept-language", lang));
+        }
+        if let Some(host) = host.and_then(|h| self.host_overrides.get(&h)) {
+            headers.push(("host", host));
+        }
+        Some((cache, headers))
+    }
+
+    /// Fetch and parse an RDAP URL, bypassing the response cache
+    ///
+    /// Returns the object, its raw body and how long it may be cached.
+    async fn fetch_rdap_uncached(&self, url: &Url) -> Result<(RdapObject, String, Duration)> {
+        let (text, ttl) = self.fetch_body(url).await?;
+        let obj = span::in_stage_sync("parse", || self.parse(&text))?;
+        Ok((obj, text, ttl))
+    }
+
+    /// Fetch the body of a successful response without parsing it
+    ///
+    /// Returns the body and how long it may be cached.
+    async fn fetch_body(&self, url: &Url) -> Result<(String, Duration)> {
+        let (final_url, response) = self.request_with_retry(url).await?;
 
         let status = response.status();
+        log::trace!(target: HTTP_LOG_TARGET, "< {status} {final_url}");
+        let ttl = response_lifetime(response.headers()).unwrap_or(self.response_ttl);
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let text = response.text().await?;
+        log::trace!(target: HTTP_LOG_TARGET, "< body: {}", truncate_body(&text));
 
         if status.is_success() {
-            let text = response.text().await?;
-            let obj = self.parse_response(&text)?;
-            Ok(obj)
+            // Gated-access servers may redirect to an HTML login page
+            if final_url.host_str() != url.host_str()
+                && !looks_like_rdap(content_type.as_deref(), &text)
+            {
+                return Err(login_redirect_error(&final_url));
+            }
+            Ok((text, ttl))
         } else if status.as_u16() == 404 {
             Err(RdapError::NotFound)
         } else {
-            // Try to parse as error response
-            let text = response.text().await?;
-            if let Ok(err_obj) = serde_json::from_str::<crate::models::ErrorResponse>(&text) {
-                Err(RdapError::ServerError {
-                    code: err_obj.error_code.unwrap_or(status.as_u16()),
-                    title: err_obj.title.unwrap_or_else(|| "Unknown error".to_string()),
-                    description: err_obj.description,
-                })
-            } else {
-                Err(RdapError::Other(format!("HTTP error: {status}")))
+            if self.parse_options.strict
+                && let Ok(value) = serde_json::from_str::<serde_json::Value>(&text)
+            {
+                let missing = crate::models::missing_error_members(&value);
+                if !missing.is_empty() {
+                    return Err(RdapError::NonConformant { missing });
+                }
             }
+            Err(status_error(status, &text))
         }
     }
 
-    /// Parse RDAP JSON response
-    fn parse_response(&self, json: &str) -> Result<RdapObject> {
-        // First, parse as generic JSON to inspect structure
-        let value: serde_json::Value = serde_json::from_str(json)?;
+    /// Parse a response body with the client's [`ParseOptions`]
+    fn parse(&self, text: &str) -> Result<RdapObject> {
+        RdapObject::parse_with(text, &self.parse_options)
+    }
 
-        // Detect object type
-        if let Some(obj) = value.as_object() {
-            // Check for error
-            if obj.contains_key("errorCode") {
-                return Ok(RdapObject::Error(serde_json::from_value(value)?));
+    /// Send a GET for `url`, retrying transient failures with backoff
+    ///
+    /// Retryable responses are returned as-is once retries are exhausted,
+    /// except for 429, which becomes [`RdapError::RateLimited`].
+    async fn request_with_retry(&self, url: &Url) -> Result<(Url, reqwest::Response)> {
+        let mut attempt = 0;
+        loop {
+            let (final_url, response) = self.follow_redirects(url).await?;
+            let status = response.status();
+            if !is_retryable(status) {
+                return Ok((final_url, response));
             }
 
-            // Check for search results
-            if obj.contains_key("domainSearchResults") {
-                return Ok(RdapObject::DomainSearch(serde_json::from_value(value)?));
+            let retry_after = retry_after(response.headers());
+            let delay = retry_after.unwrap_or_else(|| {
+                self.retry_base_delay
+                    .saturating_mul(2u32.saturating_pow(attempt))
+            });
+            if attempt >= self.max_retries || delay > MAX_RETRY_AFTER {
+                if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                    return Err(RdapError::RateLimited { retry_after });
+                }
+                return Ok((final_url, response));
             }
-            if obj.contains_key("entitySearchResults") {
-                return Ok(RdapObject::EntitySearch(serde_json::from_value(value)?));
+
+            attempt += 1;
+            log::debug!(
+                "{status} from {final_url}, retrying in {delay:?} ({attempt}/{})",
+                self.max_retries
+            );
+            #[cfg(not(target_arch = "wasm32"))]
+            tokio::time::sleep(delay).await;
+            #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+            gloo_timers::future::sleep(delay).await;
+        }
+    }
+
+    /// Send a GET for `url`, following redirects up to `max_redirects`
+    ///
+    /// Returns the final URL along with its (non-redirect) response.
+    async fn follow_redirects(&self, url: &Url) -> Result<(Url, reqwest::Response)> {
+        // Follow redirects by hand so per-host headers (Host overrides,
+        // signatures) are recomputed for every hop
+        let mut final_url = url.clone();
+        let mut visited = HashSet::new();
+        let response = loop {
+            visited.insert(final_url.clone());
+            let response = self.send_request(&final_url).await?;
+
+            let status = response.status();
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok());
+            let Some(location) = location.filter(|_| status.is_redirection()) else {
+                break response;
+            };
+
+            let next = final_url.join(location)?;
+            if visited.contains(&next) {
+                return Err(RdapError::Other(format!("Redirect loop at {next}")));
             }
-            if obj.contains_key("nameserverSearchResults") {
-                return Ok(RdapObject::NameserverSearch(serde_json::from_value(value)?));
+            if visited.len() > self.max_redirects {
+                return Err(RdapError::TooManyRedirects { url: next });
             }
+            log::debug!("Following {status} redirect: {final_url} -> {next}");
+            final_url = next;
+        };
 
-            // Check objectClassName
-            if let Some(class_name) = obj.get("objectClassName").and_then(|v| v.as_str()) {
-                match class_name {
-                    "domain" => return Ok(RdapObject::Domain(serde_json::from_value(value)?)),
-                    "entity" => return Ok(RdapObject::Entity(serde_json::from_value(value)?)),
-                    "nameserver" => {
-                        return Ok(RdapObject::Nameserver(serde_json::from_value(value)?));
-                    }
-                    "autnum" => return Ok(RdapObject::Autnum(serde_json::from_value(value)?)),
-                    "ip network" => {
-                        return Ok(RdapObject::IpNetwork(serde_json::from_value(value)?));
-                    }
-                    _ => {}
-                }
+        Ok((final_url, response))
+    }
+
+    /// Send a single GET for `url` with per-host headers applied
+    async fn send_request(&self, url: &Url) -> Result<reqwest::Response> {
+        let mut request = self
+            .http_client
+            .get(url.as_str())
+            .header(reqwest::header::ACCEPT, &self.accept);
+        if let Some(lang) = &self.accept_language {
+            request = request.header(reqwest::header::ACCEPT_LANGUAGE, lang);
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            request = request.timeout(self.timeout);
+        }
+
+        if let Some(host) = url
+            .host_str()
+            .and_then(|h| self.host_overrides.get(&h.to_lowercase()))
+        {
+            log::debug!("Overriding Host header for {url}: {host}");
+            request = request.header(reqwest::header::HOST, host);
+        }
+
+        // Names of computed headers, kept out of the trace log like credentials
+        let mut signed_headers = Vec::new();
+        if let Some((hosts, signer)) = &self.signer
+            && url
+                .host_str()
+                .is_some_and(|h| hosts.contains(&h.to_lowercase()))
+        {
+            for (name, value) in signer(url) {
+                signed_headers.push(name.clone());
+                request = request.header(name, value);
             }
+        }
 
-            // Default to Help
-            Ok(RdapObject::Help(serde_json::from_value(value)?))
-        } else {
-            Err(RdapError::Json(serde::de::Error::custom(
-                "Invalid RDAP response",
-            )))
+        let request = request.build()?;
+        if log::log_enabled!(target: HTTP_LOG_TARGET, log::Level::Trace) {
+            log::trace!(target: HTTP_LOG_TARGET, "> GET {}", request.url());
+            for (name, value) in request.headers() {
+                let value = if signed_headers.contains(name) {
+                    "<redacted>"
+                } else {
+                    redact_header(name, value)
+                };
+                log::trace!(target: HTTP_LOG_TARGET, "> {name}: {value}");
+            }
+        }
+
+        // Idle keep-alive connections are often dropped by the server; a GET
+        // that fails on a reused connection is retried once on a fresh one
+        let retry = request.try_clone();
+        match self.http_client.execute(request).await {
+            Ok(response) => Ok(response),
+            Err(e) => match retry {
+                Some(retry) if is_connection_reset(&e) => {
+                    log::debug!("Connection reset on {url} ({e}), retrying once");
+                    Ok(self.http_client.execute(retry).await?)
+                }
+                _ => Err(e.into()),
+            },
+        }
+    }
+}
+
+/// Make room in the in-memory response cache: drop expired entries, or the
+/// one closest to expiry if none have expired
+fn evict_responses(responses: &mut HashMap<Url, (Instant, RdapObject)>) {
+    let now = Instant::now();
+    responses.retain(|_, (expires, _)| *expires > now);
+    if responses.len() >= MAX_CACHED_RESPONSES
+        && let Some(url) = responses
+            .iter()
+            .min_by_key(|(_, (expires, _))| *expires)
+            .map(|(url, _)| url.clone())
+    {
+        responses.remove(&url);
+    }
+}
+
+/// Log stage for a request to `url`, e.g. `server rdap.verisign.com`
+fn stage_name(kind: &str, url: &Url) -> String {
+    format!("{kind} {}", url.host_str().unwrap_or_else(|| url.as_str()))
+}
+
+/// Whether a request failed because the connection was reset or closed early
+fn is_connection_reset(error: &reqwest::Error) -> bool {
+    let mut source = std::error::Error::source(error);
+    while let Some(err) = source {
+        if let Some(io) = err.downcast_ref::<std::io::Error>()
+            && matches!(
+                io.kind(),
+                std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+            )
+        {
+            return true;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if err
+            .downcast_ref::<hyper::Error>()
+            .is_some_and(hyper::Error::is_incomplete_message)
+        {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// Whether a response looks like RDAP JSON rather than e.g. an HTML page
+fn looks_like_rdap(content_type: Option<&str>, body: &str) -> bool {
+    content_type.map_or_else(|| body.trim_start().starts_with('{'), is_json_media_type)
+}
+
+/// Whether a `Content-Type` is `application/json`, `application/rdap+json`
+/// or another `+json` type, ignoring case and parameters such as `charset`
+fn is_json_media_type(content_type: &str) -> bool {
+    let media_type = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    media_type
+        .strip_prefix("application/")
+        .is_some_and(|subtype| subtype == "json" || subtype.ends_with("+json"))
+}
+
+/// Freshness lifetime the server allows, from `Cache-Control` or `Expires`
+///
+/// `no-store`/`no-cache` give a zero lifetime; `max-age` wins over `Expires`.
+fn response_lifetime(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    if let Some(cache_control) = headers
+        .get(reqwest::header::CACHE_CONTROL)
+        .and_then(|v| v.to_str().ok())
+    {
+        let directives: Vec<(&str, &str)> = cache_control
+            .split(',')
+            .map(str::trim)
+            .map(|d| d.split_once('=').unwrap_or((d, "")))
+            .collect();
+        if directives.iter().any(|(name, _)| {
+            name.eq_ignore_ascii_case("no-store") || name.eq_ignore_ascii_case("no-cache")
+        }) {
+            return Some(Duration::ZERO);
+        }
+        if let Some(secs) = directives
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("max-age"))
+            .and_then(|(_, value)| value.trim_matches('"').parse::<u64>().ok())
+        {
+            return Some(Duration::from_secs(secs));
         }
     }
+
+    let expires = headers
+        .get(reqwest::header::EXPIRES)
+        .and_then(|v| v.to_str().ok())?;
+    // Invalid dates (commonly "0") mean already expired
+    let Ok(expires) = chrono::DateTime::parse_from_rfc2822(expires) else {
+        return Some(Duration::ZERO);
+    };
+    Some(
+        (expires.with_timezone(&chrono::Utc) - chrono::Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Whether a status is worth retrying: rate limiting or a transient server error
+const fn is_retryable(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 500 | 502 | 503 | 504)
+}
+
+/// Delay requested by a `Retry-After` header, in seconds or as an HTTP date
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())?
+        .trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Error for a successful answer that came from a login page on another host
+fn login_redirect_error(final_url: &Url) -> RdapError {
+    RdapError::Other(format!(
+        "authentication may be required: redirected to {}",
+        final_url.host_str().unwrap_or("unknown host")
+    ))
+}
+
+/// Error for a non-success, non-404 response, using the RDAP error body if any
+fn status_error(status: reqwest::StatusCode, text: &str) -> RdapError {
+    if let Ok(err_obj) = serde_json::from_str::<crate::models::ErrorResponse>(text) {
+        RdapError::ServerError {
+            code: err_obj.error_code.unwrap_or(status.as_u16()),
+            title: err_obj.title.unwrap_or_else(|| "Unknown error".to_string()),
+            description: err_obj.description,
+        }
+    } else {
+        RdapError::Other(format!("HTTP error: {status}"))
+    }
+}
+
+/// Header value as written to the HTTP trace log, with credentials hidden
+fn redact_header<'a>(
+    name: &reqwest::header::HeaderName,
+    value: &'a reqwest::header::HeaderValue,
+) -> &'a str {
+    if name == reqwest::header::AUTHORIZATION || name == reqwest::header::PROXY_AUTHORIZATION {
+        "<redacted>"
+    } else {
+        value.to_str().unwrap_or("<binary>")
+    }
+}
+
+/// Cut a response body down to [`MAX_LOGGED_BODY`] bytes on a char boundary
+fn truncate_body(body: &str) -> std::borrow::Cow<'_, str> {
+    if body.len() <= MAX_LOGGED_BODY {
+        return body.into();
+    }
+    let mut end = MAX_LOGGED_BODY;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}... ({} bytes total)", &body[..end], body.len()).into()
+}
+
+/// Parse an RDAP JSON response body into the matching [`RdapObject`]
+///
+/// The object type is detected by [`RdapObject::from_json_value`]: error
+/// response, then search results, then `objectClassName`, then help.
+pub fn parse_rdap(json: &str) -> Result<RdapObject> {
+    // First, parse as generic JSON to inspect structure
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    RdapObject::from_json_value(value)
 }
 
 impl Default for RdapClient {
@@ -338,3 +1783,1495 @@ impl Default for RdapClient {
         Self::new().expect("Failed to create RDAP client")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Mutex, Once};
+
+    /// Records every log line so tests can assert on HTTP tracing
+    struct CaptureLogger;
+
+    static CAPTURED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    static INIT_LOGGER: Once = Once::new();
+
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            CAPTURED
+                .lock()
+                .unwrap()
+                .push(format!("{}: {}", record.target(), record.args()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn captured_lines(needle: &str) -> Vec<String> {
+        CAPTURED
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|line| line.contains(needle))
+            .cloned()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_http_trace_logs_url_and_status() {
+        INIT_LOGGER.call_once(|| {
+            log::set_logger(&CaptureLogger).unwrap();
+            log::set_max_level(log::LevelFilter::Trace);
+        });
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/traced/domain/example.com")
+            .with_status(418)
+            .with_body("I'm a teapot")
+            .create_async()
+            .await;
+
+        let url = Url::parse(&format!("{}/traced/domain/example.com", server.url())).unwrap();
+        let client = RdapClient::new().unwrap();
+        assert!(client.fetch_rdap(&url).await.is_err());
+        mock.assert_async().await;
+
+        let lines = captured_lines(url.as_str());
+        assert!(
+            lines
+                .iter()
+                .any(|l| l == &format!("{HTTP_LOG_TARGET}: > GET {url}"))
+        );
+        assert!(
+            lines
+                .iter()
+                .any(|l| l == &format!("{HTTP_LOG_TARGET}: < 418 I'm a teapot {url}"))
+        );
+        assert!(!captured_lines("body: I'm a teapot").is_empty());
+        drop(server);
+    }
+
+    #[test]
+    fn test_redact_authorization_header() {
+        use reqwest::header::{ACCEPT, AUTHORIZATION, HeaderValue};
+
+        let secret = HeaderValue::from_static("Bearer secret");
+        assert_eq!(redact_header(&AUTHORIZATION, &secret), "<redacted>");
+        let accept = HeaderValue::from_static("application/rdap+json");
+        assert_eq!(redact_header(&ACCEPT, &accept), "application/rdap+json");
+    }
+
+    #[test]
+    fn test_truncate_body() {
+        assert_eq!(truncate_body("{}"), "{}");
+        let long = "é".repeat(MAX_LOGGED_BODY);
+        let truncated = truncate_body(&long);
+        assert!(truncated.ends_with(&format!("... ({} bytes total)", long.len())));
+        assert!(truncated.len() < long.len());
+    }
+
+    #[tokio::test]
+    async fn test_max_servers_caps_attempts() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(r"^/s\d+/domain/example\.com$".into()),
+            )
+            .with_status(500)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let urls: Vec<Url> = (0..10)
+            .map(|i| Url::parse(&format!("{}/s{i}/", server.url())).unwrap())
+            .collect();
+        let client = RdapClient::new().unwrap().with_max_servers(2);
+        let request = RdapRequest::new(QueryType::Domain, "example.com");
+
+        let result = client
+            .query_urls(&request, &urls, &mut HashSet::new())
+            .await;
+        assert!(matches!(result, Err(RdapError::NoWorkingServers)));
+        mock.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_request_signer_only_signs_allowlisted_hosts() {
+        use reqwest::header::{HeaderName, HeaderValue};
+
+        let mut server = mockito::Server::new_async().await;
+        let port = server.socket_address().port();
+        let body = r#"{"objectClassName": "domain", "ldhName": "example.com"}"#;
+        let signed = server
+            .mock("GET", "/signed/domain/example.com")
+            .match_header("x-signature", "sig:/signed/domain/example.com")
+            .with_body(body)
+            .create_async()
+            .await;
+        let unsigned = server
+            .mock("GET", "/unsigned/domain/example.com")
+            .match_header("x-signature", mockito::Matcher::Missing)
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let client = RdapClient::new()
+            .unwrap()
+            .with_request_signer(["127.0.0.1"], |url: &Url| {
+                vec![(
+                    HeaderName::from_static("x-signature"),
+                    HeaderValue::from_str(&format!("sig:{}", url.path())).unwrap(),
+                )]
+            });
+
+        let url = Url::parse(&format!("{}/signed/domain/example.com", server.url())).unwrap();
+        assert!(client.fetch_rdap(&url).await.is_ok());
+        let url = Url::parse(&format!(
+            "http://localhost:{port}/unsigned/domain/example.com"
+        ))
+        .unwrap();
+        assert!(client.fetch_rdap(&url).await.is_ok());
+
+        signed.assert_async().await;
+        unsigned.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_disabled_requires_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/domain/example.com")
+            .with_body(r#"{"objectClassName": "domain", "ldhName": "example.com"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        // Even a local delegation map is not consulted
+        let client = RdapClient::new()
+            .unwrap()
+            .with_bootstrap_map(BootstrapMap::new().with_dns(&["com"], &[&server.url()]))
+            .with_bootstrap_disabled(true);
+
+        let request = RdapRequest::new(QueryType::Domain, "example.com");
+        let err = client.query(&request).await.unwrap_err();
+        assert!(matches!(&err, RdapError::Bootstrap(msg) if msg.contains("disabled")));
+        assert!(client.exists(&request).await.is_err());
+        assert!(client.prefetch_bootstrap().await.is_err());
+
+        let base = Url::parse(&format!("{}/", server.url())).unwrap();
+        let request = request.with_server(base);
+        assert!(matches!(
+            client.query(&request).await.unwrap(),
+            RdapObject::Domain(_)
+        ));
+
+        mock.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_whois_fallback_for_unplaced_domain() {
+        let request = RdapRequest::new(QueryType::Domain, "example.co.jp");
+        let client = RdapClient::new()
+            .unwrap()
+            .with_bootstrap_map(BootstrapMap::new());
+        assert!(matches!(
+            client.query(&request).await,
+            Err(RdapError::Bootstrap(_))
+        ));
+
+        let servers = [("jp".to_string(), "whois.jprs.jp".to_string())].into();
+        let client = client.with_whois_fallback(servers);
+        let err = client.query(&request).await.unwrap_err();
+        assert!(matches!(&err, RdapError::OnlyWhoisAvailable { host } if host == "whois.jprs.jp"));
+        assert!(err.to_string().contains("whois.jprs.jp"));
+        assert!(matches!(
+            client.exists(&request).await,
+            Err(RdapError::OnlyWhoisAvailable { .. })
+        ));
+
+        // TLDs without a listed WHOIS host keep the bootstrap error
+        let request = RdapRequest::new(QueryType::Domain, "example.test");
+        assert!(matches!(
+            client.query(&request).await,
+            Err(RdapError::Bootstrap(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rir_fallback_when_ip_bootstrap_empty() {
+        let mut server = mockito::Server::new_async().await;
+        let missing = server
+            .mock("GET", "/rir1/ip/192.0.2.1")
+            .with_status(404)
+            .create_async()
+            .await;
+        let broken = server
+            .mock("GET", "/rir2/ip/192.0.2.1")
+            .with_status(503)
+            .create_async()
+            .await;
+        let answer = server
+            .mock("GET", "/rir3/ip/192.0.2.1")
+            .with_body(r#"{"objectClassName": "ip network", "handle": "NET-192-0-2-0-1"}"#)
+            .create_async()
+            .await;
+
+        let servers: Vec<Url> = (1..=3)
+            .map(|i| Url::parse(&format!("{}/rir{i}/", server.url())).unwrap())
+            .collect();
+        let request = RdapRequest::new(QueryType::Ip, "192.0.2.1");
+
+        // Without the fallback an empty bootstrap is an error
+        let client = RdapClient::new()
+            .unwrap()
+            .with_bootstrap_map(BootstrapMap::new());
+        assert!(matches!(
+            client.query_with_referral(&request).await,
+            Err(RdapError::Bootstrap(_))
+        ));
+
+        let client = client.with_rir_fallback_servers(servers);
+        let result = client.query_with_referral(&request).await.unwrap();
+        assert!(matches!(
+            result.registry,
+            RdapObject::IpNetwork(ref ip) if ip.handle.as_deref() == Some("NET-192-0-2-0-1")
+        ));
+        assert!(result.registry_url.path().starts_with("/rir3/"));
+
+        // Other servers may be cancelled once the answer arrives
+        answer.assert_async().await;
+        drop((missing, broken, server));
+    }
+
+    #[tokio::test]
+    async fn test_query_ip_all_rirs_follows_chain() {
+        let mut server = mockito::Server::new_async().await;
+        let base = server.url();
+        let network = |handle: &str, start: &str, end: &str, self_path: &str, links: &str| {
+            format!(
+                r#"{{"objectClassName": "ip network", "handle": "{handle}",
+                    "startAddress": "{start}", "endAddress": "{end}",
+                    "links": [{{"rel": "self", "href": "{base}{self_path}"}}{links}]}}"#
+            )
+        };
+        server
+            .mock("GET", "/rir1/ip/192.0.2.1")
+            .with_body(network(
+                "SMALL",
+                "192.0.2.0",
+                "192.0.2.255",
+                "/rir1/ip/192.0.2.0/24",
+                &format!(r#", {{"rel": "up", "href": "{base}/rir1/ip/192.0.0.0/16"}}"#),
+            ))
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/rir1/ip/192.0.0.0/16")
+            .with_body(network(
+                "BIG",
+                "192.0.0.0",
+                "192.0.255.255",
+                "/rir1/ip/192.0.0.0/16",
+                "",
+            ))
+            .create_async()
+            .await;
+        // Another RIR's record of the same network is kept, a 404 is not
+        server
+            .mock("GET", "/rir2/ip/192.0.2.1")
+            .with_body(network(
+                "LEGACY",
+                "192.0.2.0",
+                "192.0.3.255",
+                "/rir2/ip/192.0.2.0/23",
+                &format!(r#", {{"rel": "up", "href": "{base}/rir1/ip/192.0.0.0/16"}}"#),
+            ))
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/rir3/ip/192.0.2.1")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let servers: Vec<Url> = (1..=3)
+            .map(|i| Url::parse(&format!("{base}/rir{i}/")).unwrap())
+            .collect();
+        let client = RdapClient::new()
+            .unwrap()
+            .with_rir_fallback_servers(servers);
+        let networks = client
+            .query_ip_all_rirs("192.0.2.1".parse().unwrap())
+            .await
+            .unwrap();
+        let handles: Vec<_> = networks
+            .iter()
+            .map(|(_, n)| n.handle.as_deref().unwrap())
+            .collect();
+        assert_eq!(handles, ["BIG", "LEGACY", "SMALL"]);
+        assert!(networks[0].0.path().starts_with("/rir1/"));
+
+        let missing = client
+            .query_ip_all_rirs("198.51.100.1".parse().unwrap())
+            .await;
+        assert!(missing.is_err());
+        drop(server);
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_query_and_display_writes_output() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/domain/example.com")
+            .with_body(
+                serde_json::json!({
+                    "objectClassName": "domain",
+                    "ldhName": "example.com",
+                    "status": ["active"],
+                    "entities": [{
+                        "objectClassName": "entity",
+                        "roles": ["abuse"],
+                        "vcardArray": ["vcard", [["email", {}, "text", "abuse@example.net"]]]
+                    }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let base = Url::parse(&format!("{}/", server.url())).unwrap();
+        let request = RdapRequest::new(QueryType::Domain, "example.com").with_server(base);
+        let client = RdapClient::new().unwrap();
+
+        let mut out = Vec::new();
+        let obj = client
+            .query_and_display(&request, &mut out, false)
+            .await
+            .unwrap();
+        assert!(matches!(obj, RdapObject::Domain(_)));
+
+        let out = crate::display::strip_ansi(&String::from_utf8(out).unwrap());
+        assert!(out.starts_with("Abuse contact for `example.com` is `abuse@example.net`\n"));
+        assert!(out.contains("Domain Name: example.com\n"));
+        assert!(out.contains("Status: active\n"));
+        assert!(out.contains("Email: abuse@example.net\n"));
+
+        mock.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_query_raw_returns_body_verbatim() {
+        let mut server = mockito::Server::new_async().await;
+        let body = r#"{"objectClassName": "domain", "ldhName": "example.com", "x_vendor": {"quirk": [1, 2]}}"#;
+        let mock = server
+            .mock("GET", "/domain/example.com")
+            .with_header("content-type", "application/rdap+json")
+            .with_body(body)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let base = Url::parse(&format!("{}/", server.url())).unwrap();
+        let request = RdapRequest::new(QueryType::Domain, "example.com").with_server(base);
+        let client = RdapClient::new()
+            .unwrap()
+            .with_response_ttl(Duration::from_mins(1));
+
+        // Raw queries bypass the response cache
+        for _ in 0..2 {
+            let raw = client.query_raw(&request).await.unwrap();
+            assert_eq!(raw.url.path(), "/domain/example.com");
+            assert_eq!(raw.status, reqwest::StatusCode::OK);
+            assert_eq!(raw.body, body.as_bytes());
+        }
+
+        mock.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_query_raw_keeps_bytes_redirects_and_error_bodies() {
+        let mut server = mockito::Server::new_async().await;
+        // Latin-1 "é", which a charset decode would replace
+        let body = b"{\"title\": \"caf\xe9\"}".to_vec();
+        let redirect = server
+            .mock("GET", "/domain/example.com")
+            .with_status(301)
+            .with_header("location", "/v2/domain/example.com")
+            .create_async()
+            .await;
+        let answer = server
+            .mock("GET", "/v2/domain/example.com")
+            .with_status(503)
+            .with_header("content-type", "application/rdap+json; charset=utf-8")
+            .with_body(body.clone())
+            .create_async()
+            .await;
+
+        let base = Url::parse(&format!("{}/", server.url())).unwrap();
+        let request = RdapRequest::new(QueryType::Domain, "example.com").with_server(base);
+        let client = RdapClient::new().unwrap();
+
+        let raw = client.query_raw(&request).await.unwrap();
+        assert_eq!(raw.url.path(), "/v2/domain/example.com");
+        assert_eq!(raw.status, reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(raw.body, body);
+
+        redirect.assert_async().await;
+        answer.assert_async().await;
+        drop(server);
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_query_report_contains_display_and_json() {
+        let mut server = mockito::Server::new_async().await;
+        let body =
+            r#"{"objectClassName": "domain", "ldhName": "example.com", "status": ["active"]}"#;
+        let mock = server
+            .mock("GET", "/domain/example.com")
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let base = Url::parse(&format!("{}/", server.url())).unwrap();
+        let request = RdapRequest::new(QueryType::Domain, "example.com").with_server(base);
+        let report = RdapClient::new()
+            .unwrap()
+            .query_report(&request)
+            .await
+            .unwrap();
+
+        let mut out = Vec::new();
+        report
+            .write(
+                &mut out,
+                crate::report::ReportFormat::Markdown,
+                &crate::display::DisplayOptions::default(),
+            )
+            .unwrap();
+        let out = crate::display::strip_ansi(&String::from_utf8(out).unwrap());
+        assert!(out.contains("Domain Name: example.com\n"));
+        assert!(out.contains(&format!("```json\n{body}\n```")));
+
+        mock.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_transient_status_retried() {
+        let mut server = mockito::Server::new_async().await;
+        let unavailable = server
+            .mock("GET", "/domain/example.com")
+            .with_status(503)
+            .with_header("retry-after", "0")
+            .expect(1)
+            .create_async()
+            .await;
+        let answer = server
+            .mock("GET", "/domain/example.com")
+            .with_body(r#"{"objectClassName": "domain", "ldhName": "example.com"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let missing = server
+            .mock("GET", "/domain/missing.com")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = RdapClient::new()
+            .unwrap()
+            .with_retry_policy(2, Duration::ZERO);
+        let url = Url::parse(&format!("{}/domain/example.com", server.url())).unwrap();
+        assert!(matches!(
+            client.fetch_rdap(&url).await.unwrap(),
+            RdapObject::Domain(_)
+        ));
+
+        // 404 is never retried
+        let url = Url::parse(&format!("{}/domain/missing.com", server.url())).unwrap();
+        assert!(matches!(
+            client.fetch_rdap(&url).await,
+            Err(RdapError::NotFound)
+        ));
+
+        unavailable.assert_async().await;
+        answer.assert_async().await;
+        missing.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_after_retries() {
+        let mut server = mockito::Server::new_async().await;
+        let limited = server
+            .mock("GET", "/domain/example.com")
+            .with_status(429)
+            .with_header("retry-after", "0")
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = RdapClient::new()
+            .unwrap()
+            .with_retry_policy(2, Duration::from_secs(30));
+        let url = Url::parse(&format!("{}/domain/example.com", server.url())).unwrap();
+        assert!(matches!(
+            client.fetch_rdap(&url).await,
+            Err(RdapError::RateLimited { retry_after: Some(d) }) if d.is_zero()
+        ));
+
+        limited.assert_async().await;
+        drop(server);
+    }
+
+    #[test]
+    fn test_retry_after() {
+        use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("90"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(90)));
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn test_connection_reset_retried_once() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let mut buf = [0u8; 4096];
+
+            // First connection: read the request and hang up without answering
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let _ = stream.read(&mut buf).await.unwrap();
+            drop(stream);
+
+            // Second connection: answer normally
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let _ = stream.read(&mut buf).await.unwrap();
+            let body = r#"{"objectClassName": "domain", "ldhName": "example.com"}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/rdap+json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let client = RdapClient::new().unwrap();
+        let url = Url::parse(&format!("http://{addr}/domain/example.com")).unwrap();
+        let obj = client.fetch_rdap(&url).await.unwrap();
+        assert!(matches!(obj, RdapObject::Domain(_)));
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ip_referral_to_managing_rir() {
+        let mut server = mockito::Server::new_async().await;
+        let port = server.socket_address().port();
+        let rir_a = server
+            .mock("GET", "/a/ip/192.0.2.1")
+            .with_body(
+                serde_json::json!({
+                    "objectClassName": "ip network",
+                    "handle": "ERX-BLOCK",
+                    "links": [{
+                        "rel": "related",
+                        "type": "application/rdap+json",
+                        "href": format!("http://localhost:{port}/b/ip/192.0.2.0/24")
+                    }]
+                })
+                .to_string(),
+            )
+            .expect(2)
+            .create_async()
+            .await;
+        let rir_b = server
+            .mock("GET", "/b/ip/192.0.2.0/24")
+            .with_body(r#"{"objectClassName": "ip network", "handle": "NET-TRANSFERRED"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let server_url = Url::parse(&format!("{}/a/", server.url())).unwrap();
+        let request = RdapRequest::new(QueryType::Ip, "192.0.2.1").with_server(server_url);
+        let handle = |result: &RdapQueryResult| match &result.registry {
+            RdapObject::IpNetwork(net) => net.handle.clone(),
+            _ => None,
+        };
+
+        // Disabled by default
+        let client = RdapClient::new().unwrap();
+        let result = client.query_with_referral(&request).await.unwrap();
+        assert_eq!(handle(&result).as_deref(), Some("ERX-BLOCK"));
+
+        let client = RdapClient::new().unwrap().with_follow_ip_referral(true);
+        let result = client.query_with_referral(&request).await.unwrap();
+        assert_eq!(handle(&result).as_deref(), Some("NET-TRANSFERRED"));
+        assert_eq!(result.registry_url.path(), "/b/ip/192.0.2.0/24");
+
+        rir_a.assert_async().await;
+        rir_b.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_response_ttl() {
+        let mut server = mockito::Server::new_async().await;
+        let cached = server
+            .mock("GET", "/cached/domain/example.com")
+            .with_body(r#"{"objectClassName": "domain", "ldhName": "example.com"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let uncached = server
+            .mock("GET", "/uncached/domain/example.com")
+            .with_body(r#"{"objectClassName": "domain", "ldhName": "example.com"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = RdapClient::new()
+            .unwrap()
+            .with_response_ttl(Duration::from_mins(1));
+        let url = Url::parse(&format!("{}/cached/domain/example.com", server.url())).unwrap();
+        client.fetch_rdap(&url).await.unwrap();
+        client.fetch_rdap(&url).await.unwrap();
+
+        let client = client.with_response_ttl(Duration::ZERO);
+        let url = Url::parse(&format!("{}/uncached/domain/example.com", server.url())).unwrap();
+        client.fetch_rdap(&url).await.unwrap();
+        client.fetch_rdap(&url).await.unwrap();
+
+        cached.assert_async().await;
+        uncached.assert_async().await;
+        drop(server);
+    }
+
+    #[test]
+    fn test_evict_responses() {
+        let obj = parse_rdap(r#"{"objectClassName": "autnum"}"#).unwrap();
+        let now = Instant::now();
+        let url = |i: usize| Url::parse(&format!("https://rdap.example/autnum/{i}")).unwrap();
+
+        // Expired entries go first
+        let mut responses: HashMap<_, _> = (0..MAX_CACHED_RESPONSES)
+            .map(|i| {
+                let expires = if i % 2 == 0 {
+                    now
+                } else {
+                    now + Duration::from_secs(60 + i as u64)
+                };
+                (url(i), (expires, obj.clone()))
+            })
+            .collect();
+        evict_responses(&mut responses);
+        assert_eq!(responses.len(), MAX_CACHED_RESPONSES / 2);
+
+        // Otherwise the entry closest to expiry is dropped
+        let mut responses: HashMap<_, _> = (0..MAX_CACHED_RESPONSES)
+            .map(|i| {
+                (
+                    url(i),
+                    (now + Duration::from_secs(60 + i as u64), obj.clone()),
+                )
+            })
+            .collect();
+        evict_responses(&mut responses);
+        assert_eq!(responses.len(), MAX_CACHED_RESPONSES - 1);
+        assert!(!responses.contains_key(&url(0)));
+    }
+
+    #[test]
+    fn test_response_lifetime() {
+        use reqwest::header::{CACHE_CONTROL, EXPIRES, HeaderMap, HeaderValue};
+
+        let headers = |pairs: &[(reqwest::header::HeaderName, &'static str)]| {
+            let mut map = HeaderMap::new();
+            for (name, value) in pairs {
+                map.insert(name, HeaderValue::from_static(value));
+            }
+            map
+        };
+
+        assert_eq!(response_lifetime(&headers(&[])), None);
+        assert_eq!(
+            response_lifetime(&headers(&[(CACHE_CONTROL, "public, max-age=300")])),
+            Some(Duration::from_mins(5))
+        );
+        assert_eq!(
+            response_lifetime(&headers(&[(CACHE_CONTROL, "max-age=300, no-store")])),
+            Some(Duration::ZERO)
+        );
+        // max-age wins over Expires
+        assert_eq!(
+            response_lifetime(&headers(&[
+                (CACHE_CONTROL, "max-age=60"),
+                (EXPIRES, "Thu, 01 Jan 1970 00:00:00 GMT")
+            ])),
+            Some(Duration::from_mins(1))
+        );
+        assert_eq!(
+            response_lifetime(&headers(&[(EXPIRES, "Thu, 01 Jan 1970 00:00:00 GMT")])),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(
+            response_lifetime(&headers(&[(EXPIRES, "0")])),
+            Some(Duration::ZERO)
+        );
+        let future = (chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc2822();
+        let mut map = HeaderMap::new();
+        map.insert(EXPIRES, HeaderValue::from_str(&future).unwrap());
+        assert!(response_lifetime(&map).unwrap() > Duration::from_mins(59));
+    }
+
+    #[tokio::test]
+    async fn test_disk_response_cache() {
+        let mut server = mockito::Server::new_async().await;
+        let cached = server
+            .mock("GET", "/domain/example.com")
+            .with_header("content-type", "application/rdap+json")
+            .with_header("cache-control", "max-age=300")
+            .with_body(r#"{"objectClassName": "domain", "ldhName": "example.com"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let no_store = server
+            .mock("GET", "/domain/example.net")
+            .with_header("content-type", "application/rdap+json")
+            .with_header("cache-control", "no-store")
+            .with_body(r#"{"objectClassName": "domain", "ldhName": "example.net"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let url = Url::parse(&format!("{}/domain/example.com", server.url())).unwrap();
+        let client = RdapClient::new()
+            .unwrap()
+            .with_response_cache(Cache::in_dir(dir.path()).unwrap());
+        client.fetch_rdap(&url).await.unwrap();
+
+        // A new client (e.g. the next CLI run) is served from disk
+        let client = RdapClient::new()
+            .unwrap()
+            .with_response_cache(Cache::in_dir(dir.path()).unwrap());
+        let obj = client.fetch_rdap(&url).await.unwrap();
+        assert!(
+            matches!(obj, RdapObject::Domain(ref d) if d.ldh_name.as_deref() == Some("example.com"))
+        );
+
+        // Bypassing the cache always asks the server
+        let client = client.with_cache_bypass(true);
+        client.fetch_rdap(&url).await.unwrap();
+
+        // no-store responses are never cached
+        let client = client.with_cache_bypass(false);
+        let url = Url::parse(&format!("{}/domain/example.net", server.url())).unwrap();
+        client.fetch_rdap(&url).await.unwrap();
+        client.fetch_rdap(&url).await.unwrap();
+
+        cached.assert_async().await;
+        no_store.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_disk_response_cache_keys_on_headers() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/domain/example.com")
+            .with_header("content-type", "application/rdap+json")
+            .with_header("cache-control", "max-age=300")
+            .with_body(r#"{"objectClassName": "domain", "ldhName": "example.com"}"#)
+            .expect(4)
+            .create_async()
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let url = Url::parse(&format!("{}/domain/example.com", server.url())).unwrap();
+        let cached = || {
+            RdapClient::new()
+                .unwrap()
+                .with_response_cache(Cache::in_dir(dir.path()).unwrap())
+        };
+
+        cached().fetch_rdap(&url).await.unwrap();
+        cached().fetch_rdap(&url).await.unwrap();
+
+        // Another language is a separate entry
+        let client = cached().with_accept_language("de");
+        client.fetch_rdap(&url).await.unwrap();
+        cached()
+            .with_accept_language("de")
+            .fetch_rdap(&url)
+            .await
+            .unwrap();
+
+        // Signed requests are never written to disk
+        let signed = || {
+            cached().with_request_signer(["127.0.0.1"], |_: &Url| {
+                vec![(
+                    reqwest::header::AUTHORIZATION,
+                    reqwest::header::HeaderValue::from_static("Bearer secret"),
+                )]
+            })
+        };
+        signed().fetch_rdap(&url).await.unwrap();
+        signed().fetch_rdap(&url).await.unwrap();
+
+        mock.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_query_batch_reports_progress() {
+        let mut server = mockito::Server::new_async().await;
+        let found = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(r"^/domain/found\d\.com$".into()),
+            )
+            .with_header("content-type", "application/rdap+json")
+            .with_body(r#"{"objectClassName": "domain"}"#)
+            .expect(4)
+            .create_async()
+            .await;
+        let missing = server
+            .mock("GET", "/domain/missing.com")
+            .with_status(404)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = RdapClient::new().unwrap();
+        let base = Url::parse(&format!("{}/", server.url())).unwrap();
+        let requests: Vec<RdapRequest> = ["found1.com", "missing.com", "found2.com"]
+            .iter()
+            .map(|name| RdapRequest::new(QueryType::Domain, *name).with_server(base.clone()))
+            .collect();
+
+        let mut calls = Vec::new();
+        let results = client
+            .query_batch_with_progress(&requests, 2, |done, total| calls.push((done, total)))
+            .await;
+        assert_eq!(calls, [(1, 3), (2, 3), (3, 3)]);
+        let queries: Vec<&str> = results.iter().map(|(r, _)| r.query.as_str()).collect();
+        assert_eq!(queries, ["found1.com", "missing.com", "found2.com"]);
+        assert!(results[0].1.is_ok());
+        assert!(matches!(results[1].1, Err(RdapError::NotFound)));
+        assert!(results[2].1.is_ok());
+
+        let mut calls = 0;
+        let results = client
+            .query_batch_with_referral_and_progress(&requests, 2, |_, _| calls += 1)
+            .await;
+        assert_eq!(calls, 3);
+        assert!(matches!(results[1].1, Err(RdapError::NotFound)));
+
+        found.assert_async().await;
+        missing.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_query_batch_with_referral_keeps_order() {
+        let mut server = mockito::Server::new_async().await;
+        let domain = server
+            .mock("GET", "/domain/example.com")
+            .with_header("content-type", "application/rdap+json")
+            .with_body(r#"{"objectClassName": "domain", "ldhName": "example.com"}"#)
+            .create_async()
+            .await;
+        let ip = server
+            .mock("GET", "/ip/192.0.2.1")
+            .with_header("content-type", "application/rdap+json")
+            .with_body(r#"{"objectClassName": "ip network", "startAddress": "192.0.2.0"}"#)
+            .create_async()
+            .await;
+
+        let client = RdapClient::new().unwrap();
+        let base = Url::parse(&format!("{}/", server.url())).unwrap();
+        let requests = [
+            RdapRequest::new(QueryType::Ip, "192.0.2.1").with_server(base.clone()),
+            RdapRequest::new(QueryType::Domain, "example.com").with_server(base),
+        ];
+
+        let results = client.query_batch_with_referral(&requests, 2).await;
+        assert!(matches!(
+            results[0].1.as_ref().unwrap().registry,
+            RdapObject::IpNetwork(_)
+        ));
+        let domain_result = results[1].1.as_ref().unwrap();
+        assert!(matches!(domain_result.registry, RdapObject::Domain(_)));
+        assert!(domain_result.registrar.is_none());
+
+        domain.assert_async().await;
+        ip.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_query_by_url() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/com/v1/domain/example.com")
+            .with_header("content-type", "application/rdap+json")
+            .with_body(r#"{"objectClassName": "domain", "ldhName": "example.com"}"#)
+            .create_async()
+            .await;
+
+        let client = RdapClient::new().unwrap();
+        let link = format!("{}/com/v1/domain/example.com", server.url());
+        let request = RdapRequest::new(RdapRequest::detect_type(&link).unwrap(), &link);
+        let result = client.query_with_referral(&request).await.unwrap();
+        assert!(matches!(result.registry, RdapObject::Domain(_)));
+        assert_eq!(result.registry_url.as_str(), link);
+
+        mock.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_exists() {
+        let mut server = mockito::Server::new_async().await;
+        let found = server
+            .mock("GET", "/domain/example.com")
+            .with_header("content-type", "application/rdap+json")
+            .with_body(r#"{"objectClassName": "domain", "ldhName": "example.com"}"#)
+            .create_async()
+            .await;
+        let missing = server
+            .mock("GET", "/domain/unregistered.com")
+            .with_status(404)
+            .create_async()
+            .await;
+        let failing = server
+            .mock("GET", "/domain/broken.com")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let client = RdapClient::new().unwrap();
+        let base = Url::parse(&format!("{}/", server.url())).unwrap();
+        let request =
+            |name: &str| RdapRequest::new(QueryType::Domain, name).with_server(base.clone());
+
+        assert!(client.exists(&request("example.com")).await.unwrap());
+        assert!(!client.exists(&request("unregistered.com")).await.unwrap());
+        assert!(client.exists(&request("broken.com")).await.is_err());
+
+        found.assert_async().await;
+        missing.assert_async().await;
+        failing.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_redirects_followed_across_hosts() {
+        let mut server = mockito::Server::new_async().await;
+        let port = server.socket_address().port();
+        let first = server
+            .mock("GET", "/arin/ip/192.0.2.1")
+            .with_status(302)
+            .with_header(
+                "location",
+                &format!("http://localhost:{port}/ripe/ip/192.0.2.1"),
+            )
+            .expect(2)
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/ripe/ip/192.0.2.1")
+            .with_status(303)
+            .with_header("location", "/ripe/v2/ip/192.0.2.1")
+            .expect(2)
+            .create_async()
+            .await;
+        let last = server
+            .mock("GET", "/ripe/v2/ip/192.0.2.1")
+            .with_header("content-type", "application/rdap+json")
+            .with_body(r#"{"objectClassName": "ip network", "handle": "RIPE-NET"}"#)
+            .create_async()
+            .await;
+
+        let client = RdapClient::new().unwrap();
+        let url = Url::parse(&format!("{}/arin/ip/192.0.2.1", server.url())).unwrap();
+        let obj = client.fetch_rdap(&url).await.unwrap();
+        assert!(
+            matches!(obj, RdapObject::IpNetwork(ref ip) if ip.handle.as_deref() == Some("RIPE-NET"))
+        );
+
+        // The same chain exceeds a limit of one redirect
+        let client = RdapClient::new().unwrap().with_max_redirects(1);
+        match client.fetch_rdap(&url).await {
+            Err(RdapError::TooManyRedirects { url }) => {
+                assert_eq!(url.path(), "/ripe/v2/ip/192.0.2.1");
+            }
+            other => panic!("expected TooManyRedirects, got {other:?}"),
+        }
+
+        first.assert_async().await;
+        second.assert_async().await;
+        last.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_redirect_loop_aborts() {
+        let mut server = mockito::Server::new_async().await;
+        let a = server
+            .mock("GET", "/a/domain/example.com")
+            .with_status(302)
+            .with_header("location", "/b/domain/example.com")
+            .expect(1)
+            .create_async()
+            .await;
+        let b = server
+            .mock("GET", "/b/domain/example.com")
+            .with_status(302)
+            .with_header("location", "/a/domain/example.com")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = RdapClient::new().unwrap().with_max_redirects(10);
+        let url = Url::parse(&format!("{}/a/domain/example.com", server.url())).unwrap();
+        let err = client.fetch_rdap(&url).await.unwrap_err();
+        assert!(err.to_string().contains("Redirect loop"), "{err}");
+
+        a.assert_async().await;
+        b.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_redirect_to_login_page_requires_authentication() {
+        let mut server = mockito::Server::new_async().await;
+        let port = server.socket_address().port();
+        let redirect = server
+            .mock("GET", "/rdap/domain/example.com")
+            .with_status(302)
+            .with_header("location", &format!("http://localhost:{port}/login"))
+            .create_async()
+            .await;
+        let login = server
+            .mock("GET", "/login")
+            .with_header("content-type", "text/html; charset=utf-8")
+            .with_body("<html><body>Please sign in</body></html>")
+            .create_async()
+            .await;
+
+        let url = Url::parse(&format!("{}/rdap/domain/example.com", server.url())).unwrap();
+        let client = RdapClient::new().unwrap();
+        let err = client.fetch_rdap(&url).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            RdapError::Other("authentication may be required: redirected to localhost".into())
+                .to_string()
+        );
+
+        redirect.assert_async().await;
+        login.assert_async().await;
+        drop(server);
+    }
+
+    #[test]
+    fn test_looks_like_rdap() {
+        assert!(looks_like_rdap(Some("application/rdap+json"), ""));
+        assert!(looks_like_rdap(Some("Application/JSON; charset=utf-8"), ""));
+        assert!(!looks_like_rdap(Some("text/html"), "{}"));
+        assert!(looks_like_rdap(None, "  {\"errorCode\": 404}"));
+        assert!(!looks_like_rdap(None, "<html></html>"));
+    }
+
+    #[test]
+    fn test_json_media_types() {
+        for content_type in [
+            "application/rdap+json",
+            "application/rdap+json; charset=UTF-8",
+            "application/rdap+json;charset=utf-8",
+            "APPLICATION/RDAP+JSON ; charset=\"utf-8\"",
+            "application/json; charset=utf-8",
+            "application/json;q=0.9;charset=iso-8859-1",
+            " application/json ",
+        ] {
+            assert!(is_json_media_type(content_type), "{content_type}");
+        }
+        for content_type in [
+            "text/html; charset=utf-8",
+            "text/json",
+            "application/jsonp",
+            "application/x-json-stream",
+            "text/html; note=application/json",
+            "",
+        ] {
+            assert!(!is_json_media_type(content_type), "{content_type}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_urls_and_referral_loop_skipped() {
+        let mut server = mockito::Server::new_async().await;
+        let port = server.socket_address().port();
+        let failing = server
+            .mock("GET", "/a/domain/example.com")
+            .with_status(500)
+            .expect(1)
+            .create_async()
+            .await;
+        // Referral points back at the failed URL via a different host name
+        let body = serde_json::json!({
+            "objectClassName": "domain",
+            "ldhName": "example.com",
+            "links": [{
+                "rel": "related",
+                "type": "application/rdap+json",
+                "href": format!("http://localhost:{port}/a/domain/example.com")
+            }]
+        });
+        let working = server
+            .mock("GET", "/b/domain/example.com")
+            .with_status(200)
+            .with_body(body.to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let urls: Vec<Url> = [
+            format!("http://localhost:{port}/a/"),
+            format!("http://localhost:{port}/a/"),
+            format!("http://127.0.0.1:{port}/b/"),
+        ]
+        .iter()
+        .map(|u| Url::parse(u).unwrap())
+        .collect();
+        let client = RdapClient::new().unwrap();
+        let request = RdapRequest::new(QueryType::Domain, "example.com");
+
+        let result = client
+            .query_urls(&request, &urls, &mut HashSet::new())
+            .await
+            .unwrap();
+        assert!(result.registrar.is_none());
+        assert_eq!(result.registry_url.port(), Some(port));
+        failing.assert_async().await;
+        working.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_query_with_bootstrap_map() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/rdap/autnum/64500")
+            .with_body(r#"{"objectClassName": "autnum", "startAutnum": 64500}"#)
+            .create_async()
+            .await;
+
+        let base = format!("{}/rdap/", server.url());
+        let map = BootstrapMap::new().with_asn(&["64496-64511"], &[base.as_str()]);
+        let client = RdapClient::new().unwrap().with_bootstrap_map(map);
+
+        let request = RdapRequest::new(QueryType::Autnum, "AS64500");
+        let result = client.query(&request).await.unwrap();
+        assert!(matches!(result, RdapObject::Autnum(a) if a.start_autnum == Some(64500)));
+
+        let request = RdapRequest::new(QueryType::Autnum, "AS1");
+        assert!(matches!(
+            client.query(&request).await,
+            Err(RdapError::Bootstrap(_))
+        ));
+        mock.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_http_version_modes() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/autnum/64500")
+            .with_body(r#"{"objectClassName": "autnum", "startAutnum": 64500}"#)
+            .expect(3)
+            .create_async()
+            .await;
+        let url = Url::parse(&format!("{}/autnum/64500", server.url())).unwrap();
+
+        for version in [
+            HttpVersion::Auto,
+            HttpVersion::Http1Only,
+            HttpVersion::Http2PriorKnowledge,
+        ] {
+            let client = RdapClient::new().unwrap().with_http_version(version);
+            assert_eq!(client.http_version, version);
+            assert!(client.fetch_rdap(&url).await.is_ok(), "{version:?}");
+        }
+        mock.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_expand_entity_network() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/ip/192.0.2.0")
+            .with_body(
+                r#"{"objectClassName": "ip network", "handle": "NET-192-0-2-0-1", "name": "TEST-NET-1"}"#,
+            )
+            .create_async()
+            .await;
+
+        let entity: Entity = serde_json::from_value(serde_json::json!({
+            "objectClassName": "entity",
+            "handle": "ORG-1",
+            "networks": [{
+                "objectClassName": "ip network",
+                "handle": "NET-192-0-2-0-1",
+                "links": [{"rel": "self", "href": format!("{}/ip/192.0.2.0", server.url())}]
+            }],
+            "autnums": [{"objectClassName": "autnum", "handle": "AS64500"}]
+        }))
+        .unwrap();
+
+        let client = RdapClient::new().unwrap();
+        let expanded = client.expand_entity(&entity).await.unwrap();
+        assert_eq!(expanded.networks.len(), 1);
+        assert_eq!(expanded.networks[0].name.as_deref(), Some("TEST-NET-1"));
+        // No self link, so the summary is kept
+        assert_eq!(expanded.autnums[0].handle.as_deref(), Some("AS64500"));
+        mock.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_accept_header() {
+        let mut server = mockito::Server::new_async().await;
+        let profile = server
+            .mock("GET", "/autnum/64500")
+            .match_header("accept", ICANN_PROFILE_ACCEPT)
+            .with_body(r#"{"objectClassName": "autnum", "startAutnum": 64500}"#)
+            .create_async()
+            .await;
+        let default = server
+            .mock("GET", "/autnum/64500")
+            .match_header("accept", DEFAULT_ACCEPT)
+            .with_body(r#"{"objectClassName": "autnum", "startAutnum": 64500}"#)
+            .create_async()
+            .await;
+
+        let url = Url::parse(&format!("{}/autnum/64500", server.url())).unwrap();
+        let client = RdapClient::new().unwrap();
+        assert!(client.fetch_rdap(&url).await.is_ok());
+        let client = client.with_icann_profile();
+        assert!(client.fetch_rdap(&url).await.is_ok());
+
+        profile.assert_async().await;
+        default.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_strict_parsing() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/autnum/64500")
+            .with_body(r#"{"objectClassName": "autnum", "startAutnum": 64500}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/autnum/64501")
+            .with_status(500)
+            .with_body(r#"{"rdapConformance": ["rdap_level_0"], "title": "Oops"}"#)
+            .create_async()
+            .await;
+
+        let url = Url::parse(&format!("{}/autnum/64500", server.url())).unwrap();
+        let client = RdapClient::new().unwrap();
+        assert!(client.fetch_rdap(&url).await.is_ok());
+
+        let client = client.with_strict_parsing(true);
+        let err = client.fetch_rdap(&url).await.unwrap_err();
+        assert!(
+            matches!(&err, RdapError::NonConformant { missing } if missing == &["rdapConformance"]),
+            "{err:?}"
+        );
+
+        let url = Url::parse(&format!("{}/autnum/64501", server.url())).unwrap();
+        let err = client.fetch_rdap(&url).await.unwrap_err();
+        assert!(
+            matches!(&err, RdapError::NonConformant { missing } if missing == &["errorCode"]),
+            "{err:?}"
+        );
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_host_override_header() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/autnum/64500")
+            .match_header("host", "rdap.example.net")
+            .with_body(r#"{"objectClassName": "autnum", "startAutnum": 64500}"#)
+            .create_async()
+            .await;
+
+        let client = RdapClient::new()
+            .unwrap()
+            .with_host_override("127.0.0.1", "rdap.example.net");
+        let url = Url::parse(&format!("{}/autnum/64500", server.url())).unwrap();
+        assert!(client.fetch_rdap(&url).await.is_ok());
+        mock.assert_async().await;
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_compare_servers() {
+        let mut registry = mockito::Server::new_async().await;
+        let mut registrar = mockito::Server::new_async().await;
+        let registry_mock = registry
+            .mock("GET", "/domain/example.com")
+            .with_body(
+                r#"{"objectClassName": "domain", "ldhName": "example.com", "status": ["active"], "port43": "whois.example.com"}"#,
+            )
+            .create_async()
+            .await;
+        let registrar_mock = registrar
+            .mock("GET", "/domain/example.com")
+            .with_body(
+                r#"{"objectClassName": "domain", "ldhName": "example.com", "status": ["client transfer prohibited"]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = RdapClient::new().unwrap();
+        let request = RdapRequest::new(QueryType::Domain, "example.com");
+        let diffs = client
+            .compare_servers(
+                &request,
+                &Url::parse(&format!("{}/", registry.url())).unwrap(),
+                &Url::parse(&format!("{}/", registrar.url())).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let paths: Vec<&str> = diffs.iter().map(FieldDiff::path).collect();
+        assert_eq!(paths, ["port43", "status[0]"]);
+        assert!(matches!(diffs[0], FieldDiff::OnlyLeft { .. }));
+        assert!(matches!(diffs[1], FieldDiff::Changed { .. }));
+        registry_mock.assert_async().await;
+        registrar_mock.assert_async().await;
+        drop(registry);
+        drop(registrar);
+    }
+
+    #[tokio::test]
+    async fn test_empty_success_is_not_found() {
+        // Conformance only: no object, search results, error code or notices
+        let body = r#"{"rdapConformance": ["rdap_level_0"], "notices": []}"#;
+        assert!(matches!(parse_rdap(body), Err(RdapError::NotFound)));
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/domain/missing.example")
+            .with_header("content-type", "application/rdap+json")
+            .with_body(body)
+            .create_async()
+            .await;
+        let base = Url::parse(&format!("{}/", server.url())).unwrap();
+        let request = RdapRequest::new(QueryType::Domain, "missing.example").with_server(base);
+        let result = RdapClient::new().unwrap().query(&request).await;
+        assert!(matches!(result, Err(RdapError::NotFound)));
+
+        mock.assert_async().await;
+        drop(server);
+    }
+
+    #[test]
+    fn test_parse_response_reports_object_class() {
+        let json = r#"{"objectClassName": "autnum", "startAutnum": "not a number"}"#;
+        match parse_rdap(json) {
+            Err(RdapError::Deserialize {
+                object_class,
+                message,
+            }) => {
+                assert_eq!(object_class, "autnum");
+                assert!(message.contains("invalid AS number"));
+            }
+            other => panic!("expected Deserialize error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_rdap_object_types() {
+        let cases = [
+            (
+                r#"{"objectClassName": "domain", "ldhName": "example.com"}"#,
+                "domain",
+            ),
+            (
+                r#"{"objectClassName": "entity", "handle": "XTOM"}"#,
+                "entity",
+            ),
+            (
+                r#"{"objectClassName": "nameserver", "ldhName": "ns1.example.com"}"#,
+                "nameserver",
+            ),
+            (
+                r#"{"objectClassName": "autnum", "startAutnum": 8888}"#,
+                "autnum",
+            ),
+            (
+                r#"{"objectClassName": "ip network", "startAddress": "192.0.2.0"}"#,
+                "ip network",
+            ),
+            (r#"{"errorCode": 404, "title": "Not Found"}"#, "error"),
+            (r#"{"domainSearchResults": []}"#, "domain search"),
+            (r#"{"entitySearchResults": []}"#, "entity search"),
+            (r#"{"nameserverSearchResults": []}"#, "nameserver search"),
+            (
+                r#"{"rdapConformance": ["rdap_level_0"], "notices": [{"title": "Help", "description": ["See the docs"]}]}"#,
+                "help",
+            ),
+        ];
+
+        for (json, expected) in cases {
+            let kind = match parse_rdap(json).unwrap() {
+                RdapObject::Domain(_) => "domain",
+                RdapObject::Entity(_) => "entity",
+                RdapObject::Nameserver(_) => "nameserver",
+                RdapObject::Autnum(_) => "autnum",
+                RdapObject::IpNetwork(_) => "ip network",
+                RdapObject::Error(_) => "error",
+                RdapObject::DomainSearch(_) => "domain search",
+                RdapObject::EntitySearch(_) => "entity search",
+                RdapObject::NameserverSearch(_) => "nameserver search",
+                RdapObject::Help(_) => "help",
+            };
+            assert_eq!(kind, expected, "{json}");
+        }
+
+        assert!(matches!(parse_rdap("[]"), Err(RdapError::Json(_))));
+        assert!(matches!(parse_rdap("not json"), Err(RdapError::Json(_))));
+    }
+}
@@ -9,8 +9,11 @@
 use crate::error::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
 use std::fs;
 use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::time::Duration;
 use url::Url;
 
 /// Built-in default config (embedded from config/config.json)
@@ -19,15 +22,23 @@ const BUILTIN_CONFIG: &str = include_str!("../config/config.json");
 /// Built-in TLD overrides (embedded from config/tlds.json)
 const BUILTIN_TLDS: &str = include_str!("../config/tlds.json");
 
+/// Built-in WHOIS servers for TLDs without RDAP (embedded from config/whois.json)
+const BUILTIN_WHOIS: &str = include_str!("../config/whois.json");
+
 /// Built-in IANA TLD list (embedded from config/tlds.txt)
 const BUILTIN_TLD_LIST: &str = include_str!("../config/tlds.txt");
 
+/// Public suffix list (embedded from `config/public_suffix_list.dat`)
+const BUILTIN_PUBLIC_SUFFIX_LIST: &str = include_str!("../config/public_suffix_list.dat");
+
 /// GitHub raw URLs for config updates
 pub const CONFIG_UPDATE_URL: &str =
     "https://raw.githubusercontent.com/xtomcom/rdap/main/config/config.json";
 pub const TLDS_UPDATE_URL: &str =
     "https://raw.githubusercontent.com/xtomcom/rdap/main/config/tlds.json";
 pub const TLD_LIST_UPDATE_URL: &str = "https://data.iana.org/TLD/tlds-alpha-by-domain.txt";
+pub const PUBLIC_SUFFIX_LIST_UPDATE_URL: &str =
+    "https://publicsuffix.org/list/public_suffix_list.dat";
 
 /// IANA RDAP server for TLD queries
 pub const IANA_RDAP_URL: &str = "https://rdap.iana.org/";
@@ -37,6 +48,9 @@ pub const IANA_RDAP_URL: &str = "https://rdap.iana.org/";
 pub struct Config {
     pub bootstrap: BootstrapConfig,
     pub cache: CacheConfig,
+    /// Terminal color theme (default: the built-in `dark` theme)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<ThemeConfig>,
 }
 
 /// Bootstrap URLs configuration
@@ -46,17 +60,91 @@ pub struct BootstrapConfig {
     pub asn: String,
     pub ipv4: String,
     pub ipv6: String,
+    /// RFC 8521 object tags registry (entity handle suffixes)
+    #[serde(default = "default_object_tags_url")]
+    pub object_tags: String,
 }
 
+fn default_object_tags_url() -> String {
+    "https://data.iana.org/rdap/object-tags.json".to_string()
+}
+
+/// Color theme: a built-in base theme with individual colors replaced
+///
+/// Colors are names (`cyan`, `bright blue`), `#rrggbb`, or `none` for the
+/// terminal's default color.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    /// Built-in theme to start from: `dark` (default) or `light`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// Field labels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Names, addresses, handles and other highlighted values
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Healthy status values and `yes` answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub good: Option<String>,
+    /// Locked or failing status values, `no` answers and warnings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bad: Option<String>,
+    /// Other status values, roles and contact emails
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub neutral: Option<String>,
+    /// Links
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+/// Default disk cache size limit (100 MiB)
+pub const DEFAULT_CACHE_MAX_BYTES: u64 = 100 * 1024 * 1024;
+
 /// Cache configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheConfig {
+    /// Default TTL for bootstrap registry files
     pub ttl_seconds: u64,
+    /// TTL for bootstrap registry files (default: `ttl_seconds`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bootstrap_ttl_seconds: Option<u64>,
+    /// TTL for cached RDAP responses (default: not cached unless the server
+    /// sends a lifetime)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_ttl_seconds: Option<u64>,
+    /// Disk cache size limit in bytes (default: 100 MiB, 0 = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size_bytes: Option<u64>,
+}
+
+impl CacheConfig {
+    /// How long bootstrap registry files are reused
+    pub fn bootstrap_ttl(&self) -> Duration {
+        Duration::from_secs(self.bootstrap_ttl_seconds.unwrap_or(self.ttl_seconds))
+    }
+
+    /// How long RDAP responses are reused (zero unless set explicitly)
+    pub fn response_ttl(&self) -> Duration {
+        Duration::from_secs(self.response_ttl_seconds.unwrap_or(0))
+    }
+
+    /// Disk cache size limit, or `None` when unlimited
+    pub const fn max_size(&self) -> Option<u64> {
+        match self.max_size_bytes {
+            None => Some(DEFAULT_CACHE_MAX_BYTES),
+            Some(0) => None,
+            Some(bytes) => Some(bytes),
+        }
+    }
 }
 
 /// TLD overrides - maps TLD/SLD to RDAP server URL
 pub type TldOverrides = HashMap<String, String>;
 
+/// WHOIS (port 43) hosts keyed by domain suffix, for TLDs without RDAP
+pub type WhoisServers = HashMap<String, String>;
+
 impl Default for BootstrapConfig {
     fn default() -> Self {
         Self {
@@ -64,6 +152,7 @@ impl Default for BootstrapConfig {
             asn: "https://data.iana.org/rdap/asn.json".to_string(),
             ipv4: "https://data.iana.org/rdap/ipv4.json".to_string(),
             ipv6: "https://data.iana.org/rdap/ipv6.json".to_string(),
+            object_tags: default_object_tags_url(),
         }
     }
 }
@@ -72,6 +161,9 @@ impl Default for CacheConfig {
     fn default() -> Self {
         Self {
             ttl_seconds: 86400, // 24 hours
+            bootstrap_ttl_seconds: None,
+            response_ttl_seconds: None,
+            max_size_bytes: None,
         }
     }
 }
@@ -90,6 +182,18 @@ pub fn system_config_dir() -> PathBuf {
     PathBuf::from("/etc/rdap")
 }
 
+/// Read a config file, treating a missing or unreadable file as absent
+#[cfg(not(target_arch = "wasm32"))]
+fn read_config_file(path: &std::path::Path) -> Option<String> {
+    fs::read_to_string(path).ok()
+}
+
+/// There is no filesystem in the browser, so only built-in data is used
+#[cfg(target_arch = "wasm32")]
+const fn read_config_file(_path: &std::path::Path) -> Option<String> {
+    None
+}
+
 impl Config {
     /// Get the config directory path (alias for `user_config_dir`)
     pub fn config_dir() -> Result<PathBuf> {
@@ -103,8 +207,7 @@ impl Config {
 
         // Try config.local.json first (user local overrides)
         let local_path = user_dir.join("config.local.json");
-        if local_path.exists()
-            && let Ok(content) = fs::read_to_string(&local_path)
+        if let Some(content) = read_config_file(&local_path)
             && let Ok(config) = serde_json::from_str(&content)
         {
             log::debug!("Loaded config from {}", local_path.display());
@@ -113,8 +216,7 @@ impl Config {
 
         // Try config.json in user dir (downloaded config)
         let user_path = user_dir.join("config.json");
-        if user_path.exists()
-            && let Ok(content) = fs::read_to_string(&user_path)
+        if let Some(content) = read_config_file(&user_path)
             && let Ok(config) = serde_json::from_str(&content)
         {
             log::debug!("Loaded config from {}", user_path.display());
@@ -123,8 +225,7 @@ impl Config {
 
         // Try system config
         let system_path = system_dir.join("config.json");
-        if system_path.exists()
-            && let Ok(content) = fs::read_to_string(&system_path)
+        if let Some(content) = read_config_file(&system_path)
             && let Ok(config) = serde_json::from_str(&content)
         {
             log::debug!("Loaded config from {}", system_path.display());
@@ -137,7 +238,49 @@ impl Config {
         Ok(config)
     }
 
+    /// Write the built-in config files into the user config dir if missing
+    ///
+    /// Existing files are never overwritten. Returns the paths created.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init() -> Result<Vec<PathBuf>> {
+        Self::init_in(&user_config_dir()?)
+    }
+
+    /// Write the built-in config files into `dir` if missing
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_in(dir: &std::path::Path) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)?;
+
+        let mut created = Vec::new();
+        for (name, content) in [
+            ("config.json", BUILTIN_CONFIG),
+            ("tlds.json", BUILTIN_TLDS),
+            ("tlds.txt", BUILTIN_TLD_LIST),
+        ] {
+            let path = dir.join(name);
+            // create_new fails if the file exists, so customizations are kept
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(mut file) => {
+                    std::io::Write::write_all(&mut file, content.as_bytes())?;
+                    log::info!("Created {}", path.display());
+                    created.push(path);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    log::debug!("Keeping existing {}", path.display());
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(created)
+    }
+
     /// Save config to user config file
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn save(&self) -> Result<()> {
         let config_dir = user_config_dir()?;
         fs::create_dir_all(&config_dir)?;
@@ -161,8 +304,7 @@ pub fn load_tld_overrides() -> Result<TldOverrides> {
 
     // Merge local overrides on top (tlds.local.json)
     let local_path = user_dir.join("tlds.local.json");
-    if local_path.exists()
-        && let Ok(content) = fs::read_to_string(&local_path)
+    if let Some(content) = read_config_file(&local_path)
         && let Ok(local_overrides) = serde_json::from_str::<TldOverrides>(&content)
     {
         log::debug!(
@@ -185,8 +327,7 @@ fn load_base_tld_overrides(
 ) -> Result<TldOverrides> {
     // Try user config (downloaded)
     let user_path = user_dir.join("tlds.json");
-    if user_path.exists()
-        && let Ok(content) = fs::read_to_string(&user_path)
+    if let Some(content) = read_config_file(&user_path)
         && let Ok(overrides) = serde_json::from_str(&content)
     {
         log::debug!("Loaded TLD overrides from {}", user_path.display());
@@ -195,8 +336,7 @@ fn load_base_tld_overrides(
 
     // Try system config
     let system_path = system_dir.join("tlds.json");
-    if system_path.exists()
-        && let Ok(content) = fs::read_to_string(&system_path)
+    if let Some(content) = read_config_file(&system_path)
         && let Ok(overrides) = serde_json::from_str(&content)
     {
         log::debug!("Loaded TLD overrides from {}", system_path.display());
@@ -210,6 +350,7 @@ fn load_base_tld_overrides(
 }
 
 /// Save TLD overrides to user config file
+#[cfg(not(target_arch = "wasm32"))]
 pub fn save_tld_overrides(overrides: &TldOverrides) -> Result<()> {
     let config_dir = user_config_dir()?;
     fs::create_dir_all(&config_dir)?;
@@ -240,7 +381,32 @@ pub fn lookup_tld_override(overrides: &TldOverrides, domain: &str) -> Option<Url
     None
 }
 
+/// Load WHOIS servers with priority: user > system > builtin
+pub fn load_whois_servers() -> Result<WhoisServers> {
+    for dir in [user_config_dir()?, system_config_dir()] {
+        let path = dir.join("whois.json");
+        if let Some(content) = read_config_file(&path)
+            && let Ok(servers) = serde_json::from_str(&content)
+        {
+            log::debug!("Loaded WHOIS servers from {}", path.display());
+            return Ok(servers);
+        }
+    }
+
+    Ok(serde_json::from_str(BUILTIN_WHOIS)?)
+}
+
+/// Look up the WHOIS host for a domain, most specific suffix first
+pub fn lookup_whois_server<'a>(servers: &'a WhoisServers, domain: &str) -> Option<&'a str> {
+    let domain = crate::request::to_ascii_domain(domain)
+        .map_or_else(|_| domain.to_lowercase(), |d| d.to_lowercase());
+    let domain = domain.trim_end_matches('.');
+    let parts: Vec<&str> = domain.split('.').collect();
+    (0..parts.len()).find_map(|i| servers.get(&parts[i..].join(".")).map(String::as_str))
+}
+
 /// Update configuration files from GitHub
+#[cfg(not(target_arch = "wasm32"))]
 pub async fn update_configs() -> Result<UpdateResult> {
     let client = reqwest::Client::builder()
         .user_agent(concat!("rdap-rust/", env!("CARGO_PKG_VERSION")))
@@ -322,6 +488,33 @@ pub async fn update_configs() -> Result<UpdateResult> {
         Err(e) => result.tld_list_error = Some(format!("Request failed: {e}")),
     }
 
+    // Update public_suffix_list.dat
+    match client.get(PUBLIC_SUFFIX_LIST_UPDATE_URL).send().await {
+        Ok(response) if response.status().is_success() => match response.text().await {
+            Ok(content) => {
+                // Validate content (must have an ICANN section that parses)
+                let is_valid = content.contains("===BEGIN ICANN DOMAINS===")
+                    && content.parse::<publicsuffix::IcannList>().is_ok();
+                if is_valid {
+                    let path = config_dir.join("public_suffix_list.dat");
+                    fs::write(&path, &content)?;
+                    result.public_suffix_list_updated = true;
+                    log::info!("Updated public_suffix_list.dat");
+                } else {
+                    result.public_suffix_list_error =
+                        Some("Invalid public_suffix_list.dat format".to_string());
+                }
+            }
+            Err(e) => {
+                result.public_suffix_list_error = Some(format!("Failed to read response: {e}"));
+            }
+        },
+        Ok(response) => {
+            result.public_suffix_list_error = Some(format!("HTTP {}", response.status()));
+        }
+        Err(e) => result.public_suffix_list_error = Some(format!("Request failed: {e}")),
+    }
+
     Ok(result)
 }
 
@@ -334,6 +527,32 @@ pub struct UpdateResult {
     pub tlds_error: Option<String>,
     pub tld_list_updated: bool,
     pub tld_list_error: Option<String>,
+    pub public_suffix_list_updated: bool,
+    pub public_suffix_list_error: Option<String>,
+}
+
+/// ICANN section of the public suffix list, loaded on first use with
+/// priority: user > system > builtin
+///
+/// Private suffixes (e.g. `github.io`) are left out: names below them are
+/// still registered at the registry of their ICANN suffix.
+pub(crate) fn public_suffixes() -> &'static publicsuffix::IcannList {
+    static LIST: LazyLock<publicsuffix::IcannList> = LazyLock::new(|| {
+        let dirs = user_config_dir().into_iter().chain([system_config_dir()]);
+        for path in dirs.map(|dir| dir.join("public_suffix_list.dat")) {
+            if let Some(content) = read_config_file(&path)
+                && let Ok(list) = content.parse()
+            {
+                log::debug!("Loaded public suffix list from {}", path.display());
+                return list;
+            }
+        }
+        BUILTIN_PUBLIC_SUFFIX_LIST.parse().unwrap_or_else(|e| {
+            log::warn!("Invalid built-in public suffix list: {e}");
+            publicsuffix::IcannList::default()
+        })
+    });
+    &LIST
 }
 
 /// IANA TLD list - all valid top-level domains
@@ -349,18 +568,14 @@ impl TldList {
 
         // Try user config
         let user_path = user_dir.join("tlds.txt");
-        if user_path.exists()
-            && let Ok(content) = fs::read_to_string(&user_path)
-        {
+        if let Some(content) = read_config_file(&user_path) {
             log::debug!("Loaded TLD list from {}", user_path.display());
             return Ok(Self::parse(&content));
         }
 
         // Try system config
         let system_path = system_dir.join("tlds.txt");
-        if system_path.exists()
-            && let Ok(content) = fs::read_to_string(&system_path)
-        {
+        if let Some(content) = read_config_file(&system_path) {
             log::debug!("Loaded TLD list from {}", system_path.display());
             return Ok(Self::parse(&content));
         }
@@ -406,6 +621,26 @@ mod tests {
         assert!(!config.bootstrap.dns.is_empty());
     }
 
+    #[test]
+    fn test_cache_ttls() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "bootstrap": {"dns": "x", "asn": "x", "ipv4": "x", "ipv6": "x"},
+                "cache": {"ttl_seconds": 60, "bootstrap_ttl_seconds": 604800, "response_ttl_seconds": 300}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.cache.bootstrap_ttl(), Duration::from_hours(168));
+        assert_eq!(config.cache.response_ttl(), Duration::from_mins(5));
+
+        // Older configs only have the shared TTL
+        let config: Config = serde_json::from_str(BUILTIN_CONFIG).unwrap();
+        assert_eq!(config.cache.bootstrap_ttl(), Duration::from_hours(24));
+        assert_eq!(config.cache.response_ttl(), Duration::ZERO);
+        let json = serde_json::to_value(&config).unwrap();
+        assert!(json["cache"].get("bootstrap_ttl_seconds").is_none());
+    }
+
     #[test]
     fn test_builtin_tlds_valid() {
         let tlds: TldOverrides = serde_json::from_str(BUILTIN_TLDS).expect("Invalid builtin tlds");
@@ -432,6 +667,44 @@ mod tests {
         assert!(url.is_none());
     }
 
+    #[test]
+    fn test_lookup_whois_server() {
+        let servers: WhoisServers = serde_json::from_str(BUILTIN_WHOIS).unwrap();
+        assert_eq!(
+            lookup_whois_server(&servers, "example.co.jp"),
+            Some("whois.jprs.jp")
+        );
+        assert_eq!(
+            lookup_whois_server(&servers, "пример.рф."),
+            Some("whois.tcinet.ru")
+        );
+        assert_eq!(lookup_whois_server(&servers, "example.com"), None);
+    }
+
+    #[test]
+    fn test_init_creates_missing_files_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let custom = r#"{"bootstrap": {"dns": "x", "asn": "x", "ipv4": "x", "ipv6": "x"}, "cache": {"ttl_seconds": 1}}"#;
+        fs::write(dir.path().join("config.json"), custom).unwrap();
+
+        let created = Config::init_in(dir.path()).unwrap();
+        assert_eq!(
+            created,
+            vec![dir.path().join("tlds.json"), dir.path().join("tlds.txt")]
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("config.json")).unwrap(),
+            custom
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("tlds.json")).unwrap(),
+            BUILTIN_TLDS
+        );
+
+        // Running again creates nothing
+        assert!(Config::init_in(dir.path()).unwrap().is_empty());
+    }
+
     #[test]
     fn test_tld_list_valid() {
         let tld_list = TldList::parse(BUILTIN_TLD_LIST);
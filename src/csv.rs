@@ -0,0 +1,287 @@
+//! CSV output for spreadsheets: a fixed column set, one row per object
+//!
+//! Every object type is flattened onto the same columns, left blank where a
+//! field doesn't apply (e.g. `registrar` for an IP network). Search results
+//! give one row per result.
+
+use crate::models::{ContactSource, Domain, Entity, Event, Nameserver, RdapObject, abuse_contact};
+
+/// Column names, in order
+pub const CSV_COLUMNS: [&str; 9] = [
+    "query",
+    "type",
+    "name",
+    "status",
+    "created",
+    "expires",
+    "registrar",
+    "abuse-email",
+    "country",
+];
+
+/// The header row, without a line terminator
+pub fn csv_header() -> String {
+    CSV_COLUMNS.join(",")
+}
+
+/// One object flattened onto [`CSV_COLUMNS`]; blank fields don't apply
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CsvRow {
+    pub query: String,
+    /// Object class, e.g. `domain` or `ip network`
+    pub object_type: String,
+    pub name: String,
+    /// Status values joined with `, `
+    pub status: String,
+    /// Date of the `registration` event
+    pub created: String,
+    /// Date of the `expiration` event
+    pub expires: String,
+    pub registrar: String,
+    pub abuse_email: String,
+    pub country: String,
+}
+
+impl CsvRow {
+    /// The row as a CSV line (RFC 4180 quoting), without a line terminator
+    ///
+    /// Fields come from server data, so any a spreadsheet would read as a
+    /// formula (starting with `=`, `+`, `-` or `@`) are prefixed with `'`.
+    pub fn to_line(&self) -> String {
+        [
+            &self.query,
+            &self.object_type,
+            &self.name,
+            &self.status,
+            &self.created,
+            &self.expires,
+            &self.registrar,
+            &self.abuse_email,
+            &self.country,
+        ]
+        .map(|field| quote(&neutralize(field)))
+        .join(",")
+    }
+}
+
+/// Flatten a response into rows, one per object (one per result for searches)
+pub fn to_csv_rows(query: &str, obj: &RdapObject) -> Vec<CsvRow> {
+    let mut rows = match obj {
+        RdapObject::Domain(d) => vec![domain_row(d)],
+        RdapObject::Entity(e) => vec![entity_row(e)],
+        RdapObject::Nameserver(ns) => vec![nameserver_row(ns)],
+        RdapObject::Autnum(a) => vec![CsvRow {
+            object_type: "autnum".to_string(),
+            name: a.name.clone().unwrap_or_default(),
+            status: a.status.join(", "),
+            created: event_date(&a.events, "registration"),
+            expires: event_date(&a.events, "expiration"),
+            abuse_email: abuse_email(a.contact_entities()),
+            country: a.country.clone().unwrap_or_default(),
+            ..CsvRow::default()
+        }],
+        RdapObject::IpNetwork(ip) => vec![CsvRow {
+            object_type: "ip network".to_string(),
+            name: ip.name.clone().unwrap_or_default(),
+            status: ip.status.join(", "),
+            created: event_date(&ip.events, "registration"),
+            expires: event_date(&ip.events, "expiration"),
+            abuse_email: abuse_email(ip.contact_entities()),
+            country: ip.country.clone().unwrap_or_default(),
+            ..CsvRow::default()
+        }],
+        RdapObject::DomainSearch(r) => r.domains.iter().map(domain_row).collect(),
+        RdapObject::EntitySearch(r) => r.entities.iter().map(entity_row).collect(),
+        RdapObject::NameserverSearch(r) => r.nameservers.iter().map(nameserver_row).collect(),
+        RdapObject::Error(err) => vec![CsvRow {
+            object_type: "error".to_string(),
+            name: err.title.clone().unwrap_or_default(),
+            ..CsvRow::default()
+        }],
+        RdapObject::Help(_) => vec![CsvRow {
+            object_type: "help".to_string(),
+            ..CsvRow::default()
+        }],
+    };
+    for row in &mut rows {
+        row.query = query.to_string();
+    }
+    rows
+}
+
+fn domain_row(domain: &Domain) -> CsvRow {
+    let registrar = domain.registrar_info();
+    CsvRow {
+        object_type: "domain".to_string(),
+        name: domain.ascii_name().unwrap_or_default(),
+        status: domain.status.join(", "),
+        created: event_date(&domain.events, "registration"),
+        expires: domain.expiration().unwrap_or_default().to_string(),
+        registrar: registrar
+            .as_ref()
+            .and_then(|r| r.name.clone())
+            .unwrap_or_default(),
+        abuse_email: registrar
+            .and_then(|r| r.abuse_email)
+            .unwrap_or_else(|| abuse_email(domain.contact_entities())),
+        ..CsvRow::default()
+    }
+}
+
+fn entity_row(entity: &Entity) -> CsvRow {
+    CsvRow {
+        object_type: "entity".to_string(),
+        name: entity.display_name().unwrap_or_default().to_string(),
+        status: entity.status.join(", "),
+        created: event_date(&entity.events, "registration"),
+        expires: event_date(&entity.events, "expiration"),
+        abuse_email: abuse_email([entity]),
+        country: entity
+            .vcard
+            .as_ref()
+            .and_then(crate::models::VCard::address)
+            .map(|a| a.country)
+            .unwrap_or_default(),
+        ..CsvRow::default()
+    }
+}
+
+fn nameserver_row(nameserver: &Nameserver) -> CsvRow {
+    CsvRow {
+        object_type: "nameserver".to_string(),
+        name: nameserver.ldh_name.clone().unwrap_or_default(),
+        status: nameserver.status.join(", "),
+        created: event_date(&nameserver.events, "registration"),
+        expires: event_date(&nameserver.events, "expiration"),
+        abuse_email: abuse_email(nameserver.contact_entities()),
+        ..CsvRow::default()
+    }
+}
+
+fn event_date(events: &[Event], action: &str) -> String {
+    events
+        .iter()
+        .find(|e| e.action == action)
+        .map(|e| e.date.clone())
+        .unwrap_or_default()
+}
+
+/// Email of the [`abuse_contact`] among `entities` and their nested ones
+fn abuse_email<'a>(entities: impl IntoIterator<Item = &'a Entity>) -> String {
+    abuse_contact(entities, usize::MAX)
+        .and_then(|c| c.email)
+        .unwrap_or_default()
+}
+
+/// Prefix a field with `'` if it starts like a spreadsheet formula (`=`,
+/// `+`, `-`, `@`, tab or carriage return), per OWASP's CSV injection advice
+fn neutralize(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{field}").into()
+    } else {
+        field.into()
+    }
+}
+
+/// Quote a field if it holds a comma, quote or line break
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_row() {
+        let json = r#"{
+            "objectClassName": "domain",
+            "ldhName": "example.com",
+            "status": ["client transfer prohibited", "active"],
+            "events": [
+                {"eventAction": "registration", "eventDate": "1995-08-14T04:00:00Z"},
+                {"eventAction": "expiration", "eventDate": "2027-08-13T04:00:00Z"}
+            ],
+            "entities": [{
+                "objectClassName": "entity",
+                "roles": ["registrar"],
+                "vcardArray": ["vcard", [["fn", {}, "text", "Example Registrar, Inc."]]],
+                "entities": [{
+                    "objectClassName": "entity",
+                    "roles": ["abuse"],
+                    "vcardArray": ["vcard", [["email", {}, "text", "abuse@registrar.example"]]]
+                }]
+            }]
+        }"#;
+        let rows = to_csv_rows("example.com", &crate::parse_rdap(json).unwrap());
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            rows[0].to_line(),
+            "example.com,domain,example.com,\"client transfer prohibited, active\",\
+             1995-08-14T04:00:00Z,2027-08-13T04:00:00Z,\"Example Registrar, Inc.\",\
+             abuse@registrar.example,"
+        );
+    }
+
+    #[test]
+    fn test_ip_network_and_search_rows() {
+        let json = r#"{
+            "objectClassName": "ip network",
+            "name": "TEST-NET-1",
+            "country": "US",
+            "entities": [{
+                "objectClassName": "entity",
+                "roles": ["abuse", "technical"],
+                "vcardArray": ["vcard", [["email", {}, "text", "abuse@rir.example"]]]
+            }]
+        }"#;
+        let rows = to_csv_rows("192.0.2.1", &crate::parse_rdap(json).unwrap());
+        assert_eq!(
+            rows[0].to_line(),
+            "192.0.2.1,ip network,TEST-NET-1,,,,,abuse@rir.example,US"
+        );
+
+        let json = r#"{
+            "domainSearchResults": [
+                {"objectClassName": "domain", "ldhName": "a.example"},
+                {"objectClassName": "domain", "ldhName": "b.example", "status": ["active"]}
+            ]
+        }"#;
+        let rows = to_csv_rows("*.example", &crate::parse_rdap(json).unwrap());
+        let names: Vec<_> = rows.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["a.example", "b.example"]);
+        assert!(rows.iter().all(|r| r.query == "*.example"));
+    }
+
+    #[test]
+    fn test_header_and_quoting() {
+        assert_eq!(
+            csv_header(),
+            "query,type,name,status,created,expires,registrar,abuse-email,country"
+        );
+        assert_eq!(quote(r#"say "hi""#), r#""say ""hi""""#);
+        assert_eq!(quote("plain"), "plain");
+    }
+
+    #[test]
+    fn test_formula_fields_are_neutralized() {
+        let row = CsvRow {
+            query: "example.com".to_string(),
+            name: "=HYPERLINK(\"http://evil.example\",\"x\")".to_string(),
+            registrar: "+1".to_string(),
+            abuse_email: "@SUM(A1)".to_string(),
+            country: "-2".to_string(),
+            ..CsvRow::default()
+        };
+        assert_eq!(
+            row.to_line(),
+            "example.com,,\"'=HYPERLINK(\"\"http://evil.example\"\",\"\"x\"\")\",,,,'+1,'@SUM(A1),'-2"
+        );
+        assert_eq!(neutralize("\tcmd"), "'\tcmd");
+        assert_eq!(neutralize("a=b"), "a=b");
+    }
+}
@@ -0,0 +1,139 @@
+//! Field-level comparison of RDAP responses
+
+use serde_json::Value;
+use std::fmt;
+
+/// A single difference between two JSON documents
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldDiff {
+    /// Field present only in the first document
+    OnlyLeft { path: String, value: Value },
+    /// Field present only in the second document
+    OnlyRight { path: String, value: Value },
+    /// Field present in both with different values
+    Changed {
+        path: String,
+        left: Value,
+        right: Value,
+    },
+}
+
+impl FieldDiff {
+    /// JSON path of the differing field (e.g. `events[0].eventDate`)
+    pub fn path(&self) -> &str {
+        match self {
+            Self::OnlyLeft { path, .. }
+            | Self::OnlyRight { path, .. }
+            | Self::Changed { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for FieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OnlyLeft { path, value } => write!(f, "- {path}: {value}"),
+            Self::OnlyRight { path, value } => write!(f, "+ {path}: {value}"),
+            Self::Changed { path, left, right } => write!(f, "~ {path}: {left} -> {right}"),
+        }
+    }
+}
+
+/// Compare two JSON documents field by field
+///
+/// Objects are compared by key and arrays by index. `null` is treated the
+/// same as a missing field.
+pub fn diff_json(left: &Value, right: &Value) -> Vec<FieldDiff> {
+    let mut diffs = Vec::new();
+    diff_at("", left, right, &mut diffs);
+    diffs
+}
+
+fn diff_at(path: &str, left: &Value, right: &Value, diffs: &mut Vec<FieldDiff>) {
+    match (left, right) {
+        (Value::Object(l), Value::Object(r)) => {
+            let mut keys: Vec<&String> = l.keys().chain(r.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                diff_at(
+                    &child,
+                    l.get(key).unwrap_or(&Value::Null),
+                    r.get(key).unwrap_or(&Value::Null),
+                    diffs,
+                );
+            }
+        }
+        (Value::Array(l), Value::Array(r)) => {
+            for i in 0..l.len().max(r.len()) {
+                diff_at(
+                    &format!("{path}[{i}]"),
+                    l.get(i).unwrap_or(&Value::Null),
+                    r.get(i).unwrap_or(&Value::Null),
+                    diffs,
+                );
+            }
+        }
+        (Value::Null, Value::Null) => {}
+        (value, Value::Null) => diffs.push(FieldDiff::OnlyLeft {
+            path: path.to_string(),
+            value: value.clone(),
+        }),
+        (Value::Null, value) => diffs.push(FieldDiff::OnlyRight {
+            path: path.to_string(),
+            value: value.clone(),
+        }),
+        (l, r) if l != r => diffs.push(FieldDiff::Changed {
+            path: path.to_string(),
+            left: l.clone(),
+            right: r.clone(),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_json() {
+        let left = json!({
+            "ldhName": "example.com",
+            "port43": "whois.example.com",
+            "status": ["active"],
+            "events": [{"eventAction": "expiration", "eventDate": "2026-01-01"}]
+        });
+        let right = json!({
+            "ldhName": "example.com",
+            "port43": null,
+            "status": ["active", "client hold"],
+            "events": [{"eventAction": "expiration", "eventDate": "2027-01-01"}],
+            "lang": "en"
+        });
+
+        let diffs = diff_json(&left, &right);
+        let lines: Vec<String> = diffs.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                r#"~ events[0].eventDate: "2026-01-01" -> "2027-01-01""#,
+                r#"+ lang: "en""#,
+                r#"- port43: "whois.example.com""#,
+                r#"+ status[1]: "client hold""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_identical() {
+        let value = json!({"a": [1, {"b": null}]});
+        assert!(diff_json(&value, &value).is_empty());
+    }
+}
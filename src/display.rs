@@ -1,211 +1,615 @@
 //! Beautiful colored output for RDAP objects
 
 use crate::models::{
-    Autnum, Domain, DomainSearchResults, Entity, EntitySearchResults, ErrorResponse, HelpResponse,
-    IpNetwork, Nameserver, NameserverSearchResults, Notice, RdapObject,
+    Autnum, DEFAULT_MAX_ENTITY_DEPTH, Domain, DomainSearchResults, Entity, EntitySearchResults,
+    ErrorResponse, Event, HelpResponse, IpNetwork, NameOrder, Nameserver, NameserverSearchResults,
+    Notice, RdapObject, Redaction, abuse_contact, contact_by_role, select_notices, sort_by_action,
 };
+use crate::theme::Theme;
+use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
 use colored::Colorize;
 use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Default maximum width of free-form field values
+/// (see [`DisplayOptions::max_field_width`])
+pub const DEFAULT_MAX_FIELD_WIDTH: usize = 120;
+
+/// vCard properties already shown by name; others are listed in detail mode
+const SHOWN_VCARD_PROPERTIES: [&str; 7] = ["version", "fn", "org", "email", "tel", "adr", "kind"];
+
+/// Describe `date` relative to `now`, e.g. `12 years ago` or `in 3 months`
+///
+/// Months and years are approximated as 30 and 365 days.
+pub fn relative_date(date: DateTime<FixedOffset>, now: DateTime<Utc>) -> String {
+    let delta = date.with_timezone(&Utc) - now;
+    let abs = delta.abs();
+    let (count, unit) = match abs.num_days() {
+        days @ 365.. => (days / 365, "year"),
+        days @ 30.. => (days / 30, "month"),
+        days @ 1.. => (days, "day"),
+        _ if abs.num_hours() > 0 => (abs.num_hours(), "hour"),
+        _ if abs.num_minutes() > 0 => (abs.num_minutes(), "minute"),
+        _ => return "just now".to_string(),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    if delta < TimeDelta::zero() {
+        format!("{count} {unit}{plural} ago")
+    } else {
+        format!("in {count} {unit}{plural}")
+    }
+}
+
+/// Event date as shown, with the relative form first when enabled
+fn event_date_text(event: &Event, relative: bool, now: DateTime<Utc>) -> String {
+    event.parsed_date().filter(|_| relative).map_or_else(
+        || event.date.clone(),
+        |date| format!("{} ({})", relative_date(date, now), event.date),
+    )
+}
+
+/// Whether `c` is an invisible character that can reorder displayed text
+const fn is_bidi_control(c: char) -> bool {
+    matches!(
+        c,
+        '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}'
+    )
+}
+
+/// Replace bidi control characters with visible `\u{..}` escapes
+pub fn escape_bidi(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if is_bidi_control(c) {
+                format!("\\u{{{:04X}}}", u32::from(c))
+            } else {
+                c.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Scripts whose letters are commonly confused with one another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfusableScript {
+    Latin,
+    Greek,
+    Cyrillic,
+    Armenian,
+}
+
+const fn confusable_script(c: char) -> Option<ConfusableScript> {
+    match c {
+        'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}' => {
+            Some(ConfusableScript::Latin)
+        }
+        '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => Some(ConfusableScript::Greek),
+        '\u{0400}'..='\u{052F}' => Some(ConfusableScript::Cyrillic),
+        '\u{0530}'..='\u{058F}' => Some(ConfusableScript::Armenian),
+        _ => None,
+    }
+}
+
+/// Whether any word or label mixes Latin, Greek, Cyrillic or Armenian letters
+pub fn is_mixed_script(text: &str) -> bool {
+    text.split(|c: char| c == '.' || c.is_whitespace())
+        .any(|word| {
+            let mut scripts = word.chars().filter_map(confusable_script);
+            scripts
+                .next()
+                .is_some_and(|first| scripts.any(|script| script != first))
+        })
+}
+
+/// Name with bidi controls escaped and mixed-script names flagged
+pub fn safe_name(name: &str) -> String {
+    let escaped = escape_bidi(name);
+    if is_mixed_script(name) {
+        format!("{escaped} [mixed scripts]")
+    } else {
+        escaped
+    }
+}
+
+/// Name as it should be printed: [`safe_name`] unless
+/// [`DisplayOptions::safe_names`] is off
+fn shown_name(name: &str, opts: &DisplayOptions) -> String {
+    if opts.safe_names {
+        safe_name(name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Render callback for the Unicode part of domain names
+fn render_unicode_name(name: &str, opts: &DisplayOptions) -> String {
+    if opts.safe_names {
+        escape_bidi(name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Mixed-script warning for a domain's Unicode name
+fn domain_name_warning(domain: &Domain, opts: &DisplayOptions) -> &'static str {
+    let mixed = opts.safe_names && domain.unicode_name.as_deref().is_some_and(is_mixed_script);
+    if mixed { " [mixed scripts]" } else { "" }
+}
+
+/// Collapse whitespace and control characters and cut to `max_width` characters
+pub fn sanitize_field(value: &str, max_width: usize) -> String {
+    let flat = value
+        .split(|c: char| c.is_whitespace() || c.is_control())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if max_width == 0 || flat.chars().count() <= max_width {
+        return flat;
+    }
+    let mut truncated: String = flat.chars().take(max_width.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Text with ANSI escape sequences removed, so tests can assert on output
+/// whatever `colored`'s global settings are
+#[cfg(test)]
+pub(crate) fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+        } else if chars.next() == Some('[') {
+            // Skip parameters up to the final byte of the CSI sequence
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Output detail tiers, selected by repeated `-v` flags on the CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    /// Standard output
+    #[default]
+    Normal,
+    /// Same output as `Normal` (extra detail goes to the log)
+    Info,
+    /// Adds links, remarks, notices and redacted fields
+    Details,
+    /// Adds RDAP conformance and everything else available
+    Full,
+}
+
+impl Verbosity {
+    /// Map a `-v` occurrence count to a tier
+    pub const fn from_count(count: u8) -> Self {
+        match count {
+            0 => Self::Normal,
+            1 => Self::Info,
+            2 => Self::Details,
+            _ => Self::Full,
+        }
+    }
+
+    /// Whether links, remarks, notices and redacted fields are shown
+    pub fn show_details(self) -> bool {
+        self >= Self::Details
+    }
+
+    /// Whether RDAP conformance is shown
+    pub fn show_conformance(self) -> bool {
+        self >= Self::Full
+    }
+}
+
+impl From<bool> for Verbosity {
+    fn from(verbose: bool) -> Self {
+        if verbose { Self::Full } else { Self::Normal }
+    }
+}
+
+/// Settings for one call to a display function
+///
+/// Each caller passes its own, so output written for one purpose (such as a
+/// report file) never changes what another caller prints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayOptions {
+    /// Detail tier
+    pub verbosity: Verbosity,
+    /// Limit on how many characters of free-form values are printed
+    /// (0 = unlimited)
+    pub max_field_width: usize,
+    /// Escape bidi controls and flag mixed-script names (see [`safe_name`])
+    pub safe_names: bool,
+    /// Show event dates relative to now, e.g.
+    /// `in 3 months (2027-08-13T04:00:00Z)`
+    pub relative_dates: bool,
+    /// Output colors
+    pub theme: Theme,
+    /// Nested entities deeper than this many levels are not shown
+    pub max_entity_depth: usize,
+    /// Language asked for with `Accept-Language`; notices repeated in
+    /// several languages are shown in this one (falls back to the
+    /// response's own `lang`)
+    pub lang: Option<String>,
+}
+
+impl DisplayOptions {
+    /// Default options at the given verbosity tier
+    pub fn at(verbosity: Verbosity) -> Self {
+        Self {
+            verbosity,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self {
+            verbosity: Verbosity::Normal,
+            max_field_width: DEFAULT_MAX_FIELD_WIDTH,
+            safe_names: true,
+            relative_dates: false,
+            theme: Theme::DARK,
+            max_entity_depth: DEFAULT_MAX_ENTITY_DEPTH,
+            lang: None,
+        }
+    }
+}
 
 /// Display trait for RDAP objects
 pub trait RdapDisplay {
-    fn display(&self, verbose: bool);
+    /// Display the object, with all details when `verbose` is set
+    fn display(&self, verbose: bool) {
+        self.display_at(Verbosity::from(verbose));
+    }
+
+    /// Display the object at the given verbosity tier
+    fn display_at(&self, verbose: Verbosity) {
+        self.display_with(&DisplayOptions::at(verbose));
+    }
+
+    /// Display the object with the given options
+    fn display_with(&self, opts: &DisplayOptions) {
+        // Like `println!`, but a closed stdout (e.g. `| head`) isn't a panic
+        let _ = self.write_with(&mut io::stdout().lock(), opts);
+    }
+
+    /// Write the object to `w` at the given verbosity tier
+    fn write_at(&self, w: &mut dyn Write, verbose: Verbosity) -> io::Result<()> {
+        self.write_with(w, &DisplayOptions::at(verbose))
+    }
+
+    /// Write the object to `w` with the given options
+    fn write_with(&self, w: &mut dyn Write, opts: &DisplayOptions) -> io::Result<()>;
 }
 
 /// Extended display trait with query context
 pub trait RdapDisplayWithQuery {
-    fn display_with_query(&self, query: &str, verbose: bool);
+    fn display_with_query(&self, query: &str, verbose: bool) {
+        let _ = self.write_with_query(&mut io::stdout().lock(), query, verbose);
+    }
+
+    /// Write the object to `w`, preceded by contacts relevant to `query`
+    fn write_with_query(&self, w: &mut dyn Write, query: &str, verbose: bool) -> io::Result<()> {
+        self.write_for_query(w, query, &DisplayOptions::at(Verbosity::from(verbose)))
+    }
+
+    /// Write the object to `w` with the given options, preceded by contacts
+    /// relevant to `query`
+    fn write_for_query(
+        &self,
+        w: &mut dyn Write,
+        query: &str,
+        opts: &DisplayOptions,
+    ) -> io::Result<()>;
 }
 
 impl RdapDisplay for RdapObject {
-    fn display(&self, verbose: bool) {
+    fn write_with(&self, w: &mut dyn Write, opts: &DisplayOptions) -> io::Result<()> {
         match self {
-            Self::Domain(d) => d.display(verbose),
-            Self::Entity(e) => e.display(verbose),
-            Self::Nameserver(ns) => ns.display(verbose),
-            Self::Autnum(a) => a.display(verbose),
-            Self::IpNetwork(ip) => ip.display(verbose),
-            Self::Error(err) => err.display(verbose),
-            Self::DomainSearch(ds) => ds.display(verbose),
-            Self::EntitySearch(es) => es.display(verbose),
-            Self::NameserverSearch(ns) => ns.display(verbose),
-            Self::Help(h) => h.display(verbose),
+            Self::Domain(d) => d.write_with(w, opts),
+            Self::Entity(e) => e.write_with(w, opts),
+            Self::Nameserver(ns) => ns.write_with(w, opts),
+            Self::Autnum(a) => a.write_with(w, opts),
+            Self::IpNetwork(ip) => ip.write_with(w, opts),
+            Self::Error(err) => err.write_with(w, opts),
+            Self::DomainSearch(ds) => ds.write_with(w, opts),
+            Self::EntitySearch(es) => es.write_with(w, opts),
+            Self::NameserverSearch(ns) => ns.write_with(w, opts),
+            Self::Help(h) => h.write_with(w, opts),
         }
     }
 }
 
 impl RdapDisplayWithQuery for RdapObject {
-    fn display_with_query(&self, query: &str, verbose: bool) {
+    fn write_for_query(
+        &self,
+        w: &mut dyn Write,
+        query: &str,
+        opts: &DisplayOptions,
+    ) -> io::Result<()> {
         match self {
-            Self::Domain(d) => d.display_with_query(query, verbose),
-            Self::IpNetwork(ip) => ip.display_with_query(query, verbose),
-            Self::Autnum(a) => a.display_with_query(query, verbose),
-            _ => self.display(verbose),
+            Self::Domain(d) => d.write_for_query(w, query, opts),
+            Self::IpNetwork(ip) => ip.write_for_query(w, query, opts),
+            Self::Autnum(a) => a.write_for_query(w, query, opts),
+            _ => self.write_with(w, opts),
         }
     }
 }
 
-/// Extract contact email by role from entities
-fn find_contact_by_role(entities: &[Entity], role: &str) -> Option<String> {
-    for entity in entities {
-        // Check if this entity has the specified role
-        if entity
-            .roles
-            .iter()
-            .any(|r| r.to_lowercase() == role.to_lowercase())
-            && let Some(vcard) = &entity.vcard
-            && let Some(email) = vcard.email()
-        {
-            return Some(email.to_string());
-        }
-        // Recursively check nested entities
-        if let Some(email) = find_contact_by_role(&entity.entities, role) {
-            return Some(email);
-        }
-    }
-    None
+/// A registry number followed by its mnemonic, e.g. `13 (ECDSAP256SHA256)`
+fn number_with_name(number: u8, name: Option<&str>) -> String {
+    name.map_or_else(|| number.to_string(), |name| format!("{number} ({name})"))
 }
 
-/// Extract abuse contact email from entities
-fn find_abuse_contact(entities: &[Entity]) -> Option<String> {
-    find_contact_by_role(entities, "abuse")
+/// Human-readable label for an event action
+///
+/// Covers the event actions in the IANA RDAP JSON Values registry; unknown
+/// actions are returned unchanged.
+pub fn event_action_label(action: &str) -> &str {
+    match action {
+        "registration" => "Registration",
+        "reregistration" => "Reregistration",
+        "last changed" => "Last Changed",
+        "expiration" => "Expiration",
+        "deletion" => "Deletion",
+        "reinstantiation" => "Reinstantiation",
+        "transfer" | "transferred" => "Transferred",
+        "locked" => "Locked",
+        "unlocked" => "Unlocked",
+        "last update of RDAP database" => "Last Update",
+        "registrar expiration" => "Registrar Expiration",
+        "enum validation expiration" => "ENUM Validation Expiration",
+        a => a,
+    }
 }
 
 /// Display abuse contact for IP network
 /// Returns true if contact was printed
 pub fn display_ip_abuse_contact(ip: &IpNetwork, query: &str) -> bool {
+    write_ip_abuse_contact(
+        &mut io::stdout().lock(),
+        ip,
+        query,
+        &DisplayOptions::default(),
+    )
+    .unwrap_or(false)
+}
+
+/// Write abuse contact for IP network
+/// Returns true if contact was written
+pub fn write_ip_abuse_contact(
+    w: &mut dyn Write,
+    ip: &IpNetwork,
+    query: &str,
+    opts: &DisplayOptions,
+) -> io::Result<bool> {
     if query.is_empty() {
-        return false;
+        return Ok(false);
     }
-    if let Some(abuse_email) = find_abuse_contact(&ip.entities) {
-        println!(
+    if let Some(abuse_email) =
+        abuse_contact(&ip.entities, opts.max_entity_depth).and_then(|c| c.email)
+    {
+        writeln!(
+            w,
             "Abuse contact for `{}` is `{}`",
-            query.cyan().bold(),
-            abuse_email.yellow().bold()
-        );
-        println!();
-        return true;
+            opts.theme.value(query).bold(),
+            opts.theme.neutral(&abuse_email).bold()
+        )?;
+        writeln!(w)?;
+        return Ok(true);
     }
-    false
+    Ok(false)
 }
 
 /// Display abuse contact for AS number
 /// Returns true if contact was printed
 pub fn display_asn_abuse_contact(asn: &Autnum, query: &str) -> bool {
+    write_asn_abuse_contact(
+        &mut io::stdout().lock(),
+        asn,
+        query,
+        &DisplayOptions::default(),
+    )
+    .unwrap_or(false)
+}
+
+/// Write abuse contact for AS number
+/// Returns true if contact was written
+pub fn write_asn_abuse_contact(
+    w: &mut dyn Write,
+    asn: &Autnum,
+    query: &str,
+    opts: &DisplayOptions,
+) -> io::Result<bool> {
     if query.is_empty() {
-        return false;
+        return Ok(false);
     }
-    if let Some(abuse_email) = find_abuse_contact(&asn.entities) {
-        println!(
+    if let Some(abuse_email) =
+        abuse_contact(&asn.entities, opts.max_entity_depth).and_then(|c| c.email)
+    {
+        writeln!(
+            w,
             "Abuse contact for `{}` is `{}`",
-            query.cyan().bold(),
-            abuse_email.yellow().bold()
-        );
-        println!();
-        return true;
+            opts.theme.value(query).bold(),
+            opts.theme.neutral(&abuse_email).bold()
+        )?;
+        writeln!(w)?;
+        return Ok(true);
     }
-    false
+    Ok(false)
 }
 
 /// Display contact info for a domain (abuse for domains, admin/tech for TLDs)
 /// Returns true if any contact was printed
 pub fn display_domain_contacts(domain: &Domain, query: &str, is_tld: bool) -> bool {
+    write_domain_contacts(
+        &mut io::stdout().lock(),
+        domain,
+        query,
+        is_tld,
+        &DisplayOptions::default(),
+    )
+    .unwrap_or(false)
+}
+
+/// Write contact info for a domain (abuse for domains, admin/tech for TLDs)
+/// Returns true if any contact was written
+pub fn write_domain_contacts(
+    w: &mut dyn Write,
+    domain: &Domain,
+    query: &str,
+    is_tld: bool,
+    opts: &DisplayOptions,
+) -> io::Result<bool> {
     if query.is_empty() {
-        return false;
+        return Ok(false);
     }
 
     let mut printed = false;
 
     if is_tld {
         // TLD query - show administrative and technical contacts
-        if let Some(admin_email) = find_contact_by_role(&domain.entities, "administrative") {
-            println!(
+        if let Some(admin_email) =
+            contact_by_role(&domain.entities, "administrative", opts.max_entity_depth)
+                .and_then(|c| c.email)
+        {
+            writeln!(
+                w,
                 "Administrative contact for `{}` is `{}`",
-                query.cyan().bold(),
-                admin_email.yellow().bold()
-            );
+                opts.theme.value(query).bold(),
+                opts.theme.neutral(&admin_email).bold()
+            )?;
             printed = true;
         }
-        if let Some(tech_email) = find_contact_by_role(&domain.entities, "technical") {
+        if let Some(tech_email) =
+            contact_by_role(&domain.entities, "technical", opts.max_entity_depth)
+                .and_then(|c| c.email)
+        {
             if printed {
-                println!(); // Add blank line between contacts
+                writeln!(w)?; // Add blank line between contacts
             }
-            println!(
+            writeln!(
+                w,
                 "Technical contact for `{}` is `{}`",
-                query.cyan().bold(),
-                tech_email.yellow().bold()
-            );
+                opts.theme.value(query).bold(),
+                opts.theme.neutral(&tech_email).bold()
+            )?;
             printed = true;
         }
     } else {
-        // Domain query - show abuse contact
-        if let Some(abuse_email) = find_abuse_contact(&domain.entities) {
-            println!(
+        // Domain query - show the registrar abuse contact
+        if let Some(abuse) = domain.registrar_abuse() {
+            let contact = abuse
+                .email
+                .iter()
+                .chain(&abuse.phone)
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join("`, `");
+            writeln!(
+                w,
                 "Abuse contact for `{}` is `{}`",
-                query.cyan().bold(),
-                abuse_email.yellow().bold()
-            );
+                opts.theme.value(query).bold(),
+                opts.theme.neutral(&contact).bold()
+            )?;
+            printed = true;
+        } else if let Some(abuse_email) =
+            abuse_contact(&domain.entities, opts.max_entity_depth).and_then(|c| c.email)
+        {
+            writeln!(
+                w,
+                "Abuse contact for `{}` is `{}`",
+                opts.theme.value(query).bold(),
+                opts.theme.neutral(&abuse_email).bold()
+            )?;
             printed = true;
         }
     }
 
     if printed {
-        println!();
+        writeln!(w)?;
     }
-    printed
+    Ok(printed)
 }
 
 impl RdapDisplayWithQuery for Domain {
-    fn display_with_query(&self, query: &str, verbose: bool) {
+    fn write_for_query(
+        &self,
+        w: &mut dyn Write,
+        query: &str,
+        opts: &DisplayOptions,
+    ) -> io::Result<()> {
         // Display contact info first based on query type
         let is_tld = !query.is_empty() && !query.contains('.');
-        display_domain_contacts(self, query, is_tld);
+        write_domain_contacts(w, self, query, is_tld, opts)?;
 
         // Continue with regular display
-        self.display(verbose);
+        self.write_with(w, opts)
     }
 }
 
 impl RdapDisplay for Domain {
-    fn display(&self, verbose: bool) {
-        // Domain name
-        if let Some(name) = &self.ldh_name {
-            println!(
-                "{}: {}",
-                "Domain Name".bright_white().bold(),
-                name.bright_cyan().bold()
-            );
-        }
-
-        if let Some(unicode) = &self.unicode_name {
-            println!("{}: {}", "Unicode Name".white(), unicode.cyan());
+    fn write_with(&self, w: &mut dyn Write, opts: &DisplayOptions) -> io::Result<()> {
+        // Domain name, with the Unicode form alongside the A-label for IDNs
+        if let Some(name) =
+            self.combined_name_with(NameOrder::default(), |n| render_unicode_name(n, opts))
+        {
+            writeln!(
+                w,
+                "{}: {}{}",
+                opts.theme.label("Domain Name").bold(),
+                opts.theme.value(&name).bold(),
+                opts.theme.bad(domain_name_warning(self, opts))
+            )?;
         }
 
         if let Some(handle) = &self.handle {
-            println!("{}: {}", "Handle".white(), handle.normal());
+            writeln!(w, "{}: {}", opts.theme.label("Handle"), handle.normal())?;
         }
 
         // Object class
-        println!(
+        writeln!(
+            w,
             "{}: {}",
-            "Object Class".white(),
+            opts.theme.label("Object Class"),
             self.object_class_name.normal()
-        );
+        )?;
 
         // Port43
-        if let Some(port43) = &self.port43 {
-            println!("{}: {}", "Port43".white(), port43.normal());
+        write_port43(w, self.port43.as_deref(), opts)?;
+
+        // Reseller chain (who actually sold the domain)
+        if let (Some(reseller), Some(registrar)) = (self.reseller(), self.registrar()) {
+            writeln!(
+                w,
+                "{}: {} (via Registrar {})",
+                opts.theme.label("Reseller"),
+                opts.theme.value(&shown_name(
+                    reseller.display_name().unwrap_or("unknown"),
+                    opts
+                )),
+                shown_name(registrar.display_name().unwrap_or("unknown"), opts)
+            )?;
         }
 
         // Status
         if !self.status.is_empty() {
             for status in &self.status {
-                let color_status = match status.as_str() {
-                    s if s.contains("active") => status.green(),
-                    s if s.contains("delete") || s.contains("prohibit") => status.red(),
-                    _ => status.yellow(),
-                };
-                println!("{}: {}", "Status".white(), color_status);
+                writeln!(
+                    w,
+                    "{}: {}",
+                    opts.theme.label("Status"),
+                    opts.theme.status(status)
+                )?;
             }
         }
 
@@ -213,14 +617,19 @@ impl RdapDisplay for Domain {
         if !self.nameservers.is_empty() {
             for ns in &self.nameservers {
                 if let Some(name) = &ns.ldh_name {
-                    print!("{}: {}", "Nameserver".white(), name.cyan());
+                    write!(
+                        w,
+                        "{}: {}",
+                        opts.theme.label("Nameserver"),
+                        opts.theme.value(name)
+                    )?;
                     if let Some(ips) = &ns.ip_addresses {
                         let addrs: Vec<String> = ips.v4.iter().chain(&ips.v6).cloned().collect();
                         if !addrs.is_empty() {
-                            print!(" ({})", addrs.join(", ").dimmed());
+                            write!(w, " ({})", addrs.join(", ").dimmed())?;
                         }
                     }
-                    println!();
+                    writeln!(w)?;
                 }
             }
         }
@@ -228,131 +637,164 @@ impl RdapDisplay for Domain {
         // DNSSEC
         if let Some(dnssec) = &self.secure_dns {
             if let Some(zone_signed) = dnssec.zone_signed {
-                println!(
+                writeln!(
+                    w,
                     "{}: {}",
-                    "Zone Signed".white(),
+                    opts.theme.label("Zone Signed"),
                     if zone_signed {
-                        "yes".green()
+                        opts.theme.good("yes")
                     } else {
-                        "no".red()
+                        opts.theme.bad("no")
                     }
-                );
+                )?;
             }
             if let Some(delegation_signed) = dnssec.delegation_signed {
-                println!(
+                writeln!(
+                    w,
                     "{}: {}",
-                    "Delegation Signed".white(),
+                    opts.theme.label("Delegation Signed"),
                     if delegation_signed {
-                        "yes".green()
+                        opts.theme.good("yes")
                     } else {
-                        "no".red()
+                        opts.theme.bad("no")
                     }
-                );
+                )?;
             }
             for ds in &dnssec.ds_data {
                 if let Some(key_tag) = ds.key_tag {
-                    println!("{}: {}", "DS Key Tag".white(), key_tag.to_string().normal());
+                    writeln!(
+                        w,
+                        "{}: {}",
+                        opts.theme.label("DS Key Tag"),
+                        key_tag.to_string().normal()
+                    )?;
                 }
                 if let Some(algorithm) = ds.algorithm {
-                    println!(
+                    writeln!(
+                        w,
                         "{}: {}",
-                        "DS Algorithm".white(),
-                        algorithm.to_string().normal()
-                    );
+                        opts.theme.label("DS Algorithm"),
+                        number_with_name(algorithm, ds.algorithm_name()).normal()
+                    )?;
                 }
                 if let Some(digest_type) = ds.digest_type {
-                    println!(
+                    writeln!(
+                        w,
                         "{}: {}",
-                        "DS Digest Type".white(),
-                        digest_type.to_string().normal()
-                    );
+                        opts.theme.label("DS Digest Type"),
+                        number_with_name(digest_type, ds.digest_type_name()).normal()
+                    )?;
                 }
                 if let Some(digest) = &ds.digest {
-                    println!("{}: {}", "DS Digest".white(), digest.normal());
+                    writeln!(w, "{}: {}", opts.theme.label("DS Digest"), digest.normal())?;
                 }
             }
         }
 
         // Events
-        for event in &self.events {
-            let action = match event.action.as_str() {
-                "registration" => "Registration",
-                "expiration" => "Expiration",
-                "last changed" => "Last Changed",
-                "last update of RDAP database" => "Last Update",
-                "transferred" => "Transferred",
-                "locked" => "Locked",
-                "unlocked" => "Unlocked",
-                a => a,
-            };
-            println!("{}: {}", action.white(), event.date.normal());
-        }
+        write_events(w, &self.events, opts)?;
+        write_redactions(w, &self.redacted, opts)?;
 
         // Entities
         if !self.entities.is_empty() {
-            println!();
+            writeln!(w)?;
             for entity in &self.entities {
-                display_entity(entity, verbose);
+                write_entity(w, entity, opts)?;
             }
         }
 
         // Links
-        if verbose {
+        if opts.verbosity.show_details() {
             for link in &self.links {
                 if let Some(rel) = &link.rel {
-                    println!(
+                    writeln!(
+                        w,
                         "{}: {} ({})",
-                        "Link".white(),
-                        link.href.cyan(),
+                        opts.theme.label("Link"),
+                        opts.theme.link(&link.href),
                         rel.dimmed()
-                    );
+                    )?;
                 } else {
-                    println!("{}: {}", "Link".white(), link.href.cyan());
+                    writeln!(
+                        w,
+                        "{}: {}",
+                        opts.theme.label("Link"),
+                        opts.theme.link(&link.href)
+                    )?;
                 }
             }
         }
 
         // Remarks
-        if verbose {
+        if opts.verbosity.show_details() {
             for remark in &self.remarks {
-                display_notice(remark);
+                write_notice(w, remark, opts)?;
             }
         }
 
         // Notices
-        if verbose {
-            for notice in &self.notices {
-                display_notice(notice);
+        if opts.verbosity.show_details() {
+            for notice in
+                select_notices(&self.notices, opts.lang.as_deref().or(self.lang.as_deref()))
+            {
+                write_notice(w, notice, opts)?;
             }
         }
 
         // Conformance
-        if verbose && !self.conformance.is_empty() {
-            println!("\n{}", "RDAP Conformance:".dimmed());
+        if opts.verbosity.show_conformance() && !self.conformance.is_empty() {
+            writeln!(w, "\n{}", "RDAP Conformance:".dimmed())?;
             for conf in &self.conformance {
-                println!("  {}", conf.dimmed());
+                writeln!(w, "  {}", conf.dimmed())?;
             }
         }
+        Ok(())
     }
 }
 
-impl RdapDisplay for IpNetwork {
-    fn display(&self, verbose: bool) {
-        self.display_with_query("", verbose);
+impl RdapDisplayWithQuery for IpNetwork {
+    fn write_for_query(
+        &self,
+        w: &mut dyn Write,
+        _query: &str,
+        opts: &DisplayOptions,
+    ) -> io::Result<()> {
+        self.write_with(w, opts)
     }
 }
 
-impl RdapDisplayWithQuery for IpNetwork {
-    fn display_with_query(&self, _query: &str, verbose: bool) {
+impl RdapDisplay for IpNetwork {
+    fn write_with(&self, w: &mut dyn Write, opts: &DisplayOptions) -> io::Result<()> {
         // Note: Abuse contact is now displayed before "Query from" in main.rs
         // This method is kept for compatibility but contact display is handled separately
         if let Some(handle) = &self.handle {
-            println!("{}: {}", "Handle".white(), handle.normal());
+            writeln!(w, "{}: {}", opts.theme.label("Handle"), handle.normal())?;
         }
 
-        if let (Some(start), Some(end)) = (&self.start_address, &self.end_address) {
-            println!("{}: {}", "Start Address".white(), start.cyan());
-            println!("{}: {}", "End Address".white(), end.cyan());
+        // Prefer cidr0 prefixes over the raw address range
+        let cidrs = self.cidrs();
+        if !cidrs.is_empty() {
+            for cidr in &cidrs {
+                writeln!(
+                    w,
+                    "{}: {}",
+                    opts.theme.label("CIDR"),
+                    opts.theme.value(cidr)
+                )?;
+            }
+        } else if let (Some(start), Some(end)) = (&self.start_address, &self.end_address) {
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("Start Address"),
+                opts.theme.value(start)
+            )?;
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("End Address"),
+                opts.theme.value(end)
+            )?;
         }
 
         if let Some(ip_ver) = &self.ip_version {
@@ -361,367 +803,517 @@ impl RdapDisplayWithQuery for IpNetwork {
             } else {
                 format!("v{ip_ver}")
             };
-            println!("{}: {}", "IP Version".white(), version.normal());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("IP Version"),
+                version.normal()
+            )?;
         }
 
         if let Some(name) = &self.name {
-            println!("{}: {}", "Name".white(), name.cyan());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("Name"),
+                opts.theme.value(name)
+            )?;
         }
 
         if let Some(net_type) = &self.network_type {
-            println!("{}: {}", "Type".white(), net_type.normal());
+            writeln!(w, "{}: {}", opts.theme.label("Type"), net_type.normal())?;
         }
 
         if let Some(parent) = &self.parent_handle {
-            println!("{}: {}", "Parent Handle".white(), parent.normal());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("Parent Handle"),
+                parent.normal()
+            )?;
         }
 
         if let Some(country) = &self.country {
-            println!("{}: {}", "Country".white(), country.green());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("Country"),
+                opts.theme.value(country)
+            )?;
         }
 
         // Status
         for status in &self.status {
-            println!("{}: {}", "Status".white(), status.green());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("Status"),
+                opts.theme.status(status)
+            )?;
         }
 
         // Port43
-        if let Some(port43) = &self.port43 {
-            println!("{}: {}", "Port43".white(), port43.normal());
-        }
+        write_port43(w, self.port43.as_deref(), opts)?;
 
         // Events
-        for event in &self.events {
-            println!("{}: {}", event.action.white(), event.date.normal());
-        }
+        write_events(w, &self.events, opts)?;
+        write_redactions(w, &self.redacted, opts)?;
 
         // Entities - deduplicate and display
         if !self.entities.is_empty() {
-            println!();
-            let entity_map = collect_entities(&self.entities);
+            writeln!(w)?;
+            let entity_map = collect_entities(&self.entities, opts.max_entity_depth);
             let mut handles: Vec<_> = entity_map.keys().collect();
             handles.sort();
 
             for handle in handles {
                 if let Some(entity) = entity_map.get(handle) {
-                    display_entity(entity, verbose);
-                    println!();
+                    write_entity(w, entity, opts)?;
+                    writeln!(w)?;
                 }
             }
         }
 
         // Links, Remarks, Notices
-        if verbose {
+        if opts.verbosity.show_details() {
             for link in &self.links {
-                println!("{}: {}", "Link".white(), link.href.cyan());
+                writeln!(
+                    w,
+                    "{}: {}",
+                    opts.theme.label("Link"),
+                    opts.theme.link(&link.href)
+                )?;
             }
             for remark in &self.remarks {
-                display_notice(remark);
+                write_notice(w, remark, opts)?;
             }
-            for notice in &self.notices {
-                display_notice(notice);
+            for notice in
+                select_notices(&self.notices, opts.lang.as_deref().or(self.lang.as_deref()))
+            {
+                write_notice(w, notice, opts)?;
             }
         }
+        Ok(())
     }
 }
 
-impl RdapDisplay for Autnum {
-    fn display(&self, verbose: bool) {
-        self.display_with_query("", verbose);
+impl RdapDisplayWithQuery for Autnum {
+    fn write_for_query(
+        &self,
+        w: &mut dyn Write,
+        _query: &str,
+        opts: &DisplayOptions,
+    ) -> io::Result<()> {
+        self.write_with(w, opts)
     }
 }
 
-impl RdapDisplayWithQuery for Autnum {
-    fn display_with_query(&self, _query: &str, verbose: bool) {
+impl RdapDisplay for Autnum {
+    fn write_with(&self, w: &mut dyn Write, opts: &DisplayOptions) -> io::Result<()> {
         // Note: Abuse contact is now displayed before "Query from" in main.rs
         // This method is kept for compatibility but contact display is handled separately
 
         // AS Number
         if let (Some(start), Some(end)) = (self.start_autnum, self.end_autnum) {
             if start == end {
-                println!(
+                writeln!(
+                    w,
                     "{}: {}",
-                    "AS Number".white(),
-                    format!("AS{start}").cyan().bold()
-                );
+                    opts.theme.label("AS Number"),
+                    opts.theme.value(&format!("AS{start}")).bold()
+                )?;
             } else {
-                println!(
+                writeln!(
+                    w,
                     "{}: {}",
-                    "Start Autnum".white(),
-                    format!("AS{start}").cyan()
-                );
-                println!("{}: {}", "End Autnum".white(), format!("AS{end}").cyan());
+                    opts.theme.label("Start Autnum"),
+                    opts.theme.value(&format!("AS{start}"))
+                )?;
+                writeln!(
+                    w,
+                    "{}: {}",
+                    opts.theme.label("End Autnum"),
+                    opts.theme.value(&format!("AS{end}"))
+                )?;
             }
         }
 
         if let Some(name) = &self.name {
-            println!("{}: {}", "Name".white(), name.cyan());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("Name"),
+                opts.theme.value(name)
+            )?;
         }
 
         if let Some(handle) = &self.handle {
-            println!("{}: {}", "Handle".white(), handle.normal());
+            writeln!(w, "{}: {}", opts.theme.label("Handle"), handle.normal())?;
         }
 
         // Object class
         if let Some(class) = &self.object_class_name {
-            println!("{}: {}", "Object Class".white(), class.normal());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("Object Class"),
+                class.normal()
+            )?;
         }
 
         if let Some(as_type) = &self.as_type {
-            println!("{}: {}", "Type".white(), as_type.normal());
+            writeln!(w, "{}: {}", opts.theme.label("Type"), as_type.normal())?;
         }
 
         if let Some(country) = &self.country {
-            println!("{}: {}", "Country".white(), country.green());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("Country"),
+                opts.theme.value(country)
+            )?;
         }
 
         // Status
         for status in &self.status {
-            println!("{}: {}", "Status".white(), status.green());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("Status"),
+                opts.theme.status(status)
+            )?;
         }
 
         // Port43
-        if let Some(port43) = &self.port43 {
-            println!("{}: {}", "Port43".white(), port43.normal());
-        }
+        write_port43(w, self.port43.as_deref(), opts)?;
 
         // Events
-        for event in &self.events {
-            let action = match event.action.as_str() {
-                "registration" => "Registration",
-                "last changed" => "Last Changed",
-                a => a,
-            };
-            println!("{}: {}", action.white(), event.date.normal());
-        }
+        write_events(w, &self.events, opts)?;
+        write_redactions(w, &self.redacted, opts)?;
 
         // Entities - deduplicate and display
         if !self.entities.is_empty() {
-            println!();
-            let entity_map = collect_entities(&self.entities);
+            writeln!(w)?;
+            let entity_map = collect_entities(&self.entities, opts.max_entity_depth);
             let mut handles: Vec<_> = entity_map.keys().collect();
             handles.sort();
 
             for handle in handles {
                 if let Some(entity) = entity_map.get(handle) {
-                    display_entity(entity, verbose);
-                    println!();
+                    write_entity(w, entity, opts)?;
+                    writeln!(w)?;
                 }
             }
         }
 
         // Links, Remarks, Notices
-        if verbose {
+        if opts.verbosity.show_details() {
             for link in &self.links {
                 if let Some(rel) = &link.rel {
-                    println!(
+                    writeln!(
+                        w,
                         "{}: {} ({})",
-                        "Link".white(),
-                        link.href.cyan(),
+                        opts.theme.label("Link"),
+                        opts.theme.link(&link.href),
                         rel.dimmed()
-                    );
+                    )?;
                 } else {
-                    println!("{}: {}", "Link".white(), link.href.cyan());
+                    writeln!(
+                        w,
+                        "{}: {}",
+                        opts.theme.label("Link"),
+                        opts.theme.link(&link.href)
+                    )?;
                 }
             }
             for remark in &self.remarks {
-                display_notice(remark);
+                write_notice(w, remark, opts)?;
             }
-            for notice in &self.notices {
-                display_notice(notice);
+            for notice in
+                select_notices(&self.notices, opts.lang.as_deref().or(self.lang.as_deref()))
+            {
+                write_notice(w, notice, opts)?;
             }
         }
 
         // Conformance
-        if verbose && !self.conformance.is_empty() {
-            println!("\n{}", "RDAP Conformance:".dimmed());
+        if opts.verbosity.show_conformance() && !self.conformance.is_empty() {
+            writeln!(w, "\n{}", "RDAP Conformance:".dimmed())?;
             for conf in &self.conformance {
-                println!("  {}", conf.dimmed());
+                writeln!(w, "  {}", conf.dimmed())?;
             }
         }
+        Ok(())
     }
 }
 
 impl RdapDisplay for Entity {
-    fn display(&self, verbose: bool) {
+    fn write_with(&self, w: &mut dyn Write, opts: &DisplayOptions) -> io::Result<()> {
         // If this entity has nested entities, collect and deduplicate them
         if self.entities.is_empty() {
             // No nested entities, just display this one
-            display_entity(self, verbose);
+            write_entity(w, self, opts)?;
         } else {
             // Display main entity first
-            display_entity(self, verbose);
-            println!();
+            write_entity(w, self, opts)?;
+            writeln!(w)?;
 
             // Collect and display all nested entities
-            let entity_map = collect_entities(&self.entities);
+            let entity_map = collect_entities(&self.entities, opts.max_entity_depth);
             let mut handles: Vec<_> = entity_map.keys().collect();
             handles.sort();
 
             for handle in handles {
                 if let Some(entity) = entity_map.get(handle) {
-                    display_entity(entity, verbose);
-                    println!();
+                    write_entity(w, entity, opts)?;
+                    writeln!(w)?;
                 }
             }
         }
 
+        write_redactions(w, &self.redacted, opts)?;
+
         // Display notices (for top-level entity response)
-        if verbose && !self.notices.is_empty() {
-            for notice in &self.notices {
-                display_notice(notice);
+        if opts.verbosity.show_details() && !self.notices.is_empty() {
+            for notice in
+                select_notices(&self.notices, opts.lang.as_deref().or(self.lang.as_deref()))
+            {
+                write_notice(w, notice, opts)?;
             }
         }
+        Ok(())
     }
 }
 
 impl RdapDisplay for Nameserver {
-    fn display(&self, verbose: bool) {
+    fn write_with(&self, w: &mut dyn Write, opts: &DisplayOptions) -> io::Result<()> {
         if let Some(name) = &self.ldh_name {
-            println!("{}: {}", "Nameserver".white(), name.cyan().bold());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("Nameserver"),
+                opts.theme.value(name).bold()
+            )?;
         }
 
         if let Some(handle) = &self.handle {
-            println!("{}: {}", "Handle".white(), handle.normal());
+            writeln!(w, "{}: {}", opts.theme.label("Handle"), handle.normal())?;
         }
 
         if let Some(ips) = &self.ip_addresses {
             for ip in &ips.v4 {
-                println!("{}: {}", "IPv4".white(), ip.cyan());
+                writeln!(w, "{}: {}", opts.theme.label("IPv4"), opts.theme.value(ip))?;
             }
             for ip in &ips.v6 {
-                println!("{}: {}", "IPv6".white(), ip.cyan());
+                writeln!(w, "{}: {}", opts.theme.label("IPv6"), opts.theme.value(ip))?;
             }
         }
 
         // Status
         for status in &self.status {
-            println!("{}: {}", "Status".white(), status.green());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("Status"),
+                opts.theme.status(status)
+            )?;
         }
 
+        // Port43
+        write_port43(w, self.port43.as_deref(), opts)?;
+
         // Events
-        for event in &self.events {
-            println!("{}: {}", event.action.white(), event.date.normal());
-        }
+        write_events(w, &self.events, opts)?;
+        write_redactions(w, &self.redacted, opts)?;
 
         // Entities - deduplicate and display
         if !self.entities.is_empty() {
-            println!();
-            let entity_map = collect_entities(&self.entities);
+            writeln!(w)?;
+            let entity_map = collect_entities(&self.entities, opts.max_entity_depth);
             let mut handles: Vec<_> = entity_map.keys().collect();
             handles.sort();
 
             for handle in handles {
                 if let Some(entity) = entity_map.get(handle) {
-                    display_entity(entity, verbose);
-                    println!();
+                    write_entity(w, entity, opts)?;
+                    writeln!(w)?;
                 }
             }
         }
 
-        if verbose {
+        if opts.verbosity.show_details() {
             for link in &self.links {
-                println!("{}: {}", "Link".white(), link.href.cyan());
+                writeln!(
+                    w,
+                    "{}: {}",
+                    opts.theme.label("Link"),
+                    opts.theme.link(&link.href)
+                )?;
             }
             for remark in &self.remarks {
-                display_notice(remark);
+                write_notice(w, remark, opts)?;
             }
-            for notice in &self.notices {
-                display_notice(notice);
+            for notice in
+                select_notices(&self.notices, opts.lang.as_deref().or(self.lang.as_deref()))
+            {
+                write_notice(w, notice, opts)?;
             }
         }
+        Ok(())
     }
 }
 
 impl RdapDisplay for ErrorResponse {
-    fn display(&self, _verbose: bool) {
+    fn write_with(&self, w: &mut dyn Write, opts: &DisplayOptions) -> io::Result<()> {
         if let Some(code) = self.error_code {
-            println!("{}: {}", "Error Code".red(), code.to_string().red().bold());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.bad("Error Code"),
+                opts.theme.bad(&code.to_string()).bold()
+            )?;
         }
 
         if let Some(title) = &self.title {
-            println!("{}: {}", "Title".white(), title.normal());
+            writeln!(w, "{}: {}", opts.theme.label("Title"), title.normal())?;
         }
 
         for desc in &self.description {
-            println!("{}: {}", "Description".white(), desc.normal());
+            writeln!(w, "{}: {}", opts.theme.label("Description"), desc.normal())?;
+        }
+
+        for notice in select_notices(&self.notices, opts.lang.as_deref().or(self.lang.as_deref())) {
+            write_notice(w, notice, opts)?;
+        }
+        Ok(())
+    }
+}
+
+/// Number of results shown, with the total when the server reports a larger
+/// one (e.g. `50 of 1,200`)
+fn result_count(shown: usize, total: Option<u64>) -> String {
+    let shown = shown as u64;
+    match total {
+        Some(total) if total > shown => {
+            format!("{} of {}", group_thousands(shown), group_thousands(total))
         }
+        _ => group_thousands(shown),
+    }
+}
 
-        for notice in &self.notices {
-            display_notice(notice);
+/// Format a count with `,` between groups of three digits
+fn group_thousands(n: u64) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
         }
+        grouped.push(digit);
     }
+    grouped
 }
 
 impl RdapDisplay for DomainSearchResults {
-    fn display(&self, verbose: bool) {
-        println!(
+    fn write_with(&self, w: &mut dyn Write, opts: &DisplayOptions) -> io::Result<()> {
+        writeln!(
+            w,
             "{}: {}",
-            "Domain Search Results".white(),
-            self.domains.len().to_string().cyan()
-        );
-        println!();
+            opts.theme.label("Domain Search Results"),
+            opts.theme
+                .value(&result_count(self.domains.len(), self.total_count()))
+        )?;
+        writeln!(w)?;
+
+        // One line per domain unless verbose output was requested
+        if opts.verbosity == Verbosity::Normal {
+            for domain in &self.domains {
+                writeln!(
+                    w,
+                    "{}{}",
+                    domain.summary_with(|n| render_unicode_name(n, opts)),
+                    opts.theme.bad(domain_name_warning(domain, opts))
+                )?;
+            }
+            return Ok(());
+        }
 
         for (i, domain) in self.domains.iter().enumerate() {
             if i > 0 {
-                println!("\n{}", "---".dimmed());
+                writeln!(w, "\n{}", "---".dimmed())?;
             }
-            domain.display(verbose);
+            domain.write_with(w, opts)?;
         }
+        Ok(())
     }
 }
 
 impl RdapDisplay for EntitySearchResults {
-    fn display(&self, verbose: bool) {
-        println!(
+    fn write_with(&self, w: &mut dyn Write, opts: &DisplayOptions) -> io::Result<()> {
+        writeln!(
+            w,
             "{}: {}",
-            "Entity Search Results".white(),
-            self.entities.len().to_string().cyan()
-        );
-        println!();
+            opts.theme.label("Entity Search Results"),
+            opts.theme
+                .value(&result_count(self.entities.len(), self.total_count()))
+        )?;
+        writeln!(w)?;
 
         for (i, entity) in self.entities.iter().enumerate() {
             if i > 0 {
-                println!("\n{}", "---".dimmed());
+                writeln!(w, "\n{}", "---".dimmed())?;
             }
-            display_entity(entity, verbose);
+            write_entity(w, entity, opts)?;
         }
+        Ok(())
     }
 }
 
 impl RdapDisplay for NameserverSearchResults {
-    fn display(&self, verbose: bool) {
-        println!(
+    fn write_with(&self, w: &mut dyn Write, opts: &DisplayOptions) -> io::Result<()> {
+        writeln!(
+            w,
             "{}: {}",
-            "Nameserver Search Results".white(),
-            self.nameservers.len().to_string().cyan()
-        );
-        println!();
+            opts.theme.label("Nameserver Search Results"),
+            opts.theme
+                .value(&result_count(self.nameservers.len(), self.total_count()))
+        )?;
+        writeln!(w)?;
 
         for (i, ns) in self.nameservers.iter().enumerate() {
             if i > 0 {
-                println!("\n{}", "---".dimmed());
+                writeln!(w, "\n{}", "---".dimmed())?;
             }
-            ns.display(verbose);
+            ns.write_with(w, opts)?;
         }
+        Ok(())
     }
 }
 
 impl RdapDisplay for HelpResponse {
-    fn display(&self, _verbose: bool) {
-        for notice in &self.notices {
-            display_notice(notice);
+    fn write_with(&self, w: &mut dyn Write, opts: &DisplayOptions) -> io::Result<()> {
+        for notice in select_notices(&self.notices, opts.lang.as_deref().or(self.lang.as_deref())) {
+            write_notice(w, notice, opts)?;
         }
+        Ok(())
     }
 }
 
 // Helper functions
 
 /// Collect all entities with deduplication and merging
-fn collect_entities(entities: &[Entity]) -> HashMap<String, Entity> {
+fn collect_entities(entities: &[Entity], max_depth: usize) -> HashMap<String, Entity> {
     let mut entity_map: HashMap<String, Entity> = HashMap::new();
 
-    fn collect_recursive(entities: &[Entity], map: &mut HashMap<String, Entity>) {
+    fn collect_recursive(
+        entities: &[Entity],
+        map: &mut HashMap<String, Entity>,
+        depth: usize,
+        max_depth: usize,
+    ) {
+        if depth > max_depth {
+            return;
+        }
         for entity in entities {
             if let Some(handle) = &entity.handle {
                 // Merge with existing or insert new
@@ -764,95 +1356,173 @@ fn collect_entities(entities: &[Entity]) -> HashMap<String, Entity> {
                     .or_insert_with(|| entity.clone());
 
                 // Recursively collect nested entities
-                collect_recursive(&entity.entities, map);
+                collect_recursive(&entity.entities, map, depth + 1, max_depth);
             }
         }
     }
 
-    collect_recursive(entities, &mut entity_map);
+    collect_recursive(entities, &mut entity_map, 1, max_depth);
     entity_map
 }
 
-fn display_entity(entity: &Entity, verbose: bool) {
+fn write_entity(w: &mut dyn Write, entity: &Entity, opts: &DisplayOptions) -> io::Result<()> {
     // Entity header
     if let Some(handle) = &entity.handle {
-        println!("{}: {}", "Entity Handle".white(), handle.normal());
+        writeln!(
+            w,
+            "{}: {}",
+            opts.theme.label("Entity Handle"),
+            handle.normal()
+        )?;
     }
 
     if !entity.roles.is_empty() {
         for role in &entity.roles {
-            println!("{}: {}", "Role".white(), role.yellow());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("Role"),
+                opts.theme.neutral(role)
+            )?;
         }
     }
 
     // vCard information
     if let Some(vcard) = &entity.vcard {
         if let Some(name) = vcard.name() {
-            println!("{}: {}", "Name".white(), name.cyan());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("Name"),
+                opts.theme.value(&shown_name(name, opts))
+            )?;
         }
         if let Some(org) = vcard.org() {
-            println!("{}: {}", "Organization".white(), org.normal());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("Organization"),
+                shown_name(org, opts).normal()
+            )?;
         }
         if let Some(email) = vcard.email() {
-            println!("{}: {}", "Email".white(), email.cyan());
+            writeln!(
+                w,
+                "{}: {}",
+                opts.theme.label("Email"),
+                opts.theme.value(email)
+            )?;
         }
         if let Some(tel) = vcard.tel() {
-            println!("{}: {}", "Phone".white(), tel.normal());
+            writeln!(w, "{}: {}", opts.theme.label("Phone"), tel.normal())?;
         }
 
         if let Some(addr) = vcard.address() {
             // If there's a pre-formatted label, use that
             if let Some(label) = &addr.label {
-                println!("{}: {}", "Address".white(), label.normal());
+                writeln!(w, "{}: {}", opts.theme.label("Address"), label.normal())?;
             } else {
                 // Otherwise, show individual components
                 if !addr.po_box.is_empty() {
-                    println!("{}: {}", "PO Box".white(), addr.po_box.normal());
+                    writeln!(
+                        w,
+                        "{}: {}",
+                        opts.theme.label("PO Box"),
+                        addr.po_box.normal()
+                    )?;
                 }
                 if !addr.extended.is_empty() {
-                    println!("{}: {}", "Extended Address".white(), addr.extended.normal());
+                    writeln!(
+                        w,
+                        "{}: {}",
+                        opts.theme.label("Extended Address"),
+                        addr.extended.normal()
+                    )?;
                 }
                 if !addr.street.is_empty() {
-                    println!("{}: {}", "Street".white(), addr.street.normal());
+                    writeln!(
+                        w,
+                        "{}: {}",
+                        opts.theme.label("Street"),
+                        addr.street.normal()
+                    )?;
                 }
                 if !addr.locality.is_empty() {
-                    println!("{}: {}", "Locality".white(), addr.locality.normal());
+                    writeln!(
+                        w,
+                        "{}: {}",
+                        opts.theme.label("Locality"),
+                        addr.locality.normal()
+                    )?;
                 }
                 if !addr.region.is_empty() {
-                    println!("{}: {}", "Region".white(), addr.region.normal());
+                    writeln!(
+                        w,
+                        "{}: {}",
+                        opts.theme.label("Region"),
+                        addr.region.normal()
+                    )?;
                 }
                 if !addr.postal_code.is_empty() {
-                    println!("{}: {}", "Postal Code".white(), addr.postal_code.normal());
+                    writeln!(
+                        w,
+                        "{}: {}",
+                        opts.theme.label("Postal Code"),
+                        addr.postal_code.normal()
+                    )?;
                 }
                 if !addr.country.is_empty() {
-                    println!("{}: {}", "Country".white(), addr.country.green());
+                    writeln!(
+                        w,
+                        "{}: {}",
+                        opts.theme.label("Country"),
+                        opts.theme.value(&addr.country)
+                    )?;
                 }
             }
         }
     }
 
+    // Remaining vCard properties, sanitized so odd values can't flood the terminal
+    if opts.verbosity.show_details()
+        && let Some(vcard) = &entity.vcard
+    {
+        for prop in vcard.properties() {
+            if !SHOWN_VCARD_PROPERTIES.contains(&prop.name.as_str()) {
+                writeln!(
+                    w,
+                    "{}: {}",
+                    opts.theme.label(&prop.name),
+                    sanitize_field(&prop.value.to_text(), opts.max_field_width).normal()
+                )?;
+            }
+        }
+    }
+
     // Status
     for status in &entity.status {
-        println!("{}: {}", "Status".white(), status.green());
+        writeln!(
+            w,
+            "{}: {}",
+            opts.theme.label("Status"),
+            opts.theme.status(status)
+        )?;
     }
 
     // Port43
-    if let Some(port43) = &entity.port43 {
-        println!("{}: {}", "Port43".white(), port43.normal());
-    }
+    write_port43(w, entity.port43.as_deref(), opts)?;
 
     // Events
-    for event in &entity.events {
-        println!("{}: {}", event.action.white(), event.date.normal());
-    }
+    write_events(w, &entity.events, opts)?;
 
     // Public IDs
     for public_id in &entity.public_ids {
-        println!(
+        writeln!(
+            w,
             "{}: {}",
-            public_id.id_type.white(),
-            public_id.identifier.cyan()
-        );
+            opts.theme.label(&public_id.id_type),
+            opts.theme.value(&public_id.identifier)
+        )?;
     }
 
     // Links (always show self link)
@@ -860,38 +1530,406 @@ fn display_entity(entity: &Entity, verbose: bool) {
         if let Some(rel) = &link.rel
             && rel == "self"
         {
-            println!("{}: {}", "Link".dimmed(), link.href.cyan());
+            writeln!(w, "{}: {}", "Link".dimmed(), opts.theme.link(&link.href))?;
         }
     }
 
     // More details in verbose mode
-    if verbose {
+    if opts.verbosity.show_details() {
         for link in &entity.links {
             if let Some(rel) = &link.rel
                 && rel != "self"
             {
-                println!(
+                writeln!(
+                    w,
                     "{}: {} ({})",
-                    "Link".white(),
-                    link.href.cyan(),
+                    opts.theme.label("Link"),
+                    opts.theme.link(&link.href),
                     rel.dimmed()
-                );
+                )?;
             }
         }
         for remark in &entity.remarks {
-            display_notice(remark);
+            write_notice(w, remark, opts)?;
         }
     }
+    Ok(())
+}
+
+/// WHOIS hint for a `port43` value (e.g. "whois.verisign-grs.com (port 43)")
+pub fn port43_hint(port43: &str) -> String {
+    format!("{} (port 43)", port43.trim())
+}
+
+/// Display the `port43` WHOIS server, if any
+fn write_port43(w: &mut dyn Write, port43: Option<&str>, opts: &DisplayOptions) -> io::Result<()> {
+    if let Some(port43) = port43 {
+        writeln!(
+            w,
+            "{}: {}",
+            opts.theme.label("WHOIS"),
+            port43_hint(port43).normal()
+        )?;
+    }
+    Ok(())
 }
 
-fn display_notice(notice: &Notice) {
+/// Note which fields the server withheld, so they aren't mistaken for missing data
+fn write_redactions(
+    w: &mut dyn Write,
+    redacted: &[Redaction],
+    opts: &DisplayOptions,
+) -> io::Result<()> {
+    if !opts.verbosity.show_details() {
+        return Ok(());
+    }
+    let names: Vec<String> = redacted
+        .iter()
+        .filter_map(|r| r.name.text())
+        .map(|name| sanitize_field(&name.to_lowercase(), opts.max_field_width))
+        .collect();
+    if !names.is_empty() {
+        writeln!(
+            w,
+            "{}",
+            format!("(redacted: {})", names.join(", ")).dimmed()
+        )?;
+    }
+    Ok(())
+}
+
+/// Display events in canonical action order
+fn write_events(w: &mut dyn Write, events: &[Event], opts: &DisplayOptions) -> io::Result<()> {
+    let mut events = events.to_vec();
+    sort_by_action(&mut events);
+    let now = Utc::now();
+    for event in &events {
+        writeln!(
+            w,
+            "{}: {}",
+            opts.theme.label(event_action_label(&event.action)),
+            event_date_text(event, opts.relative_dates, now).normal()
+        )?;
+    }
+    Ok(())
+}
+
+fn write_notice(w: &mut dyn Write, notice: &Notice, opts: &DisplayOptions) -> io::Result<()> {
     if let Some(title) = &notice.title {
-        println!("{}: {}", "Notice".white(), title.cyan());
+        writeln!(
+            w,
+            "{}: {}",
+            opts.theme.label("Notice"),
+            opts.theme.value(title)
+        )?;
     }
     for desc in &notice.description {
-        println!("  {}", desc.normal());
+        writeln!(w, "  {}", desc.normal())?;
     }
     for link in &notice.links {
-        println!("  {}: {}", "Link".dimmed(), link.href.cyan());
+        writeln!(w, "  {}: {}", "Link".dimmed(), opts.theme.link(&link.href))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[1;31mred\x1b[0m text"), "red text");
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+
+    #[test]
+    fn test_relative_date() {
+        let now = "2026-03-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let at = |date: &str| relative_date(DateTime::parse_from_rfc3339(date).unwrap(), now);
+
+        assert_eq!(at("2014-01-15T00:00:00Z"), "12 years ago");
+        assert_eq!(at("2025-03-01T12:00:00Z"), "1 year ago");
+        assert_eq!(at("2026-06-10T00:00:00Z"), "in 3 months");
+        assert_eq!(at("2026-03-02T12:00:00Z"), "in 1 day");
+        assert_eq!(at("2026-03-01T09:30:00+00:00"), "2 hours ago");
+        assert_eq!(at("2026-03-01T13:00:00+01:00"), "just now");
+    }
+
+    #[test]
+    fn test_event_date_text() {
+        let now = "2026-03-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let event = |date: &str| Event {
+            action: "expiration".to_string(),
+            actor: None,
+            actor_entity: None,
+            date: date.to_string(),
+            links: vec![],
+        };
+
+        assert_eq!(
+            event_date_text(&event("2026-04-12T00:00:00Z"), true, now),
+            "in 1 month (2026-04-12T00:00:00Z)"
+        );
+        assert_eq!(
+            event_date_text(&event("2026-04-12T00:00:00Z"), false, now),
+            "2026-04-12T00:00:00Z"
+        );
+        // Unparseable dates are shown as given
+        assert_eq!(event_date_text(&event("soon"), true, now), "soon");
+    }
+
+    #[test]
+    fn test_event_action_labels() {
+        let registered = [
+            "registration",
+            "reregistration",
+            "last changed",
+            "expiration",
+            "deletion",
+            "reinstantiation",
+            "transfer",
+            "locked",
+            "unlocked",
+            "last update of RDAP database",
+            "registrar expiration",
+            "enum validation expiration",
+        ];
+        for action in registered {
+            assert_ne!(event_action_label(action), action, "{action}");
+        }
+        assert_eq!(event_action_label("custom action"), "custom action");
+    }
+
+    #[test]
+    fn test_port43_consistent_across_types() {
+        let objects = [
+            r#"{"objectClassName": "domain", "port43": "whois.example"}"#,
+            r#"{"objectClassName": "entity", "port43": "whois.example"}"#,
+            r#"{"objectClassName": "nameserver", "port43": "whois.example"}"#,
+            r#"{"objectClassName": "autnum", "port43": "whois.example"}"#,
+            r#"{"objectClassName": "ip network", "port43": "whois.example"}"#,
+        ];
+        for json in objects {
+            let obj = crate::parse_rdap(json).unwrap();
+            assert_eq!(
+                obj.port43().map(port43_hint).as_deref(),
+                Some("whois.example (port 43)"),
+                "{json}"
+            );
+        }
+        let help = crate::parse_rdap(r#"{"notices": [{"description": ["Help"]}]}"#).unwrap();
+        assert!(help.port43().is_none());
+    }
+
+    #[test]
+    fn test_nested_unknown_vcard_property_truncated() {
+        let json = serde_json::json!({
+            "objectClassName": "entity",
+            "vcardArray": ["vcard", [
+                ["version", {}, "text", "4.0"],
+                ["x-blob", {}, "unknown", [
+                    "head",
+                    ["level one\nwith newline", {"deep": {"deeper": ["x".repeat(500)]}}],
+                    42
+                ]]
+            ]]
+        });
+        let entity: Entity = serde_json::from_value(json).unwrap();
+        let vcard = entity.vcard.unwrap();
+        let text = vcard.properties()[1].value.to_text();
+        assert!(text.starts_with("head; level one\nwith newline; {\"deep\""));
+        assert!(text.ends_with("; 42"));
+
+        let shown = sanitize_field(&text, 40);
+        assert_eq!(shown.chars().count(), 40);
+        assert!(shown.starts_with("head; level one with newline;"));
+        assert!(shown.ends_with('…'));
+        assert_eq!(sanitize_field(&text, 0).len(), text.len());
+    }
+
+    #[test]
+    fn test_max_field_width_is_per_call() {
+        let json = serde_json::json!({
+            "objectClassName": "entity",
+            "vcardArray": ["vcard", [["x-note", {}, "text", "y".repeat(50)]]]
+        });
+        let entity: Entity = serde_json::from_value(json).unwrap();
+        let write = |opts: &DisplayOptions| {
+            let mut out = Vec::new();
+            entity.write_with(&mut out, opts).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let narrow = DisplayOptions {
+            max_field_width: 10,
+            ..DisplayOptions::at(Verbosity::Details)
+        };
+        assert!(write(&narrow).contains(&format!("{}…", "y".repeat(9))));
+        let full = write(&DisplayOptions::at(Verbosity::Details));
+        assert!(full.contains(&"y".repeat(50)));
+    }
+
+    #[test]
+    fn test_notices_follow_requested_lang() {
+        let json = serde_json::json!({
+            "objectClassName": "domain",
+            "ldhName": "example.de",
+            "lang": "en",
+            "notices": [
+                {"title": "Terms", "type": "terms", "lang": "en", "description": ["Terms of use"]},
+                {"title": "Nutzungsbedingungen", "type": "terms", "lang": "de", "description": ["Bedingungen"]}
+            ]
+        });
+        let domain: Domain = serde_json::from_value(json).unwrap();
+        let write = |opts: &DisplayOptions| {
+            let mut out = Vec::new();
+            domain.write_with(&mut out, opts).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let english = write(&DisplayOptions::at(Verbosity::Details));
+        assert!(english.contains("Terms of use"));
+        assert!(!english.contains("Bedingungen"));
+        let german = write(&DisplayOptions {
+            lang: Some("de-DE".to_string()),
+            ..DisplayOptions::at(Verbosity::Details)
+        });
+        assert!(german.contains("Bedingungen"));
+        assert!(!german.contains("Terms of use"));
+    }
+
+    #[test]
+    fn test_bidi_override_escaped() {
+        // A right-to-left override makes this render as "evilexe.jpg"
+        let attack = "evil\u{202E}gpj.exe";
+        assert_eq!(escape_bidi(attack), "evil\\u{202E}gpj.exe");
+        assert_eq!(safe_name(attack), "evil\\u{202E}gpj.exe");
+        assert!(!safe_name("Example \u{2067}Org\u{2069}").contains('\u{2067}'));
+    }
+
+    #[test]
+    fn test_mixed_script_confusable_flagged() {
+        // Cyrillic "а" (U+0430) in an otherwise Latin label
+        let spoof = "p\u{0430}ypal.com";
+        assert!(is_mixed_script(spoof));
+        assert_eq!(safe_name(spoof), format!("{spoof} [mixed scripts]"));
+
+        assert!(!is_mixed_script("paypal.com"));
+        assert!(!is_mixed_script("пример.рф"));
+        assert!(!is_mixed_script("müller.de"));
+        // Different scripts in separate labels or words are fine
+        assert!(!is_mixed_script("пример.com"));
+        assert!(!is_mixed_script("Example Пример"));
+    }
+
+    #[test]
+    fn test_raw_names_option() {
+        let json = serde_json::json!({
+            "objectClassName": "domain",
+            "ldhName": "xn--pypal-4ve.com",
+            "unicodeName": "p\u{0430}ypal.com"
+        });
+        let domain: Domain = serde_json::from_value(json).unwrap();
+        let write = |opts: &DisplayOptions| {
+            let mut out = Vec::new();
+            domain.write_with(&mut out, opts).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert!(write(&DisplayOptions::default()).contains("[mixed scripts]"));
+        let raw = DisplayOptions {
+            safe_names: false,
+            ..DisplayOptions::default()
+        };
+        assert!(!write(&raw).contains("[mixed scripts]"));
+    }
+
+    #[test]
+    fn test_domain_name_escapes_bidi_inside_isolate() {
+        let json = serde_json::json!({
+            "objectClassName": "domain",
+            "unicodeName": "a\u{202E}b.example"
+        });
+        let domain: Domain = serde_json::from_value(json).unwrap();
+        let name = domain
+            .combined_name_with(NameOrder::default(), escape_bidi)
+            .unwrap();
+        assert_eq!(name, "\u{2068}a\\u{202E}b.example\u{2069}");
+    }
+
+    #[test]
+    fn test_verbosity_tiers() {
+        assert_eq!(Verbosity::from_count(0), Verbosity::Normal);
+        assert!(!Verbosity::from_count(1).show_details());
+        assert!(Verbosity::from_count(2).show_details());
+        assert!(!Verbosity::from_count(2).show_conformance());
+        assert!(Verbosity::from_count(3).show_conformance());
+        assert_eq!(Verbosity::from_count(9), Verbosity::Full);
+        assert_eq!(Verbosity::from(true), Verbosity::Full);
+        assert_eq!(Verbosity::from(false), Verbosity::Normal);
+    }
+
+    #[test]
+    fn test_domain_search_compact_unless_verbose() {
+        let domain = |name: &str| {
+            serde_json::json!({
+                "objectClassName": "domain",
+                "ldhName": name,
+                "status": ["active"],
+                "events": [{"eventAction": "expiration", "eventDate": "2030-01-02T00:00:00Z"}]
+            })
+        };
+        let json = serde_json::json!({
+            "domainSearchResults": [domain("a.example"), domain("b.example")]
+        });
+        let results: DomainSearchResults = serde_json::from_value(json).unwrap();
+        let write = |verbosity| {
+            let mut out = Vec::new();
+            results.write_at(&mut out, verbosity).unwrap();
+            strip_ansi(&String::from_utf8(out).unwrap())
+        };
+
+        let compact = write(Verbosity::Normal);
+        assert!(compact.contains("a.example [active] expires 2030-01-02\n"));
+        assert!(compact.contains("b.example [active] expires 2030-01-02\n"));
+        assert!(!compact.contains("Domain Name:"));
+
+        let full = write(Verbosity::Details);
+        assert!(full.contains("Domain Name: a.example"));
+        assert!(full.contains("Domain Name: b.example"));
+        assert!(full.contains("Status: active"));
+        assert!(!full.contains("[active]"));
+    }
+
+    #[test]
+    fn test_redacted_fields_marked() {
+        let json = serde_json::json!({
+            "objectClassName": "domain",
+            "ldhName": "example.com",
+            "entities": [{
+                "objectClassName": "entity",
+                "roles": ["registrant"],
+                "vcardArray": ["vcard", [["version", {}, "text", "4.0"], ["email", {}, "text", ""]]]
+            }],
+            "redacted": [{
+                "name": {"type": "Registrant Email"},
+                "postPath": "$.entities[?(@.roles[0]=='registrant')].vcardArray[1][?(@[0]=='email')][3]",
+                "method": "emptyValue"
+            }]
+        });
+        let domain: Domain = serde_json::from_value(json).unwrap();
+        let mut out = Vec::new();
+        domain.write_at(&mut out, Verbosity::Details).unwrap();
+        let out = strip_ansi(&String::from_utf8(out).unwrap());
+        assert!(out.contains("(redacted: registrant email)"), "{out}");
+    }
+
+    #[test]
+    fn test_result_count_with_total() {
+        assert_eq!(result_count(50, Some(1200)), "50 of 1,200");
+        assert_eq!(result_count(3, Some(3)), "3");
+        assert_eq!(result_count(1000, None), "1,000");
+        assert_eq!(group_thousands(1_234_567), "1,234,567");
+        assert_eq!(group_thousands(999), "999");
     }
 }
@@ -12,12 +12,21 @@ pub enum RdapError {
     #[error("JSON parsing failed: {0}")]
     Json(#[from] serde_json::Error),
 
+    #[error("Failed to deserialize {object_class}: {message}")]
+    Deserialize {
+        object_class: String,
+        message: String,
+    },
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
     #[error("Bootstrap error: {0}")]
     Bootstrap(String),
 
+    #[error("Response is not RFC 9083 conformant; missing {}", missing.join(", "))]
+    NonConformant { missing: Vec<String> },
+
     #[error("Invalid query: {0}")]
     InvalidQuery(String),
 
@@ -27,6 +36,9 @@ pub enum RdapError {
     #[error("No working RDAP servers found")]
     NoWorkingServers,
 
+    #[error("No RDAP server found; this domain's registry only offers WHOIS at {host}")]
+    OnlyWhoisAvailable { host: String },
+
     #[error("RDAP server error {code}: {title}")]
     ServerError {
         code: u16,
@@ -37,6 +49,17 @@ pub enum RdapError {
     #[error("Invalid URL: {0}")]
     InvalidUrl(#[from] url::ParseError),
 
+    #[error("Too many redirects (last: {url})")]
+    TooManyRedirects { url: url::Url },
+
+    #[error(
+        "Rate limited by server{}",
+        retry_after.map(|d| format!(" (retry after {}s)", d.as_secs())).unwrap_or_default()
+    )]
+    RateLimited {
+        retry_after: Option<std::time::Duration>,
+    },
+
     #[error("Timeout")]
     Timeout,
 
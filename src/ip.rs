@@ -1,5 +1,7 @@
 //! IP address and CIDR utilities
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::error::{RdapError, Result};
 use std::net::IpAddr;
 
 /// Normalize an IP address string, handling shorthand formats
@@ -113,6 +115,27 @@ pub fn extract_ip_from_cidr(input: &str) -> String {
     }
 }
 
+/// Resolve a hostname with the system resolver, for IP queries given a name
+///
+/// IPv4 is preferred when the host has both kinds of address, matching what
+/// most users expect from e.g. `www.example.com`. Internationalized names are
+/// converted to A-labels first.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn resolve_host(host: &str) -> Result<IpAddr> {
+    let host = crate::request::to_ascii_domain(host.trim().trim_end_matches('.'))?;
+    let addrs: Vec<IpAddr> = tokio::net::lookup_host((host.as_ref(), 0))
+        .await
+        .map_err(|e| RdapError::InvalidQuery(format!("Cannot resolve {host}: {e}")))?
+        .map(|addr| addr.ip())
+        .collect();
+    addrs
+        .iter()
+        .find(|addr| addr.is_ipv4())
+        .or_else(|| addrs.first())
+        .copied()
+        .ok_or_else(|| RdapError::InvalidQuery(format!("{host} has no addresses")))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,4 +183,14 @@ mod tests {
         assert!(!is_cidr("8.8.8.8"));
         assert!(!is_cidr("example.com"));
     }
+
+    #[tokio::test]
+    async fn test_resolve_host() {
+        assert!(resolve_host("localhost.").await.unwrap().is_loopback());
+        assert_eq!(
+            resolve_host("192.0.2.1").await.unwrap(),
+            "192.0.2.1".parse::<IpAddr>().unwrap()
+        );
+        assert!(resolve_host("bad name!").await.is_err());
+    }
 }
@@ -13,19 +13,49 @@
 //! - Type-safe JSON parsing
 //! - Configurable bootstrap URLs
 //! - Custom TLD overrides
+//!
+//! ## Cargo features
+//!
+//! - `cli` (default): the `rdap` binary plus the [`display`], [`theme`],
+//!   [`report`] and [`whois`] output modules. Disable it with
+//!   `default-features = false` to use [`RdapClient`] without `clap`,
+//!   `colored` or `env_logger`.
+//! - `wasm`: required for `wasm32-unknown-unknown`, together with
+//!   `default-features = false`. The disk cache, config files and bootstrap
+//!   snapshot files are left out there; the built-in configuration is used.
+
+// Browser fetch futures hold JS handles and are never `Send`
+#![cfg_attr(target_arch = "wasm32", allow(clippy::future_not_send))]
+
+#[cfg(all(target_arch = "wasm32", not(feature = "wasm")))]
+compile_error!("building for wasm32 requires the `wasm` feature");
 
 pub mod bootstrap;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod cache;
 pub mod client;
 pub mod config;
+pub mod csv;
+pub mod diff;
+#[cfg(feature = "cli")]
 pub mod display;
 pub mod error;
 pub mod ip;
+pub mod metrics;
 pub mod models;
+pub mod net;
+#[cfg(feature = "cli")]
+pub mod report;
 pub mod request;
+pub(crate) mod span;
+#[cfg(feature = "cli")]
+pub mod theme;
+#[cfg(feature = "cli")]
+pub mod whois;
 
-pub use client::RdapClient;
+pub use bootstrap::{Bootstrap, BootstrapMap, BootstrapSnapshot};
+pub use client::{HttpVersion, RdapClient, parse_rdap};
 pub use config::Config;
 pub use error::{RdapError, Result};
 pub use models::*;
-pub use request::{QueryType, RdapRequest};
+pub use request::{EntitySearchQuery, QueryType, RdapRequest};
@@ -2,19 +2,37 @@
 
 use clap::{Parser, ValueEnum};
 use colored::Colorize;
-use rdap::{QueryType, RdapClient, RdapRequest, display::RdapDisplay};
+use rdap::client::RdapQueryResult;
+use rdap::config::TldList;
+use rdap::display::{DisplayOptions, RdapDisplay, Verbosity};
+use rdap::report::ReportFormat;
+use rdap::theme::Theme;
+use rdap::{Config, QueryType, RdapClient, RdapRequest};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{BufRead, IsTerminal, Write};
 use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use tracing::{field, span};
+
+/// Queries in flight at once when several are given on the command line
+const BATCH_CONCURRENCY: usize = 4;
 
 #[derive(Parser)]
 #[command(name = "rdap")]
 #[command(author, version, about = "Modern RDAP client", long_about = None)]
 struct Cli {
-    /// Query string (domain, IP, AS number, etc.)
-    query: Option<String>,
+    /// Query strings (domain, IP, AS number, etc.); several are looked up concurrently
+    query: Vec<String>,
 
     /// RDAP server URL (optional, uses bootstrap if not specified)
     #[arg(short, long)]
-    server: Option<String>,
+    server: Vec<String>,
+
+    /// Query the object from two servers (two -s values) and show a field-level diff
+    #[arg(long)]
+    compare_servers: bool,
 
     /// Query type (auto-detected if not specified)
     #[arg(short = 't', long)]
@@ -24,9 +42,65 @@ struct Cli {
     #[arg(short = 'f', long, default_value = "text")]
     format: OutputFormat,
 
-    /// Verbose output
-    #[arg(short, long)]
-    verbose: bool,
+    /// Verbose output (-v: info logs, -vv: links/remarks, -vvv: conformance and trace logs)
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// With --type ip, look up a hostname query's address and query that instead
+    #[arg(long)]
+    resolve: bool,
+
+    /// Follow IP referrals to the RIR managing transferred (ERX) space
+    #[arg(long)]
+    follow_ip_referral: bool,
+
+    /// Ask all five RIRs when IP bootstrap finds no delegation (legacy space)
+    #[arg(long)]
+    all_rirs: bool,
+
+    /// Print only the record's shareable URL (same as --format url)
+    #[arg(long)]
+    print_url: bool,
+
+    /// Print the unparsed response body from the server (same as --format raw)
+    #[arg(long)]
+    raw: bool,
+
+    /// Print the text display followed by the raw JSON body, for attaching to a ticket
+    #[arg(long)]
+    report: bool,
+
+    /// Write the report to a file instead (.html for HTML, Markdown otherwise)
+    #[arg(long, value_name = "PATH")]
+    report_file: Option<std::path::PathBuf>,
+
+    /// Print a ready-to-send abuse report for the object (same as --format abuse-report)
+    #[arg(long)]
+    abuse_report: bool,
+
+    /// Show event dates relative to now, e.g. "in 3 months", with the date in parentheses
+    #[arg(long)]
+    relative_dates: bool,
+
+    /// Ask servers for answers in this language (Accept-Language), e.g. "de" or "en-US"
+    #[arg(long)]
+    lang: Option<String>,
+
+    /// Print names as returned, without escaping bidi controls or flagging mixed scripts
+    #[arg(long)]
+    raw_names: bool,
+
+    /// Trace HTTP requests and raw responses (URL, headers, status, body)
+    #[arg(long)]
+    debug_http: bool,
+
+    /// Truncate long free-form values (e.g. unknown vCard fields) to this many characters (0 = unlimited)
+    #[arg(long, default_value_t = rdap::display::DEFAULT_MAX_FIELD_WIDTH)]
+    max_field_width: usize,
+
+    /// Keep at most this many levels of nested entities (deeper ones are dropped)
+    #[arg(long, default_value_t = rdap::models::DEFAULT_MAX_ENTITY_DEPTH)]
+    max_depth: usize,
 
     /// Timeout in seconds
     #[arg(long, default_value = "30")]
@@ -40,6 +114,30 @@ struct Cli {
     #[arg(long)]
     no_referral: bool,
 
+    /// Never contact the IANA bootstrap registries; queries then need -s/--server
+    #[arg(long)]
+    no_bootstrap: bool,
+
+    /// When a domain has no RDAP server, name its registry's WHOIS host instead
+    #[arg(long)]
+    suggest_whois: bool,
+
+    /// Always query the server instead of reusing cached responses
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Keep responses in ~/.cache/rdap/responses/ and reuse them in later runs
+    #[arg(long, conflicts_with = "no_cache")]
+    cache_responses: bool,
+
+    /// Disable colored output (also off when `NO_COLOR` is set or stdout is not a terminal)
+    #[arg(long)]
+    no_color: bool,
+
+    /// Color theme; colors from the config file's `theme` section apply on top
+    #[arg(long, value_parser = Theme::NAMES)]
+    theme: Option<String>,
+
     /// JSON output source: registry or registrar (default: registrar)
     #[arg(long, default_value = "registrar")]
     json_source: JsonSource,
@@ -47,6 +145,19 @@ struct Cli {
     /// Update configuration files from GitHub (config.json and tlds.json)
     #[arg(short = 'u', long)]
     update: bool,
+
+    /// Write default configuration files to the user config dir (never overwrites)
+    #[arg(long)]
+    init: bool,
+
+    /// Start an interactive session reading queries from stdin
+    #[arg(short = 'i', long)]
+    interactive: bool,
+
+    /// For batches (several queries, or -i on piped input): print only failed
+    /// queries, one per line with the reason
+    #[arg(long)]
+    only_errors: bool,
 }
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -65,6 +176,12 @@ enum QueryTypeArg {
     NameserverSearchByIp,
     EntitySearch,
     EntitySearchByHandle,
+    DomainsReverseByEntity,
+    DomainsReverseByEntityName,
+    DomainsReverseByEntityEmail,
+    NameserversReverseByEntity,
+    EntitiesReverseByEntity,
+    Url,
 }
 
 impl From<QueryTypeArg> for QueryType {
@@ -84,15 +201,35 @@ impl From<QueryTypeArg> for QueryType {
             QueryTypeArg::NameserverSearchByIp => Self::NameserverSearchByIp,
             QueryTypeArg::EntitySearch => Self::EntitySearch,
             QueryTypeArg::EntitySearchByHandle => Self::EntitySearchByHandle,
+            QueryTypeArg::DomainsReverseByEntity => Self::DomainsReverseByEntity,
+            QueryTypeArg::DomainsReverseByEntityName => Self::DomainsReverseByEntityName,
+            QueryTypeArg::DomainsReverseByEntityEmail => Self::DomainsReverseByEntityEmail,
+            QueryTypeArg::NameserversReverseByEntity => Self::NameserversReverseByEntity,
+            QueryTypeArg::EntitiesReverseByEntity => Self::EntitiesReverseByEntity,
+            QueryTypeArg::Url => Self::Url,
         }
     }
 }
 
-#[derive(Debug, Clone, ValueEnum)]
+#[derive(Debug, Clone, PartialEq, Eq, ValueEnum)]
 enum OutputFormat {
     Text,
     Json,
     JsonPretty,
+    /// Prometheus/OpenMetrics gauges (domain queries only)
+    Prometheus,
+    /// Only the record's shareable URL (its `self` link, or the URL queried)
+    Url,
+    /// Classic WHOIS-style `Key: Value` lines, never colored
+    Whois,
+    /// One CSV row per object under a header row, for spreadsheets
+    Csv,
+    /// Report email skeleton addressed to the object's abuse contact
+    AbuseReport,
+    /// Response body exactly as the server sent it (no cache, no referral)
+    Raw,
+    /// Text display plus the raw JSON body in Markdown fences
+    Report,
 }
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -105,35 +242,392 @@ enum JsonSource {
 
 #[tokio::main]
 async fn main() {
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
-
     let cli = Cli::parse();
 
+    let mut filter = log_filter(cli.verbose).to_string();
+    if cli.debug_http {
+        filter = format!("{filter},{}=trace", rdap::client::HTTP_LOG_TARGET);
+    }
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(filter))
+        .format(format_log_record)
+        .init();
+    // Only used to label log lines, so a second subscriber is not an error
+    let _ = tracing::subscriber::set_global_default(QuerySpans);
+
     if let Err(e) = run(cli).await {
         eprintln!("{} {}", "Error:".bright_red().bold(), e);
         process::exit(1);
     }
 }
 
-async fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
-    use colored::Colorize;
+/// `env_logger`'s default layout, plus the query span the line was logged in
+///
+/// Lines from concurrent queries then read e.g.
+/// `[... DEBUG rdap::bootstrap] [#2 example.com > bootstrap] Fetching ...`.
+fn format_log_record(
+    buf: &mut env_logger::fmt::Formatter,
+    record: &log::Record,
+) -> std::io::Result<()> {
+    let style = buf.default_level_style(record.level());
+    write!(
+        buf,
+        "[{} {style}{:<5}{style:#} {}] ",
+        buf.timestamp(),
+        record.level(),
+        record.target()
+    )?;
+    if let Some(label) = QuerySpans::current_label() {
+        write!(buf, "[{label}] ")?;
+    }
+    writeln!(buf, "{}", record.args())
+}
+
+/// Open spans by id: their query label, stage and reference count
+static SPANS: LazyLock<Mutex<HashMap<u64, SpanLabel>>> = LazyLock::new(Mutex::default);
+
+static NEXT_SPAN_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    /// Spans entered on this thread, innermost last
+    static ENTERED: RefCell<Vec<span::Id>> = const { RefCell::new(Vec::new()) };
+}
+
+struct SpanLabel {
+    /// `#<id> <query>` of the outermost `rdap_query` span this span is in
+    query: Option<String>,
+    stage: Option<String>,
+    refs: usize,
+}
+
+impl std::fmt::Display for SpanLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.query.as_deref().unwrap_or_default())?;
+        if let Some(stage) = &self.stage {
+            write!(f, " > {stage}")?;
+        }
+        Ok(())
+    }
+}
+
+/// `id`, `query` and `stage` fields of the library's spans
+#[derive(Default)]
+struct SpanFields {
+    id: Option<u64>,
+    query: Option<String>,
+    stage: Option<String>,
+}
 
+impl field::Visit for SpanFields {
+    fn record_u64(&mut self, field: &field::Field, value: u64) {
+        if field.name() == "id" {
+            self.id = Some(value);
+        }
+    }
+
+    fn record_str(&mut self, field: &field::Field, value: &str) {
+        match field.name() {
+            "query" => self.query = Some(value.to_string()),
+            "stage" => self.stage = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, _: &field::Field, _: &dyn std::fmt::Debug) {}
+}
+
+/// Minimal `tracing` subscriber tracking the library's `rdap_query` spans,
+/// so [`format_log_record`] can tell which query a `log` line belongs to
+struct QuerySpans;
+
+impl QuerySpans {
+    /// Label of the innermost query span entered on this thread, e.g.
+    /// `#2 example.com > bootstrap`
+    fn current_label() -> Option<String> {
+        let id = ENTERED.with(|entered| entered.borrow().last().cloned())?;
+        let spans = SPANS.lock().ok()?;
+        spans
+            .get(&id.into_u64())
+            .filter(|label| label.query.is_some())
+            .map(ToString::to_string)
+    }
+}
+
+impl tracing::Subscriber for QuerySpans {
+    fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
+        metadata.is_span()
+    }
+
+    fn new_span(&self, attrs: &span::Attributes<'_>) -> span::Id {
+        let mut fields = SpanFields::default();
+        attrs.record(&mut fields);
+        let parent = if attrs.is_contextual() {
+            ENTERED.with(|entered| entered.borrow().last().cloned())
+        } else {
+            attrs.parent().cloned()
+        };
+        let id = NEXT_SPAN_ID.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut spans) = SPANS.lock() {
+            // A query nested in another (e.g. a referral) keeps the outer label
+            let query = parent
+                .and_then(|parent| spans.get(&parent.into_u64()))
+                .and_then(|parent| parent.query.clone())
+                .or_else(|| Some(format!("#{} {}", fields.id?, fields.query?)));
+            spans.insert(
+                id,
+                SpanLabel {
+                    query,
+                    stage: fields.stage,
+                    refs: 1,
+                },
+            );
+        }
+        span::Id::from_u64(id)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, _: &tracing::Event<'_>) {}
+
+    fn enter(&self, span: &span::Id) {
+        ENTERED.with(|entered| entered.borrow_mut().push(span.clone()));
+    }
+
+    fn exit(&self, span: &span::Id) {
+        ENTERED.with(|entered| {
+            let mut entered = entered.borrow_mut();
+            if let Some(pos) = entered.iter().rposition(|id| id == span) {
+                entered.remove(pos);
+            }
+        });
+    }
+
+    fn clone_span(&self, span: &span::Id) -> span::Id {
+        if let Some(label) = SPANS
+            .lock()
+            .ok()
+            .as_mut()
+            .and_then(|spans| spans.get_mut(&span.into_u64()))
+        {
+            label.refs += 1;
+        }
+        span.clone()
+    }
+
+    fn try_close(&self, span: span::Id) -> bool {
+        let Ok(mut spans) = SPANS.lock() else {
+            return false;
+        };
+        let Some(label) = spans.get_mut(&span.into_u64()) else {
+            return false;
+        };
+        label.refs -= 1;
+        if label.refs > 0 {
+            return false;
+        }
+        spans.remove(&span.into_u64());
+        true
+    }
+}
+
+/// Map a `-v` occurrence count to the default `env_logger` filter
+const fn log_filter(verbose: u8) -> &'static str {
+    match verbose {
+        0 => "warn",
+        1 => "info",
+        2 => "debug",
+        _ => "trace",
+    }
+}
+
+async fn run(mut cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
     // Handle --update flag first
     if cli.update {
         return run_update().await;
     }
 
-    let mut query = cli.query.ok_or("Query is required")?;
+    if cli.init {
+        return run_init();
+    }
+
+    let display = DisplayOptions {
+        theme: load_theme(cli.theme.as_deref()),
+        max_field_width: cli.max_field_width,
+        safe_names: !cli.raw_names,
+        relative_dates: cli.relative_dates,
+        max_entity_depth: cli.max_depth,
+        lang: cli.lang.clone(),
+        ..DisplayOptions::default()
+    };
+    if cli.print_url {
+        cli.format = OutputFormat::Url;
+    }
+    if cli.raw {
+        cli.format = OutputFormat::Raw;
+    }
+    if cli.report || cli.report_file.is_some() {
+        cli.format = OutputFormat::Report;
+    }
+    if cli.abuse_report {
+        cli.format = OutputFormat::AbuseReport;
+    }
+    apply_color_choice(cli.no_color, &cli.format);
 
     // Load TLD list for query type detection
-    let tld_list = rdap::config::TldList::load().ok();
+    let tld_list = TldList::load().ok();
+
+    // Create client
+    let client = RdapClient::new()?
+        .with_timeout(std::time::Duration::from_secs(cli.timeout))
+        .with_follow_referral(!cli.no_referral)
+        .with_rir_fallback(cli.all_rirs)
+        .with_follow_ip_referral(cli.follow_ip_referral)
+        .with_cache_bypass(cli.no_cache)
+        .with_bootstrap_disabled(cli.no_bootstrap)
+        .with_max_entity_depth(cli.max_depth);
+    let client = match &cli.lang {
+        Some(lang) => client.with_accept_language(lang),
+        None => client,
+    };
+    let client = if cli.suggest_whois {
+        client.with_whois_fallback(rdap::config::load_whois_servers().unwrap_or_default())
+    } else {
+        client
+    };
+    // Bootstrap files always go to the disk cache; responses only on request
+    let max_cache_size = rdap::config::Config::load()
+        .unwrap_or_default()
+        .cache
+        .max_size();
+    let client = match rdap::cache::Cache::new() {
+        Ok(cache) => {
+            let cache = match max_cache_size {
+                Some(max_bytes) => cache.with_max_size(max_bytes),
+                None => cache,
+            };
+            if cli.cache_responses {
+                client.with_response_cache(cache)
+            } else {
+                client.with_bootstrap_cache(cache)
+            }
+        }
+        Err(e) => {
+            log::warn!("Disk cache unavailable: {e}");
+            client
+        }
+    };
+
+    let mut servers = cli
+        .server
+        .iter()
+        .map(|s| url::Url::parse(s))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if cli.compare_servers {
+        let [query] = <[String; 1]>::try_from(cli.query)
+            .map_err(|_| "--compare-servers requires exactly one query")?;
+        let [left, right] = <[url::Url; 2]>::try_from(servers)
+            .map_err(|_| "--compare-servers requires exactly two --server values")?;
+        return run_compare(
+            &client,
+            tld_list.as_ref(),
+            &query,
+            cli.query_type.map(Into::into),
+            &left,
+            &right,
+            &display.theme,
+        )
+        .await;
+    }
+
+    if servers.len() > 1 {
+        return Err("Multiple --server values require --compare-servers".into());
+    }
+    let server = servers.pop();
+
+    if cli.interactive {
+        let mut state = ReplState {
+            server,
+            format: cli.format,
+            verbose: cli.verbose,
+            json_source: cli.json_source,
+            only_errors: cli.only_errors,
+            no_color: cli.no_color,
+            display,
+        };
+        let stdin = std::io::BufReader::new(std::io::stdin());
+        let mut stdout = std::io::stdout();
+        return run_repl(stdin, &mut stdout, &client, tld_list.as_ref(), &mut state).await;
+    }
+
+    let mut queries = cli.query;
+    if queries.is_empty() {
+        return Err("Query is required".into());
+    }
+    let query_type = cli.query_type.map(Into::into);
+    if cli.resolve && query_type == Some(QueryType::Ip) {
+        for query in &mut queries {
+            if rdap::ip::normalize_ip(query).is_none() {
+                let addr = rdap::ip::resolve_host(query).await?;
+                eprintln!(
+                    "{} {query} resolves to {addr}",
+                    "Note:".bright_yellow().bold()
+                );
+                *query = addr.to_string();
+            }
+        }
+    }
+
+    if let [query] = queries.as_slice() {
+        return run_query(
+            &client,
+            tld_list.as_ref(),
+            query,
+            query_type,
+            server,
+            &cli.format,
+            &cli.json_source,
+            cli.verbose,
+            &display,
+            cli.report_file.as_deref(),
+        )
+        .await;
+    }
+
+    if matches!(cli.format, OutputFormat::Raw | OutputFormat::Report) {
+        return Err("Raw and report output take a single query".into());
+    }
+    run_batch(
+        &mut std::io::stdout(),
+        &client,
+        tld_list.as_ref(),
+        &queries,
+        query_type,
+        server.as_ref(),
+        &cli.format,
+        &cli.json_source,
+        cli.verbose,
+        &display,
+        cli.only_errors,
+    )
+    .await
+}
+
+/// Detect the query type (unless given) and normalize IP queries
+fn prepare_query(
+    query: &str,
+    query_type: Option<QueryType>,
+    tld_list: Option<&TldList>,
+) -> Result<(String, QueryType), Box<dyn std::error::Error>> {
+    let mut query = query.to_string();
 
     // Detect or use specified query type (BEFORE IP normalization)
-    let query_type = if let Some(qt) = cli.query_type {
-        qt.into()
+    let query_type = if let Some(qt) = query_type {
+        qt
     } else {
         RdapRequest::detect_type_with_tld_check(&query, |q| {
-            tld_list.as_ref().is_some_and(|list| list.is_tld(q))
+            tld_list.is_some_and(|list| list.is_tld(q))
         })?
     };
 
@@ -144,7 +638,60 @@ async fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         query = normalized;
     }
 
-    if cli.verbose {
+    Ok((query, query_type))
+}
+
+/// Query two servers for the same object and print where they differ
+async fn run_compare(
+    client: &RdapClient,
+    tld_list: Option<&TldList>,
+    query: &str,
+    query_type: Option<QueryType>,
+    left: &url::Url,
+    right: &url::Url,
+    theme: &Theme,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (query, query_type) = prepare_query(query, query_type, tld_list)?;
+    let request = RdapRequest::new(query_type, &query);
+
+    let diffs = client.compare_servers(&request, left, right).await?;
+
+    println!("{} {}", "---".bright_red(), left.as_str());
+    println!("{} {}", "+++".bright_green(), right.as_str());
+    println!();
+
+    if diffs.is_empty() {
+        println!("No differences");
+    }
+    for diff in &diffs {
+        let line = diff.to_string();
+        match diff {
+            rdap::diff::FieldDiff::OnlyLeft { .. } => println!("{}", theme.bad(&line)),
+            rdap::diff::FieldDiff::OnlyRight { .. } => println!("{}", theme.good(&line)),
+            rdap::diff::FieldDiff::Changed { .. } => println!("{}", theme.neutral(&line)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Run a single query and print the result
+#[allow(clippy::too_many_arguments)]
+async fn run_query(
+    client: &RdapClient,
+    tld_list: Option<&TldList>,
+    query: &str,
+    query_type: Option<QueryType>,
+    server: Option<url::Url>,
+    format: &OutputFormat,
+    json_source: &JsonSource,
+    verbose: u8,
+    display: &DisplayOptions,
+    report_file: Option<&std::path::Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (query, query_type) = prepare_query(query, query_type, tld_list)?;
+
+    if verbose > 0 {
         eprintln!("{} Query: {}", "→".bright_blue(), query.bright_white());
         eprintln!(
             "{} Type:  {}",
@@ -156,73 +703,346 @@ async fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
     // Build request
     let mut request = RdapRequest::new(query_type, &query);
 
-    if let Some(server_url) = cli.server {
-        let url = url::Url::parse(&server_url)?;
-        request = request.with_server(url);
-        if cli.verbose {
+    if let Some(url) = server {
+        if verbose > 0 {
             eprintln!(
                 "{} Server: {}",
                 "→".bright_blue(),
-                server_url.bright_green()
+                url.as_str().bright_green()
             );
         }
+        request = request.with_server(url);
     }
 
-    // Create client
-    let client = RdapClient::new()?
-        .with_timeout(std::time::Duration::from_secs(cli.timeout))
-        .with_follow_referral(!cli.no_referral);
-
     // Execute query
-    if cli.verbose {
+    if verbose > 0 {
         eprintln!("\n{} Querying RDAP server...\n", "⟳".bright_blue());
     }
 
-    // Use query_with_referral to get both registry and registrar data
-    let query_result = client.query_with_referral(&request).await?;
+    if *format == OutputFormat::Raw {
+        let raw = client.query_raw(&request).await?;
+        if verbose > 0 {
+            eprintln!("Query from {}", display.theme.link(raw.url.as_str()));
+        }
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(&raw.body)?;
+        stdout.flush()?;
+        if !raw.status.is_success() {
+            return Err(format!("HTTP error: {}", raw.status).into());
+        }
+        return Ok(());
+    }
+
+    if *format == OutputFormat::Report {
+        let report = client.query_report(&request).await?;
+        let opts = DisplayOptions {
+            verbosity: Verbosity::from_count(verbose),
+            ..display.clone()
+        };
+        if let Some(path) = report_file {
+            colored::control::set_override(false);
+            let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+            report.write(&mut file, ReportFormat::from_path(path), &opts)?;
+            file.flush()?;
+            eprintln!("Report written to {}", path.display());
+        } else {
+            report.write(&mut std::io::stdout().lock(), ReportFormat::Markdown, &opts)?;
+        }
+        return Ok(());
+    }
 
-    // Display result
-    match cli.format {
+    // Use query_with_referral to get both registry and registrar data;
+    // searches can take a while, so show they're in progress
+    let query_result = if is_search(query_type) {
+        with_spinner("Searching...", client.query_with_referral(&request)).await
+    } else {
+        client.query_with_referral(&request).await
+    }
+    .map_err(|e| registrable_domain_hint(&e, &query, query_type))?;
+
+    if *format == OutputFormat::Csv {
+        println!("{}", rdap::csv::csv_header());
+    }
+
+    print_result(
+        &query_result,
+        &query,
+        query_type,
+        format,
+        json_source,
+        verbose,
+        display,
+    )
+}
+
+/// Whether `query_type` is a search, whose result count isn't known up front
+const fn is_search(query_type: QueryType) -> bool {
+    matches!(
+        query_type,
+        QueryType::DomainSearch
+            | QueryType::DomainSearchByNameserver
+            | QueryType::DomainSearchByNameserverIp
+            | QueryType::NameserverSearch
+            | QueryType::NameserverSearchByIp
+            | QueryType::EntitySearch
+            | QueryType::EntitySearchByHandle
+    )
+}
+
+/// Await `future`, showing a spinner and `label` on stderr if it's a terminal
+async fn with_spinner<T>(label: &str, future: impl std::future::Future<Output = T>) -> T {
+    if !std::io::stderr().is_terminal() {
+        return future.await;
+    }
+    let spin = async {
+        let mut ticks = tokio::time::interval(std::time::Duration::from_millis(100));
+        for frame in ['|', '/', '-', '\\'].iter().cycle() {
+            ticks.tick().await;
+            eprint!("\r\x1b[K{frame} {label}");
+            let _ = std::io::stderr().flush();
+        }
+    };
+    let result = tokio::select! {
+        result = future => result,
+        () = spin => unreachable!("the spinner never stops"),
+    };
+    eprint!("\r\x1b[K");
+    result
+}
+
+/// Look up several queries concurrently, printing each under a `==> query <==` header
+///
+/// Types are detected per query unless given. Failures are reported under
+/// their header without stopping the rest, and make the run fail at the end.
+/// CSV output has no per-query headers, only one CSV header row up front.
+///
+/// With `only_errors`, successful queries print nothing and each failure is
+/// written to `out` as `query<TAB>reason`, as in [`run_repl`].
+#[allow(clippy::too_many_arguments)]
+async fn run_batch<W: Write>(
+    out: &mut W,
+    client: &RdapClient,
+    tld_list: Option<&TldList>,
+    queries: &[String],
+    query_type: Option<QueryType>,
+    server: Option<&url::Url>,
+    format: &OutputFormat,
+    json_source: &JsonSource,
+    verbose: u8,
+    display: &DisplayOptions,
+    only_errors: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let prepared: Vec<_> = queries
+        .iter()
+        .map(|query| prepare_query(query, query_type, tld_list).map_err(|e| e.to_string()))
+        .collect();
+    let requests: Vec<RdapRequest> = prepared
+        .iter()
+        .flatten()
+        .map(|(query, query_type)| {
+            let request = RdapRequest::new(*query_type, query);
+            match server {
+                Some(url) => request.with_server(url.clone()),
+                None => request,
+            }
+        })
+        .collect();
+    // A running count on a terminal while the batch is in flight
+    let show_progress = std::io::stderr().is_terminal();
+    let mut results = client
+        .query_batch_with_referral_and_progress(&requests, BATCH_CONCURRENCY, |done, total| {
+            if show_progress {
+                eprint!("\r\x1b[K{done}/{total} queried");
+                let _ = std::io::stderr().flush();
+            }
+        })
+        .await
+        .into_iter();
+    if show_progress && !requests.is_empty() {
+        eprint!("\r\x1b[K");
+    }
+
+    let mut failed = 0;
+    if only_errors {
+        for (query, prepared) in queries.iter().zip(prepared) {
+            let reason = match prepared {
+                Ok(_) => {
+                    let (_, result) = results.next().expect("one result per prepared query");
+                    failure_reason(result)
+                }
+                Err(e) => Some(e),
+            };
+            if let Some(reason) = reason {
+                failed += 1;
+                writeln!(out, "{query}\t{reason}")?;
+            }
+        }
+        if failed > 0 {
+            return Err(format!("{failed} of {} queries failed", queries.len()).into());
+        }
+        return Ok(());
+    }
+
+    let csv = *format == OutputFormat::Csv;
+    if csv {
+        println!("{}", rdap::csv::csv_header());
+    }
+    // JSON stays machine-readable: one document per line (NDJSON) for
+    // `json`, one array printed at the end for `json-pretty`
+    let json = matches!(format, OutputFormat::Json | OutputFormat::JsonPretty);
+    let mut pretty_results = Vec::new();
+
+    for (i, (query, prepared)) in queries.iter().zip(prepared).enumerate() {
+        if !csv && !json {
+            if i > 0 {
+                println!();
+            }
+            println!("{}", format!("==> {query} <==").bright_white().bold());
+        }
+        let outcome = prepared.and_then(|(query, query_type)| {
+            let (_, result) = results.next().expect("one result per prepared query");
+            let result = result.map_err(|e| registrable_domain_hint(&e, &query, query_type))?;
+            if *format == OutputFormat::JsonPretty {
+                pretty_results.push(selected_object(&result, json_source).clone());
+                return Ok(());
+            }
+            print_result(
+                &result,
+                &query,
+                query_type,
+                format,
+                json_source,
+                verbose,
+                display,
+            )
+            .map_err(|e| e.to_string())
+        });
+        if let Err(e) = outcome {
+            failed += 1;
+            eprintln!("{} {e}", "Error:".bright_red().bold());
+        }
+    }
+    if *format == OutputFormat::JsonPretty {
+        println!("{}", serde_json::to_string_pretty(&pretty_results)?);
+    }
+
+    if failed > 0 {
+        return Err(format!("{failed} of {} queries failed", queries.len()).into());
+    }
+    Ok(())
+}
+
+/// Point a domain query that wasn't found at its registrable domain, since
+/// registries only know names like `example.co.uk`, not `www.example.co.uk`
+fn registrable_domain_hint(e: &rdap::RdapError, query: &str, query_type: QueryType) -> String {
+    let name = query.trim_end_matches('.');
+    if matches!(e, rdap::RdapError::NotFound)
+        && query_type == QueryType::Domain
+        && let Some(domain) = rdap::request::registrable_domain(name)
+        && !domain.eq_ignore_ascii_case(&rdap::request::to_ascii_domain(name).unwrap_or_default())
+    {
+        return format!("{e}; try the registrable domain {domain}");
+    }
+    e.to_string()
+}
+
+/// Object rendered by the JSON, WHOIS and CSV formats: the registrar's
+/// answer or the registry's, as chosen by `--json-source`
+fn selected_object<'a>(
+    query_result: &'a RdapQueryResult,
+    json_source: &JsonSource,
+) -> &'a rdap::RdapObject {
+    match json_source {
+        JsonSource::Registry => &query_result.registry,
+        JsonSource::Registrar => query_result
+            .registrar
+            .as_ref()
+            .unwrap_or(&query_result.registry),
+    }
+}
+
+/// Print a query result in the requested output format
+fn print_result(
+    query_result: &RdapQueryResult,
+    query: &str,
+    query_type: QueryType,
+    format: &OutputFormat,
+    json_source: &JsonSource,
+    verbose: u8,
+    display: &DisplayOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let opts = DisplayOptions {
+        verbosity: Verbosity::from_count(verbose),
+        ..display.clone()
+    };
+
+    match format {
         OutputFormat::Text => {
             println!(); // Empty line before output
             // For domain queries with registrar data, show both
             if query_result.registrar.is_some() && query_type == QueryType::Domain {
                 // Show abuse contact from registrar first (if available)
                 if let Some(rdap::RdapObject::Domain(domain)) = &query_result.registrar {
-                    rdap::display::display_domain_contacts(domain, &query, false);
+                    let _ = rdap::display::write_domain_contacts(
+                        &mut std::io::stdout().lock(),
+                        domain,
+                        query,
+                        false,
+                        &opts,
+                    );
                 }
 
                 // Show registry server URL and data
-                println!("Query from {}", query_result.registry_url.as_str().cyan());
+                println!(
+                    "Query from {}",
+                    opts.theme.link(query_result.registry_url.as_str())
+                );
                 println!();
-                query_result.registry.display(cli.verbose);
+                query_result.registry.display_with(&opts);
 
                 // Show registrar server URL and data
                 if let Some(registrar) = &query_result.registrar {
                     println!();
                     if let Some(registrar_url) = &query_result.registrar_url {
-                        println!("Query from {}", registrar_url.as_str().green());
+                        println!("Query from {}", opts.theme.link(registrar_url.as_str()));
                         println!();
                     }
-                    registrar.display(cli.verbose);
+                    registrar.display_with(&opts);
                 }
             } else {
                 // Show contacts first based on query type
                 match &query_type {
                     QueryType::Tld => {
                         if let rdap::RdapObject::Domain(domain) = &query_result.registry {
-                            rdap::display::display_domain_contacts(domain, &query, true);
+                            let _ = rdap::display::write_domain_contacts(
+                                &mut std::io::stdout().lock(),
+                                domain,
+                                query,
+                                true,
+                                &opts,
+                            );
                         }
                     }
                     QueryType::Domain => {
                         if let rdap::RdapObject::Domain(domain) = &query_result.registry {
-                            rdap::display::display_domain_contacts(domain, &query, false);
+                            let _ = rdap::display::write_domain_contacts(
+                                &mut std::io::stdout().lock(),
+                                domain,
+                                query,
+                                false,
+                                &opts,
+                            );
                         }
                     }
                     QueryType::Ip => {
                         if let rdap::RdapObject::IpNetwork(ip) = &query_result.registry {
                             // For display, use the original query (including CIDR if specified)
-                            rdap::display::display_ip_abuse_contact(ip, &query);
+                            let _ = rdap::display::write_ip_abuse_contact(
+                                &mut std::io::stdout().lock(),
+                                ip,
+                                query,
+                                &opts,
+                            );
                         }
                     }
                     QueryType::Autnum => {
@@ -232,42 +1052,299 @@ async fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
                             format!("AS{query}")
                         };
                         if let rdap::RdapObject::Autnum(asn) = &query_result.registry {
-                            rdap::display::display_asn_abuse_contact(asn, &display_query);
+                            let _ = rdap::display::write_asn_abuse_contact(
+                                &mut std::io::stdout().lock(),
+                                asn,
+                                &display_query,
+                                &opts,
+                            );
                         }
                     }
                     _ => {}
                 }
 
                 // Show server URL
-                println!("Query from {}", query_result.registry_url.as_str().cyan());
+                println!(
+                    "Query from {}",
+                    opts.theme.link(query_result.registry_url.as_str())
+                );
                 println!();
 
                 // Display the main data
-                query_result.registry.display(cli.verbose);
+                query_result.registry.display_with(&opts);
             }
         }
         OutputFormat::Json => {
-            let result = match cli.json_source {
-                JsonSource::Registry => &query_result.registry,
-                JsonSource::Registrar => query_result
-                    .registrar
-                    .as_ref()
-                    .unwrap_or(&query_result.registry),
-            };
+            let result = selected_object(query_result, json_source);
             let json = serde_json::to_string(result)?;
             println!("{json}");
         }
         OutputFormat::JsonPretty => {
-            let result = match cli.json_source {
-                JsonSource::Registry => &query_result.registry,
-                JsonSource::Registrar => query_result
-                    .registrar
-                    .as_ref()
-                    .unwrap_or(&query_result.registry),
-            };
+            let result = selected_object(query_result, json_source);
             let json = serde_json::to_string_pretty(result)?;
             println!("{json}");
         }
+        OutputFormat::Prometheus => {
+            let rdap::RdapObject::Domain(domain) = &query_result.registry else {
+                return Err("Prometheus output is only supported for domain queries".into());
+            };
+            print!("{}", rdap::metrics::domain_metrics(domain));
+        }
+        OutputFormat::Url => println!("{}", query_result.source_url()),
+        // Printed by `run_query` before parsing
+        OutputFormat::Raw => unreachable!("raw output is not parsed"),
+        OutputFormat::Report => unreachable!("reports are written by `run_query`"),
+        OutputFormat::Whois => {
+            let result = selected_object(query_result, json_source);
+            for line in rdap::whois::to_whois_lines_with_depth(result, opts.max_entity_depth) {
+                println!("{line}");
+            }
+        }
+        OutputFormat::AbuseReport => {
+            // The registrar's answer usually carries the authoritative abuse contact
+            let report = query_result
+                .registrar
+                .as_ref()
+                .and_then(rdap::RdapObject::abuse_report)
+                .or_else(|| query_result.registry.abuse_report())
+                .ok_or_else(|| format!("No abuse contact found for {query}"))?;
+            print!("{}", report.to_text(query));
+        }
+        // The header row is printed by the caller, once per run
+        OutputFormat::Csv => {
+            let result = selected_object(query_result, json_source);
+            for row in rdap::csv::to_csv_rows(query, result) {
+                println!("{}", row.to_line());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The config file's theme, based on the `--theme` one if given
+fn load_theme(name: Option<&str>) -> Theme {
+    let mut theme_config = Config::load()
+        .ok()
+        .and_then(|config| config.theme)
+        .unwrap_or_default();
+    if let Some(name) = name {
+        theme_config.base = Some(name.to_string());
+    }
+    match Theme::from_config(&theme_config) {
+        Ok(theme) => theme,
+        Err(e) => {
+            log::warn!("{e}; ignoring the configured theme colors");
+            name.and_then(Theme::named).unwrap_or_default()
+        }
+    }
+}
+
+/// Force plain output for `--no-color` and the WHOIS format
+///
+/// Otherwise `colored` decides on its own: it honors `NO_COLOR` and
+/// `CLICOLOR_FORCE`, and disables styling when stdout is not a terminal.
+fn apply_color_choice(no_color: bool, format: &OutputFormat) {
+    if no_color || *format == OutputFormat::Whois {
+        colored::control::set_override(false);
+    } else {
+        colored::control::unset_override();
+    }
+}
+
+/// Settings that can be changed during an interactive session
+struct ReplState {
+    server: Option<url::Url>,
+    format: OutputFormat,
+    verbose: u8,
+    json_source: JsonSource,
+    /// Print only failed queries (batch mode)
+    only_errors: bool,
+    /// Colors were disabled with `--no-color`
+    no_color: bool,
+    /// Display settings from the command line
+    display: DisplayOptions,
+}
+
+impl ReplState {
+    /// Apply a `:command` line, returning false if the session should end
+    fn apply_command(&mut self, line: &str) -> Result<bool, String> {
+        let (command, arg) = line
+            .split_once(char::is_whitespace)
+            .map_or((line, ""), |(c, a)| (c, a.trim()));
+
+        match command {
+            ":quit" | ":exit" | ":q" => return Ok(false),
+            ":server" => {
+                if arg.is_empty() {
+                    self.server = None;
+                } else {
+                    self.server =
+                        Some(url::Url::parse(arg).map_err(|e| format!("Invalid URL: {e}"))?);
+                }
+            }
+            ":format" => {
+                self.format = OutputFormat::from_str(arg, true)
+                    .map_err(|_| format!("Unknown format: {arg}"))?;
+                apply_color_choice(self.no_color, &self.format);
+            }
+            ":verbose" => {
+                // Level 1 only raises the log level, so toggle to 2 (details)
+                self.verbose = if arg.is_empty() {
+                    if self.verbose == 0 { 2 } else { 0 }
+                } else {
+                    arg.parse()
+                        .map_err(|_| format!("Invalid verbosity level: {arg}"))?
+                };
+            }
+            ":help" => {
+                eprintln!("Commands:");
+                eprintln!("  :server <url>   Use a fixed RDAP server (no argument resets)");
+                eprintln!("  :format <fmt>   Set output format (text, json, json-pretty, whois)");
+                eprintln!("  :verbose [n]    Toggle detailed output, or set level 0-3");
+                eprintln!("  :quit           Exit the session");
+            }
+            _ => return Err(format!("Unknown command: {command}")),
+        }
+        Ok(true)
+    }
+}
+
+/// Read queries line by line, reusing the same client until EOF
+///
+/// With `only_errors`, successful queries print nothing and each failure is
+/// written to `out` as `query<TAB>reason`.
+async fn run_repl<R: BufRead, W: Write>(
+    reader: R,
+    out: &mut W,
+    client: &RdapClient,
+    tld_list: Option<&TldList>,
+    state: &mut ReplState,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut lines = reader.lines();
+    // Batch runs show a running count on a terminal instead of the prompt
+    let show_progress = state.only_errors && std::io::stderr().is_terminal();
+    let (mut done, mut failed) = (0usize, 0usize);
+
+    loop {
+        if !state.only_errors {
+            eprint!("{} ", "rdap>".bright_blue());
+            std::io::stderr().flush()?;
+        }
+
+        let Some(line) = lines.next() else {
+            if show_progress || !state.only_errors {
+                eprintln!();
+            }
+            break;
+        };
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with(':') {
+            match state.apply_command(line) {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(e) => eprintln!("{} {}", "Error:".bright_red().bold(), e),
+            }
+            continue;
+        }
+
+        if state.only_errors {
+            if let Some(reason) = query_failure(client, tld_list, line, state.server.clone()).await
+            {
+                failed += 1;
+                if show_progress {
+                    eprint!("\r\x1b[K");
+                }
+                writeln!(out, "{line}\t{reason}")?;
+            }
+            done += 1;
+            if show_progress {
+                eprint!("\r\x1b[K{done} queried, {failed} failed");
+                std::io::stderr().flush()?;
+            }
+            continue;
+        }
+
+        if let Err(e) = run_query(
+            client,
+            tld_list,
+            line,
+            None,
+            state.server.clone(),
+            &state.format,
+            &state.json_source,
+            state.verbose,
+            &state.display,
+            None,
+        )
+        .await
+        {
+            eprintln!("{} {}", "Error:".bright_red().bold(), e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Run a query without printing it, returning why it failed (if it did)
+async fn query_failure(
+    client: &RdapClient,
+    tld_list: Option<&TldList>,
+    query: &str,
+    server: Option<url::Url>,
+) -> Option<String> {
+    let (query, query_type) = match prepare_query(query, None, tld_list) {
+        Ok(prepared) => prepared,
+        Err(e) => return Some(e.to_string()),
+    };
+
+    let mut request = RdapRequest::new(query_type, &query);
+    if let Some(url) = server {
+        request = request.with_server(url);
+    }
+
+    failure_reason(client.query_with_referral(&request).await)
+}
+
+/// Why a query failed: a transport error or an RDAP error response
+fn failure_reason(result: rdap::Result<RdapQueryResult>) -> Option<String> {
+    match result {
+        Ok(RdapQueryResult {
+            registry: rdap::RdapObject::Error(err),
+            ..
+        }) => Some(format!(
+            "RDAP error {}: {}",
+            err.error_code
+                .map_or_else(|| "?".to_string(), |c| c.to_string()),
+            err.title.as_deref().unwrap_or("Unknown error")
+        )),
+        Ok(_) => None,
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+fn run_init() -> Result<(), Box<dyn std::error::Error>> {
+    let created = rdap::Config::init()?;
+
+    if created.is_empty() {
+        println!("{} Configuration files already exist", "✓".bright_green());
+    }
+    for path in &created {
+        println!("{} Created {}", "✓".bright_green(), path.display());
+    }
+
+    if let Ok(config_dir) = rdap::config::user_config_dir() {
+        println!();
+        println!(
+            "Config directory: {}",
+            config_dir.display().to_string().cyan()
+        );
     }
 
     Ok(())
@@ -304,6 +1381,13 @@ async fn run_update() -> Result<(), Box<dyn std::error::Error>> {
         println!("{} tlds.txt: {}", "✗".bright_red(), err);
     }
 
+    // Report public_suffix_list.dat status
+    if result.public_suffix_list_updated {
+        println!("{} public_suffix_list.dat updated", "✓".bright_green());
+    } else if let Some(err) = result.public_suffix_list_error {
+        println!("{} public_suffix_list.dat: {}", "✗".bright_red(), err);
+    }
+
     println!();
 
     // Show config directory
@@ -318,9 +1402,229 @@ async fn run_update() -> Result<(), Box<dyn std::error::Error>> {
         println!("  - Create config.local.json or tlds.local.json for local overrides");
     }
 
-    if result.config_updated || result.tlds_updated || result.tld_list_updated {
+    if result.config_updated
+        || result.tlds_updated
+        || result.tld_list_updated
+        || result.public_suffix_list_updated
+    {
         Ok(())
     } else {
         Err("Failed to update any configuration files".into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_state() -> ReplState {
+        ReplState {
+            server: None,
+            format: OutputFormat::Text,
+            verbose: 0,
+            json_source: JsonSource::Registrar,
+            only_errors: false,
+            no_color: false,
+            display: DisplayOptions::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repl_scripted_commands() {
+        let script = b":server https://rdap.example.net/\n:format json\n\n:verbose\n:bogus\n:quit\n:verbose\n";
+        let client = RdapClient::new().unwrap();
+        let mut state = default_state();
+
+        run_repl(&script[..], &mut std::io::sink(), &client, None, &mut state)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            state.server.as_ref().map(url::Url::as_str),
+            Some("https://rdap.example.net/")
+        );
+        assert_eq!(state.format, OutputFormat::Json);
+        // The trailing :verbose after :quit is never read
+        assert_eq!(state.verbose, 2);
+    }
+
+    #[tokio::test]
+    async fn test_only_errors_prints_failures() {
+        let mut server = mockito::Server::new_async().await;
+        let _ok = server
+            .mock("GET", "/domain/example.com")
+            .with_body(r#"{"objectClassName": "domain", "ldhName": "example.com"}"#)
+            .create_async()
+            .await;
+        let _missing = server
+            .mock("GET", "/domain/missing.com")
+            .with_status(404)
+            .create_async()
+            .await;
+        let _refused = server
+            .mock("GET", "/domain/refused.com")
+            .with_body(r#"{"errorCode": 403, "title": "Forbidden"}"#)
+            .create_async()
+            .await;
+
+        let script = "example.com\nmissing.com\nrefused.com\nexample.com\n";
+        let client = RdapClient::new().unwrap().with_follow_referral(false);
+        let mut state = default_state();
+        state.only_errors = true;
+        state.server = Some(url::Url::parse(&format!("{}/", server.url())).unwrap());
+
+        let mut out = Vec::new();
+        run_repl(script.as_bytes(), &mut out, &client, None, &mut state)
+            .await
+            .unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                format!("missing.com\t{}", rdap::RdapError::NotFound),
+                "refused.com\tRDAP error 403: Forbidden".to_string(),
+            ]
+        );
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_batch_only_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let _ok = server
+            .mock("GET", "/domain/example.com")
+            .with_body(r#"{"objectClassName": "domain", "ldhName": "example.com"}"#)
+            .create_async()
+            .await;
+        let _missing = server
+            .mock("GET", "/domain/missing.com")
+            .with_status(404)
+            .create_async()
+            .await;
+        let _refused = server
+            .mock("GET", "/domain/refused.com")
+            .with_body(r#"{"errorCode": 403, "title": "Forbidden"}"#)
+            .create_async()
+            .await;
+
+        let queries = [
+            "example.com",
+            "missing.com",
+            "https://example.com/x",
+            "refused.com",
+        ]
+        .map(String::from);
+        let client = RdapClient::new().unwrap().with_follow_referral(false);
+        let base = url::Url::parse(&format!("{}/", server.url())).unwrap();
+
+        let mut out = Vec::new();
+        let err = run_batch(
+            &mut out,
+            &client,
+            None,
+            &queries,
+            None,
+            Some(&base),
+            &OutputFormat::Text,
+            &JsonSource::Registrar,
+            0,
+            &DisplayOptions::default(),
+            true,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "3 of 4 queries failed");
+
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            format!("missing.com\t{}", rdap::RdapError::NotFound)
+        );
+        assert!(lines[1].starts_with("https://example.com/x\t"));
+        assert_eq!(lines[2], "refused.com\tRDAP error 403: Forbidden");
+        drop(server);
+    }
+
+    #[test]
+    fn test_repl_command_errors() {
+        let mut state = default_state();
+        assert!(state.apply_command(":format yaml").is_err());
+        assert!(state.apply_command(":server not a url").is_err());
+        assert_eq!(state.apply_command(":format json-pretty"), Ok(true));
+        assert_eq!(state.format, OutputFormat::JsonPretty);
+        assert_eq!(state.apply_command(":server"), Ok(true));
+        assert!(state.server.is_none());
+        assert_eq!(state.apply_command(":verbose 3"), Ok(true));
+        assert_eq!(state.verbose, 3);
+        assert_eq!(state.apply_command(":verbose"), Ok(true));
+        assert_eq!(state.verbose, 0);
+    }
+
+    #[test]
+    fn test_verbose_count_mapping() {
+        let cli = Cli::parse_from(["rdap", "-vv", "example.com"]);
+        assert_eq!(cli.verbose, 2);
+        assert_eq!(log_filter(cli.verbose), "debug");
+        assert!(Verbosity::from_count(cli.verbose).show_details());
+        assert!(!Verbosity::from_count(cli.verbose).show_conformance());
+
+        let cli = Cli::parse_from(["rdap", "--verbose", "example.com"]);
+        assert_eq!(cli.verbose, 1);
+        assert_eq!(log_filter(cli.verbose), "info");
+
+        assert_eq!(log_filter(0), "warn");
+        assert_eq!(log_filter(3), "trace");
+        assert_eq!(log_filter(7), "trace");
+    }
+
+    #[test]
+    fn test_query_span_labels() {
+        let _guard = tracing::subscriber::set_default(QuerySpans);
+        assert_eq!(QuerySpans::current_label(), None);
+
+        tracing::debug_span!("rdap_query", id = 7u64, query = "example.com").in_scope(|| {
+            assert_eq!(
+                QuerySpans::current_label().as_deref(),
+                Some("#7 example.com")
+            );
+            tracing::debug_span!("rdap_stage", stage = "bootstrap").in_scope(|| {
+                assert_eq!(
+                    QuerySpans::current_label().as_deref(),
+                    Some("#7 example.com > bootstrap")
+                );
+                tracing::debug_span!("rdap_query", id = 8u64, query = "example.com").in_scope(
+                    || {
+                        assert_eq!(
+                            QuerySpans::current_label().as_deref(),
+                            Some("#7 example.com")
+                        );
+                    },
+                );
+            });
+        });
+        assert_eq!(QuerySpans::current_label(), None);
+    }
+
+    #[test]
+    fn test_registrable_domain_hint() {
+        let hint =
+            |query| registrable_domain_hint(&rdap::RdapError::NotFound, query, QueryType::Domain);
+        assert_eq!(
+            hint("www.example.co.uk"),
+            "Object not found (404); try the registrable domain example.co.uk"
+        );
+        assert_eq!(hint("example.co.uk."), "Object not found (404)");
+        assert_eq!(
+            registrable_domain_hint(
+                &rdap::RdapError::Timeout,
+                "www.example.com",
+                QueryType::Domain
+            ),
+            "Timeout"
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Prometheus/OpenMetrics text output for RDAP objects
+
+use crate::models::Domain;
+use std::fmt::Write;
+
+/// Render gauges for a domain in the Prometheus text exposition format
+///
+/// Emits one line per available timestamp (registration, last changed,
+/// expiration) as Unix seconds, plus a DNSSEC gauge (1 if the delegation
+/// is signed, 0 otherwise).
+pub fn domain_metrics(domain: &Domain) -> String {
+    let name = domain.ascii_name().unwrap_or_default().to_lowercase();
+    let labels = format!("domain=\"{}\"", escape_label(&name));
+    let mut out = String::new();
+
+    let timestamps = [
+        (
+            "rdap_domain_registration_seconds",
+            "Domain registration time as a Unix timestamp",
+            "registration",
+        ),
+        (
+            "rdap_domain_last_changed_seconds",
+            "Domain last changed time as a Unix timestamp",
+            "last changed",
+        ),
+        (
+            "rdap_domain_expiry_seconds",
+            "Domain expiration time as a Unix timestamp",
+            "expiration",
+        ),
+    ];
+
+    for (metric, help, action) in timestamps {
+        if let Some(date) = domain
+            .events
+            .iter()
+            .find(|e| e.action == action)
+            .and_then(crate::models::Event::parsed_date)
+        {
+            let _ = writeln!(out, "# HELP {metric} {help}");
+            let _ = writeln!(out, "# TYPE {metric} gauge");
+            let _ = writeln!(out, "{metric}{{{labels}}} {}", date.timestamp());
+        }
+    }
+
+    let signed = domain
+        .secure_dns
+        .as_ref()
+        .and_then(|d| d.delegation_signed)
+        .unwrap_or(false);
+    let _ = writeln!(
+        out,
+        "# HELP rdap_domain_dnssec Whether the domain delegation is DNSSEC signed"
+    );
+    let _ = writeln!(out, "# TYPE rdap_domain_dnssec gauge");
+    let _ = writeln!(out, "rdap_domain_dnssec{{{labels}}} {}", u8::from(signed));
+
+    out
+}
+
+/// Escape a Prometheus label value
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_metrics() {
+        let json = r#"{
+            "objectClassName": "domain",
+            "ldhName": "EXAMPLE.COM",
+            "secureDNS": {"delegationSigned": true},
+            "events": [
+                {"eventAction": "registration", "eventDate": "1995-08-14T04:00:00Z"},
+                {"eventAction": "expiration", "eventDate": "2024-12-13T00:00:00Z"}
+            ]
+        }"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        let metrics = domain_metrics(&domain);
+        let lines: Vec<&str> = metrics.lines().filter(|l| !l.starts_with('#')).collect();
+
+        assert_eq!(
+            lines,
+            [
+                r#"rdap_domain_registration_seconds{domain="example.com"} 808372800"#,
+                r#"rdap_domain_expiry_seconds{domain="example.com"} 1734048000"#,
+                r#"rdap_domain_dnssec{domain="example.com"} 1"#,
+            ]
+        );
+        assert!(metrics.contains("# TYPE rdap_domain_expiry_seconds gauge"));
+    }
+
+    #[test]
+    fn test_domain_metrics_unsigned() {
+        let json = r#"{"objectClassName": "domain", "ldhName": "example.org"}"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        let metrics = domain_metrics(&domain);
+        assert!(metrics.contains(r#"rdap_domain_dnssec{domain="example.org"} 0"#));
+        assert!(!metrics.contains("rdap_domain_expiry_seconds"));
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label(r#"a"b\c"#), r#"a\"b\\c"#);
+    }
+}
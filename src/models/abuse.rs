@@ -0,0 +1,218 @@
+//! Abuse report skeletons, built by [`RdapObject::abuse_report`]
+
+use super::{ContactSource, Event, RdapObject, abuse_contact};
+use std::fmt::Write as _;
+
+/// Who to send an abuse report to, and what the record says about the object
+#[derive(Debug, Clone, Default)]
+pub struct AbuseReport {
+    /// Abuse contact email, without a `mailto:` prefix
+    pub email: String,
+    pub phone: Option<String>,
+    /// Object class, e.g. `domain` or `ip network`
+    pub object_type: String,
+    /// The reported resource: domain name, address range or AS number(s)
+    pub resource: Option<String>,
+    pub handle: Option<String>,
+    /// Lifecycle events, in display order; the RDAP database update is left out
+    pub events: Vec<Event>,
+    /// The record's `self` link
+    pub source_url: Option<String>,
+}
+
+impl AbuseReport {
+    /// Ready-to-edit report email about `target` (the domain, address, ...
+    /// observed in the abuse), with placeholders in square brackets
+    pub fn to_text(&self, target: &str) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "To: {}", self.email);
+        let _ = writeln!(text, "Subject: Abuse report for {target}");
+        let _ = writeln!(text);
+        let _ = writeln!(text, "Hello,");
+        let _ = writeln!(text);
+        let _ = writeln!(
+            text,
+            "We have observed abusive activity involving {target}, which according to"
+        );
+        let _ = writeln!(text, "its RDAP record falls under your responsibility:");
+        let _ = writeln!(text);
+        let mut field = |label: &str, value: &str| {
+            let _ = writeln!(text, "  {:<13} {value}", format!("{label}:"));
+        };
+        field("Object", &self.object_type);
+        if let Some(resource) = &self.resource {
+            field("Resource", resource);
+        }
+        if let Some(handle) = &self.handle {
+            field("Handle", handle);
+        }
+        for event in &self.events {
+            field(&event_label(event), &event.date);
+        }
+        if let Some(url) = &self.source_url {
+            field("RDAP record", url);
+        }
+        let _ = writeln!(text);
+        let _ = writeln!(
+            text,
+            "Type of abuse:    [phishing / malware / spam / scanning / ...]"
+        );
+        let _ = writeln!(text, "First seen (UTC): [timestamp]");
+        let _ = writeln!(text, "Last seen (UTC):  [timestamp]");
+        let _ = writeln!(text);
+        let _ = writeln!(text, "Evidence:");
+        let _ = writeln!(text, "[logs, URLs, message headers]");
+        let _ = writeln!(text);
+        let _ = writeln!(
+            text,
+            "Please investigate and take appropriate action. We are happy to provide"
+        );
+        let _ = writeln!(text, "further details on request.");
+        let _ = writeln!(text);
+        let _ = writeln!(text, "Regards,");
+        let _ = writeln!(text, "[name, organization, contact]");
+        text
+    }
+}
+
+/// Event action with its first letter capitalized, e.g. `Last changed`
+fn event_label(event: &Event) -> String {
+    let mut chars = event.action.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+impl RdapObject {
+    /// Abuse contact and metadata for reporting abuse of this object
+    ///
+    /// Domains use the registrar's abuse contact, then any `abuse` entity;
+    /// other objects the first `abuse` entity with an email, nested ones
+    /// included. `None` when there is no abuse email, and for searches,
+    /// errors and help.
+    pub fn abuse_report(&self) -> Option<AbuseReport> {
+        let (object_type, resource, handle, events) = match self {
+            Self::Domain(d) => ("domain", d.ascii_name(), &d.handle, &d.events),
+            Self::IpNetwork(ip) => {
+                let range = match (&ip.start_address, &ip.end_address) {
+                    (Some(start), Some(end)) => Some(format!("{start} - {end}")),
+                    (start, _) => start.clone(),
+                };
+                ("ip network", range, &ip.handle, &ip.events)
+            }
+            Self::Autnum(a) => {
+                let range = match (a.start_autnum, a.end_autnum) {
+                    (Some(start), Some(end)) if start != end => {
+                        Some(format!("AS{start} - AS{end}"))
+                    }
+                    (Some(start), _) => Some(format!("AS{start}")),
+                    _ => None,
+                };
+                ("autnum", range, &a.handle, &a.events)
+            }
+            Self::Entity(e) => (
+                "entity",
+                e.display_name().map(str::to_string),
+                &e.handle,
+                &e.events,
+            ),
+            Self::Nameserver(ns) => ("nameserver", ns.ldh_name.clone(), &ns.handle, &ns.events),
+            _ => return None,
+        };
+        let registrar_abuse = match self {
+            Self::Domain(d) => d
+                .registrar_abuse()
+                .and_then(|abuse| Some((abuse.email?, abuse.phone))),
+            _ => None,
+        };
+        let (email, phone) = registrar_abuse.or_else(|| {
+            abuse_contact(self.contact_entities(), usize::MAX)
+                .and_then(|c| Some((c.email?, c.phone)))
+        })?;
+
+        let mut events: Vec<Event> = events
+            .iter()
+            .filter(|e| e.action != "last update of RDAP database")
+            .cloned()
+            .collect();
+        super::sort_by_action(&mut events);
+
+        Some(AbuseReport {
+            email: email.trim_start_matches("mailto:").to_string(),
+            phone,
+            object_type: object_type.to_string(),
+            resource,
+            handle: handle.clone(),
+            events,
+            source_url: self.source_url().map(str::to_string),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_network_abuse_report() {
+        let obj: RdapObject = serde_json::from_str(
+            r#"{
+                "objectClassName": "ip network",
+                "handle": "NET-192-0-2-0-1",
+                "startAddress": "192.0.2.0",
+                "endAddress": "192.0.2.255",
+                "links": [{"rel": "self", "href": "https://rdap.example/ip/192.0.2.0"}],
+                "events": [
+                    {"eventAction": "last update of RDAP database", "eventDate": "2026-01-02T00:00:00Z"},
+                    {"eventAction": "last changed", "eventDate": "2024-05-06T00:00:00Z"},
+                    {"eventAction": "registration", "eventDate": "2001-02-03T00:00:00Z"}
+                ],
+                "entities": [{
+                    "objectClassName": "entity",
+                    "handle": "ORG-1",
+                    "roles": ["registrant"],
+                    "entities": [{
+                        "objectClassName": "entity",
+                        "handle": "ABUSE-1",
+                        "roles": ["abuse"],
+                        "vcardArray": ["vcard", [
+                            ["email", {}, "text", "mailto:abuse@net.example"],
+                            ["tel", {}, "uri", "tel:+1-555-0100"]
+                        ]]
+                    }]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let report = obj.abuse_report().unwrap();
+        assert_eq!(report.email, "abuse@net.example");
+        assert_eq!(report.phone.as_deref(), Some("+1-555-0100"));
+        assert_eq!(report.resource.as_deref(), Some("192.0.2.0 - 192.0.2.255"));
+        let actions: Vec<_> = report.events.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(actions, ["registration", "last changed"]);
+
+        let text = report.to_text("192.0.2.10");
+        assert!(text.starts_with("To: abuse@net.example\nSubject: Abuse report for 192.0.2.10\n"));
+        assert!(text.contains("  Handle:       NET-192-0-2-0-1\n"));
+        assert!(text.contains("  Last changed: 2024-05-06T00:00:00Z\n"));
+        assert!(text.contains("  RDAP record:  https://rdap.example/ip/192.0.2.0\n"));
+    }
+
+    #[test]
+    fn test_no_abuse_contact() {
+        let obj: RdapObject = serde_json::from_str(
+            r#"{
+                "objectClassName": "autnum",
+                "startAutnum": 64496,
+                "entities": [{
+                    "objectClassName": "entity",
+                    "roles": ["abuse"],
+                    "vcardArray": ["vcard", [["tel", {}, "uri", "tel:+1-555-0100"]]]
+                }]
+            }"#,
+        )
+        .unwrap();
+        assert!(obj.abuse_report().is_none());
+    }
+}
@@ -1,27 +1,41 @@
 //! Autonomous System Number model
 
-use super::{Entity, Event, Link, Notice, Remark, Status};
-use serde::{Deserialize, Serialize};
+use super::{Entity, Event, Extension, Link, Notice, Redaction, Remark, Status};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use serde_json::Value;
+use std::collections::BTreeSet;
 
 /// Autonomous System Number information
+///
+/// Bounds are accepted as numbers, numeric strings or single-element
+/// arrays, and a nonstandard `autnum` key fills in missing start/end.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct Autnum {
     #[serde(rename = "objectClassName", default)]
     pub object_class_name: Option<String>,
 
-    #[serde(rename = "rdapConformance", default)]
+    #[serde(
+        rename = "rdapConformance",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
     pub conformance: Vec<String>,
 
+    /// Parsed `rdapConformance` (see [`models`](super))
+    #[serde(skip)]
+    pub extensions: BTreeSet<Extension>,
+
     #[serde(default)]
     pub notices: Vec<Notice>,
 
     #[serde(default)]
     pub handle: Option<String>,
 
-    #[serde(rename = "startAutnum", default)]
+    #[serde(rename = "startAutnum", default, deserialize_with = "lenient_autnum")]
     pub start_autnum: Option<u32>,
 
-    #[serde(rename = "endAutnum", default)]
+    #[serde(rename = "endAutnum", default, deserialize_with = "lenient_autnum")]
     pub end_autnum: Option<u32>,
 
     #[serde(rename = "ipVersion", default)]
@@ -39,7 +53,7 @@ pub struct Autnum {
     #[serde(default)]
     pub country: Option<String>,
 
-    #[serde(default)]
+    #[serde(default, deserialize_with = "super::common::lenient_vec")]
     pub entities: Vec<Entity>,
 
     #[serde(default)]
@@ -56,4 +70,133 @@ pub struct Autnum {
 
     #[serde(default)]
     pub lang: Option<String>,
+
+    /// Redacted fields
+    #[serde(default, deserialize_with = "super::common::lenient_vec")]
+    pub redacted: Vec<Redaction>,
+
+    /// Members not covered above (see [`models`](super))
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl<'de> Deserialize<'de> for Autnum {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut value = Value::deserialize(deserializer)?;
+        let single = value
+            .as_object_mut()
+            .and_then(|obj| obj.remove("autnum"))
+            .map(|v| autnum_from_value(&v).ok_or_else(|| invalid_autnum(&v)))
+            .transpose()?;
+
+        let mut autnum = Self::deserialize(value).map_err(de::Error::custom)?;
+        if let Some(asn) = single {
+            autnum.start_autnum.get_or_insert(asn);
+            autnum.end_autnum.get_or_insert(asn);
+        }
+        Ok(autnum)
+    }
+}
+
+impl Serialize for Autnum {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Self::serialize(self, serializer)
+    }
+}
+
+/// Deserialize an optional AS number given as a number, string or `[n]`
+fn lenient_autnum<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<Value>::deserialize(deserializer)?
+        .filter(|v| !v.is_null())
+        .map(|v| autnum_from_value(&v).ok_or_else(|| invalid_autnum(&v)))
+        .transpose()
+}
+
+fn autnum_from_value(value: &Value) -> Option<u32> {
+    match value {
+        Value::Number(n) => n.as_u64().and_then(|n| u32::try_from(n).ok()),
+        Value::String(s) => s.trim().parse().ok(),
+        Value::Array(items) if items.len() == 1 => autnum_from_value(&items[0]),
+        _ => None,
+    }
+}
+
+fn invalid_autnum<E: de::Error>(value: &Value) -> E {
+    E::custom(format!("invalid AS number: {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds(json: &str) -> (Option<u32>, Option<u32>) {
+        let autnum: Autnum = serde_json::from_str(json).unwrap();
+        (autnum.start_autnum, autnum.end_autnum)
+    }
+
+    #[test]
+    fn test_numeric_bounds() {
+        assert_eq!(
+            bounds(r#"{"startAutnum": 64496, "endAutnum": 64511}"#),
+            (Some(64496), Some(64511))
+        );
+    }
+
+    #[test]
+    fn test_string_bounds() {
+        assert_eq!(
+            bounds(r#"{"startAutnum": "64496", "endAutnum": " 64511 "}"#),
+            (Some(64496), Some(64511))
+        );
+    }
+
+    #[test]
+    fn test_array_bounds() {
+        assert_eq!(
+            bounds(r#"{"startAutnum": [64496], "endAutnum": ["64511"]}"#),
+            (Some(64496), Some(64511))
+        );
+    }
+
+    #[test]
+    fn test_single_autnum_key() {
+        assert_eq!(bounds(r#"{"autnum": 15169}"#), (Some(15169), Some(15169)));
+        assert_eq!(bounds(r#"{"autnum": "15169"}"#), (Some(15169), Some(15169)));
+        // Explicit bounds take precedence
+        assert_eq!(
+            bounds(r#"{"autnum": 1, "startAutnum": 64496, "endAutnum": 64511}"#),
+            (Some(64496), Some(64511))
+        );
+    }
+
+    #[test]
+    fn test_missing_and_null_bounds() {
+        assert_eq!(bounds("{}"), (None, None));
+        assert_eq!(bounds(r#"{"startAutnum": null}"#), (None, None));
+    }
+
+    #[test]
+    fn test_invalid_bounds_rejected() {
+        assert!(serde_json::from_str::<Autnum>(r#"{"startAutnum": "AS-X"}"#).is_err());
+        assert!(serde_json::from_str::<Autnum>(r#"{"startAutnum": 4294967296}"#).is_err());
+        assert!(serde_json::from_str::<Autnum>(r#"{"startAutnum": [1, 2]}"#).is_err());
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let autnum: Autnum = serde_json::from_str(r#"{"autnum": 15169}"#).unwrap();
+        let json = serde_json::to_value(&autnum).unwrap();
+        assert_eq!(json["startAutnum"], 15169);
+        assert_eq!(json["endAutnum"], 15169);
+        assert!(json.get("autnum").is_none());
+    }
 }
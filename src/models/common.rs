@@ -1,5 +1,6 @@
 //! Common RDAP structures
 
+use super::Entity;
 use serde::{Deserialize, Serialize};
 
 /// Deserialize a field that can be either a single string or an array of strings
@@ -34,6 +35,26 @@ where
     deserializer.deserialize_any(StringOrVec)
 }
 
+/// Deserialize an array, skipping elements that fail to parse instead of
+/// failing the parent object (e.g. a stray `null` in `entities`)
+pub(crate) fn lenient_vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let values = Option::<Vec<serde_json::Value>>::deserialize(deserializer)?.unwrap_or_default();
+    Ok(values
+        .into_iter()
+        .filter_map(|value| match serde_json::from_value(value) {
+            Ok(item) => Some(item),
+            Err(e) => {
+                log::warn!("Skipping invalid array element: {e}");
+                None
+            }
+        })
+        .collect())
+}
+
 /// Link to related resources
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Link {
@@ -58,6 +79,16 @@ pub struct Link {
     pub link_type: Option<String>,
 }
 
+impl Link {
+    /// Target as a URL; a relative `href` is resolved against `value`
+    pub fn url(&self) -> Option<url::Url> {
+        url::Url::parse(&self.href).ok().or_else(|| {
+            let base = url::Url::parse(self.value.as_deref()?).ok()?;
+            base.join(&self.href).ok()
+        })
+    }
+}
+
 /// Notice or remark
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Notice {
@@ -72,10 +103,146 @@ pub struct Notice {
 
     #[serde(default)]
     pub links: Vec<Link>,
+
+    #[serde(default)]
+    pub lang: Option<String>,
+}
+
+/// Registered notice and remark `type` values (RFC 9083 section 10.2.1)
+///
+/// Servers differ in capitalization, so values are matched
+/// case-insensitively; unregistered ones are kept in [`NoticeType::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NoticeType {
+    /// `result set truncated due to authorization`
+    ResultSetTruncatedAuthorization,
+    /// `result set truncated due to excessive load`
+    ResultSetTruncatedLoad,
+    /// `result set truncated due to unexplainable reasons`
+    ResultSetTruncatedUnexplainable,
+    /// `object truncated due to authorization`
+    ObjectTruncatedAuthorization,
+    /// `object truncated due to excessive load`
+    ObjectTruncatedLoad,
+    /// `object truncated due to unexplainable reasons`
+    ObjectTruncatedUnexplainable,
+    /// `object redacted due to authorization`
+    ObjectRedactedAuthorization,
+    /// Any other value
+    Other(String),
+}
+
+impl NoticeType {
+    /// The value as registered with IANA
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::ResultSetTruncatedAuthorization => "result set truncated due to authorization",
+            Self::ResultSetTruncatedLoad => "result set truncated due to excessive load",
+            Self::ResultSetTruncatedUnexplainable => {
+                "result set truncated due to unexplainable reasons"
+            }
+            Self::ObjectTruncatedAuthorization => "object truncated due to authorization",
+            Self::ObjectTruncatedLoad => "object truncated due to excessive load",
+            Self::ObjectTruncatedUnexplainable => "object truncated due to unexplainable reasons",
+            Self::ObjectRedactedAuthorization => "object redacted due to authorization",
+            Self::Other(value) => value,
+        }
+    }
+
+    /// Whether the response is missing search results or object members
+    pub const fn is_truncation(&self) -> bool {
+        matches!(
+            self,
+            Self::ResultSetTruncatedAuthorization
+                | Self::ResultSetTruncatedLoad
+                | Self::ResultSetTruncatedUnexplainable
+                | Self::ObjectTruncatedAuthorization
+                | Self::ObjectTruncatedLoad
+                | Self::ObjectTruncatedUnexplainable
+        )
+    }
+}
+
+impl From<&str> for NoticeType {
+    fn from(value: &str) -> Self {
+        [
+            Self::ResultSetTruncatedAuthorization,
+            Self::ResultSetTruncatedLoad,
+            Self::ResultSetTruncatedUnexplainable,
+            Self::ObjectTruncatedAuthorization,
+            Self::ObjectTruncatedLoad,
+            Self::ObjectTruncatedUnexplainable,
+            Self::ObjectRedactedAuthorization,
+        ]
+        .into_iter()
+        .find(|known| known.as_str().eq_ignore_ascii_case(value.trim()))
+        .unwrap_or_else(|| Self::Other(value.to_string()))
+    }
+}
+
+impl std::fmt::Display for NoticeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Notice {
+    /// Parsed `type`, if the notice has one
+    pub fn kind(&self) -> Option<NoticeType> {
+        self.notice_type.as_deref().map(NoticeType::from)
+    }
+
+    /// Whether the notice is tagged with `lang` (`en` also matches `en-US`)
+    pub fn is_in_lang(&self, lang: &str) -> bool {
+        let primary = |tag: &str| {
+            tag.split(['-', '_'])
+                .next()
+                .unwrap_or(tag)
+                .to_ascii_lowercase()
+        };
+        self.lang
+            .as_deref()
+            .is_some_and(|own| own.eq_ignore_ascii_case(lang) || primary(own) == primary(lang))
+    }
+}
+
+/// Pick one notice per `type` from notices repeated in several languages
+///
+/// Within each type, the first notice in `lang` is kept, or the first one if
+/// none matches. Untyped notices are all kept. Order follows the first
+/// occurrence of each type.
+pub fn select_notices<'a>(notices: &'a [Notice], lang: Option<&str>) -> Vec<&'a Notice> {
+    let mut selected: Vec<&Notice> = Vec::new();
+    for notice in notices {
+        let Some(notice_type) = &notice.notice_type else {
+            selected.push(notice);
+            continue;
+        };
+        let existing = selected
+            .iter_mut()
+            .find(|n| n.notice_type.as_ref() == Some(notice_type));
+        match existing {
+            None => selected.push(notice),
+            Some(chosen) => {
+                if let Some(lang) = lang
+                    && !chosen.is_in_lang(lang)
+                    && notice.is_in_lang(lang)
+                {
+                    *chosen = notice;
+                }
+            }
+        }
+    }
+    selected
 }
 
 /// Event information
+///
+/// `eventActor` is normally a handle, but some servers embed the actor as
+/// an entity object; its handle then fills in `actor` and the object is kept
+/// in `actor_entity` so it serializes back unchanged.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct Event {
     #[serde(rename = "eventAction")]
     pub action: String,
@@ -83,6 +250,10 @@ pub struct Event {
     #[serde(rename = "eventActor", default)]
     pub actor: Option<String>,
 
+    /// Actor given as an embedded entity rather than a handle
+    #[serde(skip)]
+    pub actor_entity: Option<Box<Entity>>,
+
     #[serde(rename = "eventDate")]
     pub date: String,
 
@@ -90,6 +261,86 @@ pub struct Event {
     pub links: Vec<Link>,
 }
 
+impl<'de> Deserialize<'de> for Event {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+        let actor_entity = match value.get_mut("eventActor") {
+            Some(actor @ serde_json::Value::Object(_)) => {
+                let entity: Entity =
+                    serde_json::from_value(actor.take()).map_err(D::Error::custom)?;
+                *actor = entity
+                    .handle
+                    .clone()
+                    .map_or(serde_json::Value::Null, serde_json::Value::String);
+                Some(Box::new(entity))
+            }
+            _ => None,
+        };
+
+        let mut event = Self::deserialize(value).map_err(D::Error::custom)?;
+        event.actor_entity = actor_entity;
+        Ok(event)
+    }
+}
+
+impl Serialize for Event {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::Error;
+
+        let Some(entity) = &self.actor_entity else {
+            return Self::serialize(self, serializer);
+        };
+        let mut value =
+            Self::serialize(self, serde_json::value::Serializer).map_err(S::Error::custom)?;
+        value["eventActor"] = serde_json::to_value(entity).map_err(S::Error::custom)?;
+        value.serialize(serializer)
+    }
+}
+
+impl Event {
+    /// Parse the event date (RFC 3339)
+    pub fn parsed_date(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        chrono::DateTime::parse_from_rfc3339(&self.date).ok()
+    }
+
+    /// Canonical display position of the event action (lower comes first)
+    pub fn action_priority(&self) -> u8 {
+        match self.action.as_str() {
+            "registration" => 0,
+            "reregistration" => 1,
+            "reinstantiation" => 2,
+            "transfer" | "transferred" => 3,
+            "last changed" => 4,
+            "locked" => 5,
+            "unlocked" => 6,
+            "expiration" => 7,
+            "registrar expiration" => 8,
+            "enum validation expiration" => 9,
+            "deletion" => 10,
+            "last update of RDAP database" => 12,
+            _ => 11,
+        }
+    }
+}
+
+/// Sort events by date, oldest first (events with unparseable dates go last)
+pub fn sort_chronologically(events: &mut [Event]) {
+    events.sort_by_key(|e| (e.parsed_date().is_none(), e.parsed_date()));
+}
+
+/// Sort events into canonical display order (registration, last changed, expiration, ...)
+pub fn sort_by_action(events: &mut [Event]) {
+    events.sort_by_key(Event::action_priority);
+}
+
 /// Public identifier
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PublicId {
@@ -99,8 +350,236 @@ pub struct PublicId {
     pub identifier: String,
 }
 
+/// Field withheld by the server (RFC 9537 `redacted` member)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Redaction {
+    /// What was redacted, e.g. `Registrant Email`
+    pub name: RedactionText,
+
+    #[serde(default)]
+    pub reason: Option<RedactionText>,
+
+    /// `JSONPath` of the field before redaction
+    #[serde(rename = "prePath", default)]
+    pub pre_path: Option<String>,
+
+    /// `JSONPath` of the field after redaction
+    #[serde(rename = "postPath", default)]
+    pub post_path: Option<String>,
+
+    #[serde(rename = "replacementPath", default)]
+    pub replacement_path: Option<String>,
+
+    #[serde(rename = "pathLang", default)]
+    pub path_lang: Option<String>,
+
+    /// `removal` (the default), `emptyValue`, `partialValue` or `replacementValue`
+    #[serde(default)]
+    pub method: Option<String>,
+}
+
+/// Registered type or free-form description naming a redaction or its reason
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RedactionText {
+    #[serde(rename = "type", default)]
+    pub text_type: Option<String>,
+
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl RedactionText {
+    /// The registered type, or the description if there is none
+    pub fn text(&self) -> Option<&str> {
+        self.text_type.as_deref().or(self.description.as_deref())
+    }
+}
+
 /// Status values
 pub type Status = Vec<String>;
 
 /// Remark (same structure as Notice)
 pub type Remark = Notice;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notice(notice_type: Option<&str>, lang: &str, title: &str) -> Notice {
+        Notice {
+            title: Some(title.to_string()),
+            notice_type: notice_type.map(str::to_string),
+            description: vec![],
+            links: vec![],
+            lang: Some(lang.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_notice_kind() {
+        let kind = |t| notice(Some(t), "en", "x").kind().unwrap();
+        assert_eq!(
+            kind("Result Set Truncated Due To Authorization"),
+            NoticeType::ResultSetTruncatedAuthorization
+        );
+        assert!(kind("object truncated due to excessive load").is_truncation());
+        assert!(!kind("object redacted due to authorization").is_truncation());
+        assert_eq!(
+            kind("terms of service"),
+            NoticeType::Other("terms of service".to_string())
+        );
+        assert_eq!(
+            NoticeType::ObjectTruncatedLoad.to_string(),
+            "object truncated due to excessive load"
+        );
+        assert!(notice(None, "en", "x").kind().is_none());
+    }
+
+    #[test]
+    fn test_redaction() {
+        let redacted: Vec<Redaction> = serde_json::from_str(
+            r#"[
+                {
+                    "name": {"type": "Registrant Email"},
+                    "prePath": "$.entities[?(@.roles[0]=='registrant')].vcardArray[1][?(@[0]=='email')]",
+                    "pathLang": "jsonpath",
+                    "method": "removal",
+                    "reason": {"type": "Server Policy"}
+                },
+                {"name": {"description": "Administrative Contact"}, "method": "emptyValue"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(redacted[0].name.text(), Some("Registrant Email"));
+        assert_eq!(
+            redacted[0].reason.as_ref().and_then(RedactionText::text),
+            Some("Server Policy")
+        );
+        assert!(
+            redacted[0]
+                .pre_path
+                .as_deref()
+                .unwrap()
+                .starts_with("$.entities")
+        );
+        assert_eq!(redacted[1].name.text(), Some("Administrative Contact"));
+        assert_eq!(redacted[1].method.as_deref(), Some("emptyValue"));
+        assert!(redacted[1].reason.is_none());
+    }
+
+    #[test]
+    fn test_select_notices_by_lang() {
+        let notices = vec![
+            notice(Some("terms"), "de", "Nutzungsbedingungen"),
+            notice(Some("terms"), "en-US", "Terms of Use"),
+            notice(Some("inaccuracy"), "de", "Ungenauigkeit"),
+            notice(None, "de", "Hinweis"),
+            notice(None, "en", "Note"),
+        ];
+        let titles = |lang| {
+            select_notices(&notices, lang)
+                .iter()
+                .map(|n| n.title.as_deref().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            titles(Some("en")),
+            ["Terms of Use", "Ungenauigkeit", "Hinweis", "Note"]
+        );
+        assert_eq!(
+            titles(Some("DE")),
+            ["Nutzungsbedingungen", "Ungenauigkeit", "Hinweis", "Note"]
+        );
+        // No match or no preference falls back to the first of each type
+        assert_eq!(titles(Some("fr"))[0], "Nutzungsbedingungen");
+        assert_eq!(titles(None)[0], "Nutzungsbedingungen");
+    }
+
+    #[test]
+    fn test_event_actor_handle_or_entity() {
+        let event: Event = serde_json::from_str(
+            r#"{"eventAction": "last changed", "eventActor": "REG-1", "eventDate": "2024-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert_eq!(event.actor.as_deref(), Some("REG-1"));
+        assert!(event.actor_entity.is_none());
+
+        let json = serde_json::json!({
+            "eventAction": "transfer",
+            "eventActor": {
+                "objectClassName": "entity",
+                "handle": "292",
+                "roles": ["registrar"],
+                "links": [{"value": "https://rdap.example/entity/292", "rel": "self", "href": "https://rdap.example/entity/292"}]
+            },
+            "eventDate": "2024-01-01T00:00:00Z"
+        });
+        let event: Event = serde_json::from_value(json).unwrap();
+        assert_eq!(event.actor.as_deref(), Some("292"));
+        let entity = event.actor_entity.as_deref().unwrap();
+        assert!(entity.has_role("registrar"));
+
+        // The embedded entity is written back instead of the bare handle
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["eventActor"]["handle"], "292");
+        assert_eq!(
+            value["eventActor"]["links"][0]["href"],
+            "https://rdap.example/entity/292"
+        );
+    }
+
+    fn event(action: &str, date: &str) -> Event {
+        Event {
+            action: action.to_string(),
+            actor: None,
+            actor_entity: None,
+            date: date.to_string(),
+            links: vec![],
+        }
+    }
+
+    fn shuffled() -> Vec<Event> {
+        vec![
+            event("last update of RDAP database", "2026-01-05T00:00:00Z"),
+            event("expiration", "2027-08-13T04:00:00Z"),
+            event("custom", "not a date"),
+            event("registration", "1995-08-14T04:00:00Z"),
+            event("last changed", "2025-08-14T07:01:34+02:00"),
+        ]
+    }
+
+    #[test]
+    fn test_sort_chronologically() {
+        let mut events = shuffled();
+        sort_chronologically(&mut events);
+        let actions: Vec<&str> = events.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(
+            actions,
+            [
+                "registration",
+                "last changed",
+                "last update of RDAP database",
+                "expiration",
+                "custom"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_action() {
+        let mut events = shuffled();
+        sort_by_action(&mut events);
+        let actions: Vec<&str> = events.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(
+            actions,
+            [
+                "registration",
+                "last changed",
+                "expiration",
+                "custom",
+                "last update of RDAP database"
+            ]
+        );
+    }
+}
@@ -0,0 +1,421 @@
+//! Flat contact list harvested from entity vCards
+
+use super::vcard::{VCard, VCardAddress};
+use super::{
+    Autnum, Domain, DomainSearchResults, Entity, EntitySearchResults, IpNetwork, Nameserver,
+    NameserverSearchResults, RdapObject,
+};
+use serde::Serialize;
+
+/// One entity's contact details, flattened for export (e.g. to a spreadsheet)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Contact {
+    pub handle: Option<String>,
+    pub name: Option<String>,
+    pub org: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    /// Roles joined with `, `, e.g. `administrative, technical`
+    pub role: Option<String>,
+    /// Address on one line, from the `label` parameter or the components
+    pub address: Option<String>,
+}
+
+impl Contact {
+    fn from_entity(entity: &Entity) -> Self {
+        let vcard = entity.vcard.as_ref();
+        let text = |value: Option<&str>| {
+            value
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            handle: entity.handle.clone(),
+            name: text(vcard.and_then(|v| v.name())),
+            org: text(vcard.and_then(|v| v.org())),
+            email: text(
+                vcard
+                    .and_then(|v| v.email())
+                    .map(|e| e.trim_start_matches("mailto:")),
+            ),
+            phone: text(
+                vcard
+                    .and_then(|v| v.tel())
+                    .map(|t| t.trim_start_matches("tel:")),
+            ),
+            role: (!entity.roles.is_empty()).then(|| entity.roles.join(", ")),
+            address: vcard.and_then(VCard::address).and_then(|a| one_line(&a)),
+        }
+    }
+
+    /// Add roles, and details this contact lacks, from another occurrence of
+    /// the same entity (e.g. a complete vCard after a redacted stub)
+    fn merge(&mut self, entity: &Entity) {
+        let other = Self::from_entity(entity);
+        for (field, value) in [
+            (&mut self.name, other.name),
+            (&mut self.org, other.org),
+            (&mut self.email, other.email),
+            (&mut self.phone, other.phone),
+            (&mut self.address, other.address),
+        ] {
+            if field.is_none() {
+                *field = value;
+            }
+        }
+
+        let mut roles: Vec<&str> = self
+            .role
+            .as_deref()
+            .map(|r| r.split(", ").collect())
+            .unwrap_or_default();
+        for role in &entity.roles {
+            if !roles.iter().any(|r| r.eq_ignore_ascii_case(role)) {
+                roles.push(role);
+            }
+        }
+        self.role = (!roles.is_empty()).then(|| roles.join(", "));
+    }
+}
+
+fn one_line(address: &VCardAddress) -> Option<String> {
+    let line = address.label.as_ref().map_or_else(
+        || {
+            [
+                &address.po_box,
+                &address.extended,
+                &address.street,
+                &address.locality,
+                &address.region,
+                &address.postal_code,
+                &address.country,
+            ]
+            .iter()
+            .map(|part| part.trim())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ")
+        },
+        |label| {
+            label
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .collect::<Vec<_>>()
+                .join(", ")
+        },
+    );
+    (!line.is_empty()).then_some(line)
+}
+
+/// Objects whose entities can be flattened into a [`Contact`] list
+pub trait ContactSource {
+    /// Entities directly attached to the object
+    fn contact_entities(&self) -> Vec<&Entity>;
+
+    /// Contacts of every entity, nested ones included
+    ///
+    /// Entities are listed in document order. One that appears several times
+    /// under the same handle (e.g. as registrant and again nested elsewhere)
+    /// becomes a single contact with its roles merged and any details missing
+    /// from the first occurrence filled in from later ones.
+    fn contacts(&self) -> Vec<Contact> {
+        let mut contacts = Vec::new();
+        for entity in self.contact_entities() {
+            harvest(entity, &mut contacts);
+        }
+        contacts
+    }
+}
+
+fn harvest(entity: &Entity, contacts: &mut Vec<Contact>) {
+    let existing = entity.handle.as_ref().and_then(|handle| {
+        contacts
+            .iter_mut()
+            .find(|c| c.handle.as_ref() == Some(handle))
+    });
+    match existing {
+        Some(contact) => contact.merge(entity),
+        None => contacts.push(Contact::from_entity(entity)),
+    }
+
+    for nested in entity.contact_entities() {
+        harvest(nested, contacts);
+    }
+}
+
+/// First contact with `role` that has an email, searched depth-first
+///
+/// `entities` are the top level; nested entities are searched at most
+/// `max_depth` levels down in total (`usize::MAX` for no limit).
+pub fn contact_by_role<'a>(
+    entities: impl IntoIterator<Item = &'a Entity>,
+    role: &str,
+    max_depth: usize,
+) -> Option<Contact> {
+    find_by_role(entities.into_iter().collect(), role, 1, max_depth)
+}
+
+/// An object's abuse contact: the first entity with the `abuse` role and an
+/// email (see [`contact_by_role`])
+///
+/// Shared by the text display, CSV export and abuse reports so they agree.
+pub fn abuse_contact<'a>(
+    entities: impl IntoIterator<Item = &'a Entity>,
+    max_depth: usize,
+) -> Option<Contact> {
+    contact_by_role(entities, "abuse", max_depth)
+}
+
+fn find_by_role(
+    entities: Vec<&Entity>,
+    role: &str,
+    depth: usize,
+    max_depth: usize,
+) -> Option<Contact> {
+    if depth > max_depth {
+        return None;
+    }
+    for entity in entities {
+        if entity.roles.iter().any(|r| r.eq_ignore_ascii_case(role)) {
+            let contact = Contact::from_entity(entity);
+            if contact.email.is_some() {
+                return Some(contact);
+            }
+        }
+        if let Some(contact) = find_by_role(entity.contact_entities(), role, depth + 1, max_depth) {
+            return Some(contact);
+        }
+    }
+    None
+}
+
+impl ContactSource for Entity {
+    fn contact_entities(&self) -> Vec<&Entity> {
+        let networks = self.networks.iter().flat_map(|n| &n.entities);
+        let autnums = self.autnums.iter().flat_map(|a| &a.entities);
+        self.entities
+            .iter()
+            .chain(networks)
+            .chain(autnums)
+            .collect()
+    }
+
+    /// Contacts of the entity itself, followed by those nested in it
+    fn contacts(&self) -> Vec<Contact> {
+        let mut contacts = Vec::new();
+        harvest(self, &mut contacts);
+        contacts
+    }
+}
+
+impl ContactSource for Domain {
+    fn contact_entities(&self) -> Vec<&Entity> {
+        let nameservers = self.nameservers.iter().flat_map(|ns| &ns.entities);
+        let network = self.network.iter().flat_map(|n| &n.entities);
+        self.entities
+            .iter()
+            .chain(nameservers)
+            .chain(network)
+            .collect()
+    }
+}
+
+impl ContactSource for Nameserver {
+    fn contact_entities(&self) -> Vec<&Entity> {
+        self.entities.iter().collect()
+    }
+}
+
+impl ContactSource for Autnum {
+    fn contact_entities(&self) -> Vec<&Entity> {
+        self.entities.iter().collect()
+    }
+}
+
+impl ContactSource for IpNetwork {
+    fn contact_entities(&self) -> Vec<&Entity> {
+        self.entities.iter().collect()
+    }
+}
+
+impl ContactSource for DomainSearchResults {
+    fn contact_entities(&self) -> Vec<&Entity> {
+        self.domains
+            .iter()
+            .flat_map(ContactSource::contact_entities)
+            .collect()
+    }
+}
+
+impl ContactSource for EntitySearchResults {
+    /// Every result, each followed by its nested contacts
+    fn contact_entities(&self) -> Vec<&Entity> {
+        self.entities.iter().collect()
+    }
+}
+
+impl ContactSource for NameserverSearchResults {
+    fn contact_entities(&self) -> Vec<&Entity> {
+        self.nameservers
+            .iter()
+            .flat_map(|ns| &ns.entities)
+            .collect()
+    }
+}
+
+impl ContactSource for RdapObject {
+    fn contact_entities(&self) -> Vec<&Entity> {
+        match self {
+            Self::Domain(d) => d.contact_entities(),
+            Self::Entity(e) => vec![e],
+            Self::Nameserver(ns) => ns.contact_entities(),
+            Self::Autnum(a) => a.contact_entities(),
+            Self::IpNetwork(ip) => ip.contact_entities(),
+            Self::DomainSearch(r) => r.contact_entities(),
+            Self::EntitySearch(r) => r.contact_entities(),
+            Self::NameserverSearch(r) => r.contact_entities(),
+            Self::Error(_) | Self::Help(_) => Vec::new(),
+        }
+    }
+}
+
+impl RdapObject {
+    /// Contacts of every entity in the object, see [`ContactSource::contacts`]
+    pub fn contacts(&self) -> Vec<Contact> {
+        ContactSource::contacts(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_contacts_flattened() {
+        let json = r#"{
+            "objectClassName": "domain",
+            "ldhName": "example.com",
+            "entities": [
+                {
+                    "objectClassName": "entity",
+                    "handle": "REG-1",
+                    "roles": ["registrant"],
+                    "vcardArray": ["vcard", [
+                        ["version", {}, "text", "4.0"],
+                        ["fn", {}, "text", "Jane Doe"],
+                        ["org", {}, "text", "Example Inc."],
+                        ["email", {}, "text", "mailto:jane@example.com"],
+                        ["tel", {"type": "voice"}, "uri", "tel:+1.5555550100"],
+                        ["adr", {}, "text", ["", "", "1 Main St", "Springfield", "IL", "62701", "US"]]
+                    ]]
+                },
+                {
+                    "objectClassName": "entity",
+                    "handle": "292",
+                    "roles": ["registrar"],
+                    "entities": [{
+                        "objectClassName": "entity",
+                        "roles": ["abuse"],
+                        "vcardArray": ["vcard", [["email", {}, "text", "abuse@registrar.example"]]]
+                    }]
+                },
+                {"objectClassName": "entity", "handle": "REG-1", "roles": ["administrative"]},
+                {"objectClassName": "entity", "handle": "TECH-1", "roles": ["technical"], "remarks": [{"title": "REDACTED FOR PRIVACY"}]},
+                {
+                    "objectClassName": "entity",
+                    "handle": "TECH-1",
+                    "roles": ["billing"],
+                    "vcardArray": ["vcard", [["fn", {}, "text", "Tech Team"], ["email", {}, "text", "tech@example.com"]]]
+                }
+            ]
+        }"#;
+        let object = crate::parse_rdap(json).unwrap();
+        let contacts = object.contacts();
+
+        assert_eq!(contacts.len(), 4);
+        assert_eq!(
+            contacts[0],
+            Contact {
+                handle: Some("REG-1".to_string()),
+                name: Some("Jane Doe".to_string()),
+                org: Some("Example Inc.".to_string()),
+                email: Some("jane@example.com".to_string()),
+                phone: Some("+1.5555550100".to_string()),
+                role: Some("registrant, administrative".to_string()),
+                address: Some("1 Main St, Springfield, IL, 62701, US".to_string()),
+            }
+        );
+        assert_eq!(contacts[1].handle.as_deref(), Some("292"));
+        assert_eq!(contacts[2].role.as_deref(), Some("abuse"));
+        assert_eq!(
+            contacts[2].email.as_deref(),
+            Some("abuse@registrar.example")
+        );
+        // The redacted stub came first, the complete vCard fills it in
+        assert_eq!(contacts[3].name.as_deref(), Some("Tech Team"));
+        assert_eq!(contacts[3].email.as_deref(), Some("tech@example.com"));
+        assert_eq!(contacts[3].role.as_deref(), Some("technical, billing"));
+    }
+
+    #[test]
+    fn test_abuse_contact_depth() {
+        let json = serde_json::json!({
+            "objectClassName": "ip network",
+            "entities": [{
+                "objectClassName": "entity",
+                "roles": ["registrant", "abuse"],
+                "entities": [{
+                    "objectClassName": "entity",
+                    "roles": ["Abuse"],
+                    "vcardArray": ["vcard", [
+                        ["email", {}, "text", "mailto:abuse@example.net"],
+                        ["tel", {}, "uri", "tel:+1.5555550199"]
+                    ]]
+                }]
+            }]
+        });
+        let net: IpNetwork = serde_json::from_value(json).unwrap();
+
+        // The outer abuse entity has no email, so the nested one is used
+        let contact = abuse_contact(&net.entities, usize::MAX).unwrap();
+        assert_eq!(contact.email.as_deref(), Some("abuse@example.net"));
+        assert_eq!(contact.phone.as_deref(), Some("+1.5555550199"));
+        assert!(abuse_contact(&net.entities, 1).is_none());
+        assert!(contact_by_role(&net.entities, "technical", usize::MAX).is_none());
+    }
+
+    #[test]
+    fn test_search_and_entity_contacts() {
+        let json = r#"{
+            "rdapConformance": ["rdap_level_0"],
+            "domainSearchResults": [
+                {"objectClassName": "domain", "ldhName": "a.example", "entities": [{"objectClassName": "entity", "handle": "A", "roles": ["technical"]}]},
+                {"objectClassName": "domain", "ldhName": "b.example", "entities": [{"objectClassName": "entity", "handle": "B", "roles": ["technical"]}]}
+            ]
+        }"#;
+        let handles: Vec<_> = crate::parse_rdap(json)
+            .unwrap()
+            .contacts()
+            .into_iter()
+            .filter_map(|c| c.handle)
+            .collect();
+        assert_eq!(handles, ["A", "B"]);
+
+        // An entity lookup includes the entity itself
+        let json = r#"{
+            "objectClassName": "entity",
+            "handle": "ORG-1",
+            "vcardArray": ["vcard", [["fn", {"label": "x"}, "text", "Org One"], ["adr", {"label": "1 Main St\nSpringfield"}, "text", ["", "", "", "", "", "", ""]]]]
+        }"#;
+        let contacts = crate::parse_rdap(json).unwrap().contacts();
+        assert_eq!(contacts.len(), 1);
+        assert_eq!(contacts[0].name.as_deref(), Some("Org One"));
+        assert_eq!(
+            contacts[0].address.as_deref(),
+            Some("1 Main St, Springfield")
+        );
+        assert!(contacts[0].role.is_none());
+    }
+}
@@ -1,7 +1,12 @@
 //! Domain object model
 
-use super::{Entity, Event, IpNetwork, Link, Nameserver, Notice, PublicId, Remark, Status};
+use super::{
+    Entity, Event, Extension, IpNetwork, Link, Nameserver, Notice, PublicId, Redaction, Remark,
+    Status,
+};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 /// Domain name registration information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,9 +14,17 @@ pub struct Domain {
     #[serde(rename = "objectClassName")]
     pub object_class_name: String,
 
-    #[serde(rename = "rdapConformance", default)]
+    #[serde(
+        rename = "rdapConformance",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
     pub conformance: Vec<String>,
 
+    /// Parsed `rdapConformance` (see [`models`](super))
+    #[serde(skip)]
+    pub extensions: BTreeSet<Extension>,
+
     #[serde(default)]
     pub notices: Vec<Notice>,
 
@@ -33,7 +46,7 @@ pub struct Domain {
     #[serde(rename = "secureDNS", default)]
     pub secure_dns: Option<SecureDNS>,
 
-    #[serde(default)]
+    #[serde(default, deserialize_with = "super::common::lenient_vec")]
     pub entities: Vec<Entity>,
 
     #[serde(default)]
@@ -59,6 +72,239 @@ pub struct Domain {
 
     #[serde(default)]
     pub lang: Option<String>,
+
+    /// Redacted fields
+    #[serde(default, deserialize_with = "super::common::lenient_vec")]
+    pub redacted: Vec<Redaction>,
+
+    /// Members not covered above (see [`models`](super))
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Domain {
+    /// Domain name, preferring `ldhName` and falling back to `unicodeName`
+    pub fn name(&self) -> Option<&str> {
+        self.ldh_name.as_deref().or(self.unicode_name.as_deref())
+    }
+
+    /// Raw value of a member this model doesn't cover, e.g. `fred_nsset`
+    pub fn extension(&self, key: &str) -> Option<&serde_json::Value> {
+        self.extra.get(key)
+    }
+
+    /// ASCII (A-label) form of the name, derived from `unicodeName` when
+    /// the response has no `ldhName`
+    pub fn ascii_name(&self) -> Option<String> {
+        if let Some(ldh) = &self.ldh_name {
+            return Some(ldh.clone());
+        }
+        idna::domain_to_ascii(self.unicode_name.as_ref()?).ok()
+    }
+
+    /// Name with both the Unicode and A-label forms, e.g. `müller.de (xn--mller-kva.de)`
+    ///
+    /// Non-ASCII names are wrapped in Unicode bidi isolates (U+2068..U+2069)
+    /// so right-to-left labels can't reorder the surrounding text. When the
+    /// two forms are the same, only one is shown.
+    pub fn combined_name(&self, order: NameOrder) -> Option<String> {
+        self.combined_name_with(order, str::to_string)
+    }
+
+    /// Like [`Self::combined_name`], passing the Unicode form through `render`
+    /// (e.g. to escape control characters) before it is isolated
+    pub fn combined_name_with<F>(&self, order: NameOrder, render: F) -> Option<String>
+    where
+        F: Fn(&str) -> String,
+    {
+        let ascii = self.ascii_name();
+        let unicode = self
+            .unicode_name
+            .as_deref()
+            .filter(|u| ascii.as_deref().is_none_or(|a| !a.eq_ignore_ascii_case(u)));
+
+        match (ascii, unicode) {
+            (Some(ascii), Some(unicode)) => {
+                let unicode = bidi_isolate(unicode, &render);
+                Some(match order {
+                    NameOrder::UnicodeFirst => format!("{unicode} ({ascii})"),
+                    NameOrder::AsciiFirst => format!("{ascii} ({unicode})"),
+                })
+            }
+            (Some(ascii), None) => Some(ascii),
+            (None, Some(unicode)) => Some(bidi_isolate(unicode, &render)),
+            (None, None) => None,
+        }
+    }
+
+    /// Expiration date from the events, if present
+    pub fn expiration(&self) -> Option<&str> {
+        self.events
+            .iter()
+            .find(|e| e.action == "expiration")
+            .map(|e| e.date.as_str())
+    }
+
+    /// Parsed date of the first event with the given action
+    fn event_date(&self, action: &str) -> Option<DateTime<Utc>> {
+        self.events
+            .iter()
+            .find(|e| e.action == action)
+            .and_then(super::Event::parsed_date)
+            .map(|d| d.with_timezone(&Utc))
+    }
+
+    /// Time since registration
+    pub fn age(&self) -> Option<Duration> {
+        self.age_at(Utc::now())
+    }
+
+    /// Time since registration, as of `now`
+    pub fn age_at(&self, now: DateTime<Utc>) -> Option<Duration> {
+        Some(now - self.event_date("registration")?)
+    }
+
+    /// Time remaining until expiration (negative once expired)
+    pub fn time_until_expiry(&self) -> Option<Duration> {
+        self.time_until_expiry_at(Utc::now())
+    }
+
+    /// Time remaining until expiration, as of `now`
+    pub fn time_until_expiry_at(&self, now: DateTime<Utc>) -> Option<Duration> {
+        Some(self.event_date("expiration")? - now)
+    }
+
+    /// Sponsoring registrar entity
+    pub fn registrar(&self) -> Option<&Entity> {
+        self.entities.iter().find(|e| e.has_role("registrar"))
+    }
+
+    /// Sponsoring registrar's name, IANA ID and abuse email in one place
+    ///
+    /// The IANA ID comes from the registrar entity's `IANA Registrar ID`
+    /// public ID, which gTLD registries are required to include.
+    pub fn registrar_info(&self) -> Option<Registrar> {
+        let entity = self.registrar()?;
+        let name = entity
+            .vcard
+            .as_ref()
+            .and_then(|v| v.name().filter(|n| !n.is_empty()).or_else(|| v.org()))
+            .map(str::to_string);
+        let iana_id = entity
+            .public_ids
+            .iter()
+            .find(|id| id.id_type.eq_ignore_ascii_case("IANA Registrar ID"))
+            .map(|id| id.identifier.trim().to_string());
+        let abuse_email = self.registrar_abuse().and_then(|abuse| abuse.email);
+        Some(Registrar {
+            name,
+            iana_id,
+            abuse_email,
+        })
+    }
+
+    /// Reseller that sold the domain, nested under the registrar entity
+    pub fn reseller(&self) -> Option<&Entity> {
+        self.registrar()?
+            .entities
+            .iter()
+            .find(|e| e.has_role("reseller"))
+    }
+
+    /// Registrar abuse contact, as required for gTLDs by the ICANN RDAP profile
+    ///
+    /// Looks for an `abuse` entity nested under the registrar first, then
+    /// falls back to a top-level `abuse` entity as some registrars return.
+    pub fn registrar_abuse(&self) -> Option<AbuseContact> {
+        let nested = self
+            .registrar()
+            .into_iter()
+            .flat_map(|registrar| &registrar.entities);
+        nested
+            .chain(&self.entities)
+            .filter(|e| e.has_role("abuse"))
+            .find_map(AbuseContact::from_entity)
+    }
+
+    /// One-line summary: name, status and expiry (e.g. for search results)
+    pub fn summary(&self) -> String {
+        self.summary_with(str::to_string)
+    }
+
+    /// Like [`Self::summary`], rendering the Unicode name with `render`
+    pub fn summary_with<F>(&self, render: F) -> String
+    where
+        F: Fn(&str) -> String,
+    {
+        let mut parts = vec![
+            self.combined_name_with(NameOrder::default(), render)
+                .unwrap_or_else(|| "(unnamed)".to_string()),
+        ];
+        if let Some(handle) = &self.handle {
+            parts.push(format!("({handle})"));
+        }
+        if !self.status.is_empty() {
+            parts.push(format!("[{}]", self.status.join(", ")));
+        }
+        if let Some(expiration) = self.expiration() {
+            let date = chrono::DateTime::parse_from_rfc3339(expiration).map_or_else(
+                |_| expiration.to_string(),
+                |d| d.format("%Y-%m-%d").to_string(),
+            );
+            parts.push(format!("expires {date}"));
+        }
+        parts.join(" ")
+    }
+}
+
+/// Abuse email and phone of a registrar
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AbuseContact {
+    pub email: Option<String>,
+    pub phone: Option<String>,
+}
+
+impl AbuseContact {
+    /// Contact details from an entity's vCard, if it has any
+    fn from_entity(entity: &Entity) -> Option<Self> {
+        let vcard = entity.vcard.as_ref()?;
+        let email = vcard
+            .email()
+            .map(|e| e.trim_start_matches("mailto:").to_string());
+        let phone = vcard
+            .tel()
+            .map(|t| t.trim_start_matches("tel:").to_string());
+        (email.is_some() || phone.is_some()).then_some(Self { email, phone })
+    }
+}
+
+/// Registrar details collected by [`Domain::registrar_info`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Registrar {
+    /// vCard `fn` (or `org`) of the registrar entity
+    pub name: Option<String>,
+    /// IANA Registrar ID, e.g. `292`
+    pub iana_id: Option<String>,
+    pub abuse_email: Option<String>,
+}
+
+/// Which form of an IDN comes first in [`Domain::combined_name`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NameOrder {
+    /// `müller.de (xn--mller-kva.de)`
+    #[default]
+    UnicodeFirst,
+    /// `xn--mller-kva.de (müller.de)`
+    AsciiFirst,
+}
+
+/// Render non-ASCII text inside a first-strong isolate so bidi text stays contained
+fn bidi_isolate(text: &str, render: impl Fn(&str) -> String) -> String {
+    if text.is_ascii() {
+        render(text)
+    } else {
+        format!("\u{2068}{}\u{2069}", render(text))
+    }
 }
 
 /// Domain variant
@@ -125,6 +371,18 @@ pub struct DSData {
     pub links: Vec<Link>,
 }
 
+impl DSData {
+    /// IANA mnemonic of the key's algorithm, see [`dnssec_algorithm_name`]
+    pub fn algorithm_name(&self) -> Option<&'static str> {
+        self.algorithm.and_then(dnssec_algorithm_name)
+    }
+
+    /// IANA mnemonic of the digest type, see [`ds_digest_type_name`]
+    pub fn digest_type_name(&self) -> Option<&'static str> {
+        self.digest_type.and_then(ds_digest_type_name)
+    }
+}
+
 /// DNSKEY data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeyData {
@@ -146,3 +404,320 @@ pub struct KeyData {
     #[serde(default)]
     pub links: Vec<Link>,
 }
+
+impl KeyData {
+    /// IANA mnemonic of the key's algorithm, see [`dnssec_algorithm_name`]
+    pub fn algorithm_name(&self) -> Option<&'static str> {
+        self.algorithm.and_then(dnssec_algorithm_name)
+    }
+}
+
+/// IANA mnemonic for a DNSSEC algorithm number, e.g. `ECDSAP256SHA256` for 13
+///
+/// Covers the assigned values of the IANA DNS Security Algorithm Numbers
+/// registry; unassigned and reserved numbers give `None`.
+pub const fn dnssec_algorithm_name(algorithm: u8) -> Option<&'static str> {
+    Some(match algorithm {
+        1 => "RSAMD5",
+        2 => "DH",
+        3 => "DSA",
+        5 => "RSASHA1",
+        6 => "DSA-NSEC3-SHA1",
+        7 => "RSASHA1-NSEC3-SHA1",
+        8 => "RSASHA256",
+        10 => "RSASHA512",
+        12 => "ECC-GOST",
+        13 => "ECDSAP256SHA256",
+        14 => "ECDSAP384SHA384",
+        15 => "ED25519",
+        16 => "ED448",
+        17 => "SM2SM3",
+        23 => "ECC-GOST12",
+        252 => "INDIRECT",
+        253 => "PRIVATEDNS",
+        254 => "PRIVATEOID",
+        _ => return None,
+    })
+}
+
+/// IANA mnemonic for a DS digest type, e.g. `SHA-256` for 2
+pub const fn ds_digest_type_name(digest_type: u8) -> Option<&'static str> {
+    Some(match digest_type {
+        1 => "SHA-1",
+        2 => "SHA-256",
+        3 => "GOST R 34.11-94",
+        4 => "SHA-384",
+        5 => "GOST R 34.11-2012",
+        6 => "SM3",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secure_dns_names() {
+        let json = r#"{
+            "objectClassName": "domain",
+            "secureDNS": {
+                "delegationSigned": true,
+                "dsData": [{"keyTag": 370, "algorithm": 13, "digestType": 2, "digest": "AB12"}],
+                "keyData": [{"flags": 257, "protocol": 3, "algorithm": 8, "publicKey": "AwEAAa"}, {"algorithm": 99}]
+            }
+        }"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        let dnssec = domain.secure_dns.unwrap();
+        assert_eq!(dnssec.ds_data[0].algorithm_name(), Some("ECDSAP256SHA256"));
+        assert_eq!(dnssec.ds_data[0].digest_type_name(), Some("SHA-256"));
+        assert_eq!(dnssec.key_data[0].algorithm_name(), Some("RSASHA256"));
+        assert_eq!(dnssec.key_data[1].algorithm_name(), None);
+        assert_eq!(ds_digest_type_name(0), None);
+    }
+
+    #[test]
+    fn test_unknown_members_preserved() {
+        let json = r#"{
+            "objectClassName": "domain",
+            "ldhName": "example.cz",
+            "fred_keyset": {"handle": "KS-1"},
+            "fred_nsset": {"handle": "NS-1"}
+        }"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        assert_eq!(domain.extension("fred_keyset").unwrap()["handle"], "KS-1");
+        assert!(domain.extension("ldhName").is_none());
+        assert!(domain.extension("missing").is_none());
+
+        let value = serde_json::to_value(&domain).unwrap();
+        assert_eq!(value["fred_nsset"]["handle"], "NS-1");
+        assert_eq!(value["ldhName"], "example.cz");
+    }
+
+    #[test]
+    fn test_unicode_name_without_ldh_name() {
+        let json = r#"{"objectClassName": "domain", "unicodeName": "müller.de"}"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        assert!(domain.ldh_name.is_none());
+        assert_eq!(domain.name(), Some("müller.de"));
+        assert_eq!(domain.ascii_name().as_deref(), Some("xn--mller-kva.de"));
+    }
+
+    #[test]
+    fn test_reseller_under_registrar() {
+        let json = r#"{
+            "objectClassName": "domain",
+            "ldhName": "example.com",
+            "entities": [
+                {"objectClassName": "entity", "handle": "REG-1", "roles": ["registrant"]},
+                {
+                    "objectClassName": "entity",
+                    "handle": "292",
+                    "roles": ["registrar"],
+                    "vcardArray": ["vcard", [["version", {}, "text", "4.0"], ["fn", {}, "text", "Example Registrar, Inc."]]],
+                    "entities": [
+                        {"objectClassName": "entity", "handle": "ABUSE", "roles": ["abuse"]},
+                        {
+                            "objectClassName": "entity",
+                            "roles": ["Reseller"],
+                            "vcardArray": ["vcard", [["version", {}, "text", "4.0"], ["fn", {}, "text", "Cheap Names Ltd"]]]
+                        }
+                    ]
+                }
+            ]
+        }"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        let registrar = domain.registrar().unwrap();
+        assert_eq!(registrar.display_name(), Some("Example Registrar, Inc."));
+        let reseller = domain.reseller().unwrap();
+        assert_eq!(reseller.display_name(), Some("Cheap Names Ltd"));
+    }
+
+    #[test]
+    fn test_registrar_abuse_nested_under_registrar() {
+        // Shaped like a .com registry response
+        let json = r#"{
+            "objectClassName": "domain",
+            "ldhName": "EXAMPLE.COM",
+            "entities": [{
+                "objectClassName": "entity",
+                "handle": "376",
+                "roles": ["registrar"],
+                "publicIds": [{"type": "IANA Registrar ID", "identifier": "376"}],
+                "vcardArray": ["vcard", [["version", {}, "text", "4.0"], ["fn", {}, "text", "RESERVED-Internet Assigned Numbers Authority"]]],
+                "entities": [{
+                    "objectClassName": "entity",
+                    "roles": ["abuse"],
+                    "vcardArray": ["vcard", [
+                        ["version", {}, "text", "4.0"],
+                        ["fn", {}, "text", ""],
+                        ["tel", {"type": "voice"}, "uri", "tel:+1.3103015800"],
+                        ["email", {}, "text", "abuse@iana.org"]
+                    ]]
+                }]
+            }]
+        }"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            domain.registrar_abuse(),
+            Some(AbuseContact {
+                email: Some("abuse@iana.org".to_string()),
+                phone: Some("+1.3103015800".to_string()),
+            })
+        );
+        assert_eq!(
+            domain.registrar_info(),
+            Some(Registrar {
+                name: Some("RESERVED-Internet Assigned Numbers Authority".to_string()),
+                iana_id: Some("376".to_string()),
+                abuse_email: Some("abuse@iana.org".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_registrar_abuse_top_level_fallback() {
+        // Registrar RDAP servers sometimes list the abuse contact at the top level
+        let json = r#"{
+            "objectClassName": "domain",
+            "ldhName": "example.org",
+            "entities": [
+                {
+                    "objectClassName": "entity",
+                    "handle": "1234",
+                    "roles": ["registrar"],
+                    "entities": [{"objectClassName": "entity", "roles": ["technical"]}]
+                },
+                {
+                    "objectClassName": "entity",
+                    "roles": ["abuse"],
+                    "vcardArray": ["vcard", [
+                        ["version", {}, "text", "4.0"],
+                        ["email", {}, "text", "mailto:abuse@registrar.example"]
+                    ]]
+                }
+            ]
+        }"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            domain.registrar_abuse(),
+            Some(AbuseContact {
+                email: Some("abuse@registrar.example".to_string()),
+                phone: None,
+            })
+        );
+
+        let json = r#"{"objectClassName": "domain", "ldhName": "example.net"}"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        assert!(domain.registrar_abuse().is_none());
+    }
+
+    #[test]
+    fn test_no_reseller_at_top_level() {
+        let json = r#"{
+            "objectClassName": "domain",
+            "entities": [{"objectClassName": "entity", "handle": "R", "roles": ["reseller"]}]
+        }"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        assert!(domain.registrar().is_none());
+        assert!(domain.registrar_info().is_none());
+        assert!(domain.reseller().is_none());
+    }
+
+    #[test]
+    fn test_summary() {
+        let json = r#"{
+            "objectClassName": "domain",
+            "ldhName": "example.com",
+            "handle": "2336799_DOMAIN_COM-VRSN",
+            "status": ["active", "client transfer prohibited"],
+            "events": [{"eventAction": "expiration", "eventDate": "2026-08-13T04:00:00Z"}]
+        }"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            domain.summary(),
+            "example.com (2336799_DOMAIN_COM-VRSN) [active, client transfer prohibited] expires 2026-08-13"
+        );
+
+        let json = r#"{"objectClassName": "domain", "unicodeName": "müller.de"}"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            domain.summary(),
+            "\u{2068}müller.de\u{2069} (xn--mller-kva.de)"
+        );
+    }
+
+    #[test]
+    fn test_combined_name() {
+        let json = r#"{"objectClassName": "domain", "ldhName": "xn--mller-kva.de", "unicodeName": "müller.de"}"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            domain.combined_name(NameOrder::AsciiFirst).as_deref(),
+            Some("xn--mller-kva.de (\u{2068}müller.de\u{2069})")
+        );
+
+        // Identical forms are shown once
+        let json = r#"{"objectClassName": "domain", "ldhName": "EXAMPLE.com", "unicodeName": "example.com"}"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            domain.combined_name(NameOrder::UnicodeFirst).as_deref(),
+            Some("EXAMPLE.com")
+        );
+    }
+
+    #[test]
+    fn test_combined_name_rtl_is_isolated() {
+        let unicode = "مثال.إختبار";
+        let ascii = idna::domain_to_ascii(unicode).unwrap();
+        let json = serde_json::json!({
+            "objectClassName": "domain",
+            "ldhName": ascii,
+            "unicodeName": unicode,
+        });
+        let domain: Domain = serde_json::from_value(json).unwrap();
+        let combined = domain.combined_name(NameOrder::UnicodeFirst).unwrap();
+        assert_eq!(combined, format!("\u{2068}{unicode}\u{2069} ({ascii})"));
+        // The RTL run is closed before the A-label starts
+        let close = combined.find('\u{2069}').unwrap();
+        assert!(combined[close..].contains(&ascii));
+    }
+
+    #[test]
+    fn test_age_and_time_until_expiry() {
+        let json = r#"{
+            "objectClassName": "domain",
+            "ldhName": "example.com",
+            "events": [
+                {"eventAction": "registration", "eventDate": "2020-01-01T00:00:00Z"},
+                {"eventAction": "expiration", "eventDate": "2026-12-31T00:00:00+02:00"}
+            ]
+        }"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        let now = DateTime::parse_from_rfc3339("2026-10-15T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(domain.age_at(now).unwrap().num_days(), 2479);
+        let remaining = domain.time_until_expiry_at(now).unwrap();
+        assert_eq!(remaining, Duration::days(77) - Duration::hours(2));
+    }
+
+    #[test]
+    fn test_age_missing_events() {
+        let json = r#"{
+            "objectClassName": "domain",
+            "events": [{"eventAction": "registration", "eventDate": "not a date"}]
+        }"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        assert!(domain.age().is_none());
+        assert!(domain.time_until_expiry().is_none());
+    }
+
+    #[test]
+    fn test_ldh_name_preferred() {
+        let json = r#"{"objectClassName": "domain", "ldhName": "xn--mller-kva.de", "unicodeName": "müller.de"}"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        assert_eq!(domain.name(), Some("xn--mller-kva.de"));
+        assert_eq!(domain.ascii_name().as_deref(), Some("xn--mller-kva.de"));
+    }
+}
@@ -1,7 +1,10 @@
 //! Entity (person/organization) model
 
-use super::{Autnum, Event, IpNetwork, Link, Notice, PublicId, Remark, Status, VCard};
+use super::{
+    Autnum, Event, Extension, IpNetwork, Link, Notice, PublicId, Redaction, Remark, Status, VCard,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 /// Entity representing a person or organization
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,9 +12,17 @@ pub struct Entity {
     #[serde(rename = "objectClassName", default)]
     pub object_class_name: Option<String>,
 
-    #[serde(rename = "rdapConformance", default)]
+    #[serde(
+        rename = "rdapConformance",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
     pub conformance: Vec<String>,
 
+    /// Parsed `rdapConformance` (see [`models`](super))
+    #[serde(skip)]
+    pub extensions: BTreeSet<Extension>,
+
     #[serde(default)]
     pub notices: Vec<Notice>,
 
@@ -27,7 +38,7 @@ pub struct Entity {
     #[serde(rename = "publicIds", default)]
     pub public_ids: Vec<PublicId>,
 
-    #[serde(default)]
+    #[serde(default, deserialize_with = "super::common::lenient_vec")]
     pub entities: Vec<Self>,
 
     #[serde(default)]
@@ -56,4 +67,84 @@ pub struct Entity {
 
     #[serde(default)]
     pub lang: Option<String>,
+
+    /// Redacted fields
+    #[serde(default, deserialize_with = "super::common::lenient_vec")]
+    pub redacted: Vec<Redaction>,
+
+    /// Members not covered above (see [`models`](super))
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Entity {
+    /// Whether the entity has the given role (case-insensitive)
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r.eq_ignore_ascii_case(role))
+    }
+
+    /// Human-readable name: vCard `fn`, then `org`, then the handle
+    pub fn display_name(&self) -> Option<&str> {
+        self.vcard
+            .as_ref()
+            .and_then(|v| v.name().filter(|n| !n.is_empty()).or_else(|| v.org()))
+            .or(self.handle.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entities_skip_invalid_elements() {
+        let json = r#"{
+            "objectClassName": "entity",
+            "handle": "PARENT",
+            "entities": [null, "bogus", {"objectClassName": "entity", "handle": "CHILD", "roles": ["abuse"]}]
+        }"#;
+        let entity: Entity = serde_json::from_str(json).unwrap();
+        assert_eq!(entity.entities.len(), 1);
+        assert_eq!(entity.entities[0].handle.as_deref(), Some("CHILD"));
+    }
+
+    #[test]
+    fn test_entities_null_array() {
+        let json = r#"{"objectClassName": "entity", "entities": null}"#;
+        let entity: Entity = serde_json::from_str(json).unwrap();
+        assert!(entity.entities.is_empty());
+    }
+
+    #[test]
+    fn test_as_event_actor_round_trip() {
+        // Shaped like a registro.br entity response
+        let json = r#"{
+            "objectClassName": "entity",
+            "handle": "ABC123",
+            "roles": ["registrant"],
+            "asEventActor": [
+                {"eventAction": "registration", "eventDate": "2005-01-27T12:00:00Z"},
+                {
+                    "eventAction": "last changed",
+                    "eventDate": "2021-06-01T10:15:00Z",
+                    "links": [{
+                        "value": "https://rdap.registro.br/entity/ABC123",
+                        "rel": "related",
+                        "href": "https://rdap.registro.br/domain/example.com.br"
+                    }]
+                }
+            ]
+        }"#;
+        let entity: Entity = serde_json::from_str(json).unwrap();
+        assert_eq!(entity.as_event_actor.len(), 2);
+        assert_eq!(entity.as_event_actor[1].links.len(), 1);
+
+        let value = serde_json::to_value(&entity).unwrap();
+        let reparsed: Entity = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&reparsed).unwrap(), value);
+        assert_eq!(
+            value["asEventActor"][1]["links"][0]["href"],
+            "https://rdap.registro.br/domain/example.com.br"
+        );
+    }
 }
@@ -1,14 +1,23 @@
 //! Error response model
 
-use super::Notice;
+use super::{Extension, Notice};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 /// RDAP error response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorResponse {
-    #[serde(rename = "rdapConformance", default)]
+    #[serde(
+        rename = "rdapConformance",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
     pub conformance: Vec<String>,
 
+    /// Parsed `rdapConformance` (see [`models`](super))
+    #[serde(skip)]
+    pub extensions: BTreeSet<Extension>,
+
     #[serde(default)]
     pub notices: Vec<Notice>,
 
@@ -23,4 +32,8 @@ pub struct ErrorResponse {
 
     #[serde(default)]
     pub lang: Option<String>,
+
+    /// Members not covered above (see [`models`](super))
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
@@ -0,0 +1,80 @@
+//! RDAP extension identifiers from `rdapConformance`
+
+use std::fmt;
+
+/// An `rdapConformance` token
+///
+/// Well-known extensions get their own variant so feature checks don't
+/// compare strings; anything else is kept verbatim in [`Extension::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Extension {
+    /// `rdap_level_0` (RFC 9083)
+    RdapLevel0,
+    /// `cidr0`: CIDR prefixes on IP networks
+    Cidr0,
+    /// `rirSearch1`: RIR search (RFC 9910)
+    RirSearch1,
+    /// `redacted`: redaction markers (RFC 9537)
+    Redacted,
+    /// `paging`: paged search results
+    Paging,
+    /// `sorting`: sorted search results
+    Sorting,
+    /// `subsetting`: field sets in search results
+    Subsetting,
+    /// `nro_rdap_profile_0`: NRO RIR profile
+    NroRdapProfile0,
+    /// `icann_rdap_response_profile_0`: ICANN gTLD response profile
+    IcannRdapResponseProfile0,
+    /// `icann_rdap_technical_implementation_guide_0`
+    IcannRdapTechnicalImplementationGuide0,
+    /// Any other token
+    Other(String),
+}
+
+impl Extension {
+    /// The token as it appears in `rdapConformance`
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::RdapLevel0 => "rdap_level_0",
+            Self::Cidr0 => "cidr0",
+            Self::RirSearch1 => "rirSearch1",
+            Self::Redacted => "redacted",
+            Self::Paging => "paging",
+            Self::Sorting => "sorting",
+            Self::Subsetting => "subsetting",
+            Self::NroRdapProfile0 => "nro_rdap_profile_0",
+            Self::IcannRdapResponseProfile0 => "icann_rdap_response_profile_0",
+            Self::IcannRdapTechnicalImplementationGuide0 => {
+                "icann_rdap_technical_implementation_guide_0"
+            }
+            Self::Other(token) => token,
+        }
+    }
+}
+
+impl From<&str> for Extension {
+    fn from(token: &str) -> Self {
+        match token {
+            "rdap_level_0" => Self::RdapLevel0,
+            "cidr0" => Self::Cidr0,
+            "rirSearch1" => Self::RirSearch1,
+            "redacted" => Self::Redacted,
+            "paging" => Self::Paging,
+            "sorting" => Self::Sorting,
+            "subsetting" => Self::Subsetting,
+            "nro_rdap_profile_0" => Self::NroRdapProfile0,
+            "icann_rdap_response_profile_0" => Self::IcannRdapResponseProfile0,
+            "icann_rdap_technical_implementation_guide_0" => {
+                Self::IcannRdapTechnicalImplementationGuide0
+            }
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Extension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
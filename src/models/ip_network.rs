@@ -1,7 +1,9 @@
 //! IP Network model
 
-use super::{Entity, Event, Link, Notice, Remark, Status};
+use super::{Entity, Event, Extension, Link, Notice, Redaction, Remark, Status};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::net::IpAddr;
 
 /// IP Network information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,9 +11,17 @@ pub struct IpNetwork {
     #[serde(rename = "objectClassName", default)]
     pub object_class_name: Option<String>,
 
-    #[serde(rename = "rdapConformance", default)]
+    #[serde(
+        rename = "rdapConformance",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
     pub conformance: Vec<String>,
 
+    /// Parsed `rdapConformance` (see [`models`](super))
+    #[serde(skip)]
+    pub extensions: BTreeSet<Extension>,
+
     #[serde(default)]
     pub notices: Vec<Notice>,
 
@@ -42,7 +52,7 @@ pub struct IpNetwork {
     #[serde(default)]
     pub status: Status,
 
-    #[serde(default)]
+    #[serde(default, deserialize_with = "super::common::lenient_vec")]
     pub entities: Vec<Entity>,
 
     #[serde(default)]
@@ -59,4 +69,150 @@ pub struct IpNetwork {
 
     #[serde(default)]
     pub lang: Option<String>,
+
+    /// Redacted fields
+    #[serde(default, deserialize_with = "super::common::lenient_vec")]
+    pub redacted: Vec<Redaction>,
+
+    /// `cidr0` prefixes; malformed entries are skipped
+    #[serde(
+        rename = "cidr0_cidrs",
+        default,
+        deserialize_with = "super::common::lenient_vec",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub cidr0_cidrs: Vec<Cidr0Cidr>,
+
+    /// Members not covered above (see [`models`](super))
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// CIDR prefix from the `cidr0` extension
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cidr0Cidr {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub v4prefix: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub v6prefix: Option<String>,
+
+    /// Prefix length; `None` if the server left it out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<u8>,
+}
+
+impl Cidr0Cidr {
+    /// Format as CIDR notation (e.g. "193.0.0.0/21"), `None` without a
+    /// prefix or length
+    pub fn to_cidr(&self) -> Option<String> {
+        let prefix = self.v4prefix.as_ref().or(self.v6prefix.as_ref())?;
+        Some(format!("{prefix}/{}", self.length?))
+    }
+}
+
+impl IpNetwork {
+    /// CIDR prefixes from the `cidr0` extension
+    ///
+    /// Ignored when the response declares its conformance without `cidr0`.
+    /// The parsed [`Self::extensions`] are checked when present, otherwise
+    /// the raw `rdapConformance` strings.
+    pub fn cidrs(&self) -> Vec<String> {
+        let declared = if self.extensions.is_empty() {
+            self.conformance.is_empty() || self.conformance.iter().any(|c| c == "cidr0")
+        } else {
+            self.extensions.contains(&Extension::Cidr0)
+        };
+        if !declared {
+            return vec![];
+        }
+        self.cidr0_cidrs
+            .iter()
+            .filter_map(Cidr0Cidr::to_cidr)
+            .collect()
+    }
+
+    /// Number of addresses from `startAddress` to `endAddress`, saturating
+    /// at `u128::MAX` for all of IPv6
+    ///
+    /// `None` if either bound is missing or not an address of the same family.
+    pub fn address_count(&self) -> Option<u128> {
+        let parse = |addr: &Option<String>| addr.as_deref()?.parse::<IpAddr>().ok();
+        let (start, end) = match (parse(&self.start_address)?, parse(&self.end_address)?) {
+            (IpAddr::V4(start), IpAddr::V4(end)) => {
+                (u128::from(start.to_bits()), u128::from(end.to_bits()))
+            }
+            (IpAddr::V6(start), IpAddr::V6(end)) => (start.to_bits(), end.to_bits()),
+            _ => return None,
+        };
+        end.checked_sub(start).map(|span| span.saturating_add(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RdapObject;
+
+    #[test]
+    fn test_cidr0_ripe_response() {
+        let json = r#"{
+            "objectClassName": "ip network",
+            "rdapConformance": ["cidr0", "rdap_level_0"],
+            "handle": "193.0.0.0 - 193.0.7.255",
+            "startAddress": "193.0.0.0",
+            "endAddress": "193.0.7.255",
+            "ipVersion": "v4",
+            "name": "RIPE-NCC",
+            "cidr0_cidrs": [{"v4prefix": "193.0.0.0", "length": 21}]
+        }"#;
+        let net: IpNetwork = serde_json::from_str(json).unwrap();
+        assert_eq!(net.cidrs(), vec!["193.0.0.0/21".to_string()]);
+        assert_eq!(net.address_count(), Some(2048));
+    }
+
+    #[test]
+    fn test_cidr0_requires_conformance() {
+        let json = r#"{
+            "objectClassName": "ip network",
+            "rdapConformance": ["rdap_level_0"],
+            "cidr0_cidrs": [{"v6prefix": "2001:67c:2e8::", "length": 48}]
+        }"#;
+        let net: IpNetwork = serde_json::from_str(json).unwrap();
+        assert!(net.cidrs().is_empty());
+    }
+
+    #[test]
+    fn test_cidr0_parsed_extensions() {
+        let json = r#"{
+            "objectClassName": "ip network",
+            "rdapConformance": ["rdap_level_0", "cidr0"],
+            "cidr0_cidrs": [{"v4prefix": "192.0.2.0", "length": 24}]
+        }"#;
+        let Ok(RdapObject::IpNetwork(net)) = crate::parse_rdap(json) else {
+            panic!("expected an IP network");
+        };
+        assert!(net.extensions.contains(&Extension::Cidr0));
+        assert_eq!(net.cidrs(), vec!["192.0.2.0/24".to_string()]);
+    }
+
+    #[test]
+    fn test_cidr0_malformed_entries_skipped() {
+        let json = r#"{
+            "objectClassName": "ip network",
+            "rdapConformance": ["rdap_level_0", "cidr0"],
+            "name": "EXAMPLE-NET",
+            "cidr0_cidrs": [
+                {"v4prefix": "192.0.2.0"},
+                {"v4prefix": "198.51.100.0", "length": "24"},
+                {"v4prefix": "203.0.113.0", "length": 24}
+            ]
+        }"#;
+        let Ok(RdapObject::IpNetwork(net)) = crate::parse_rdap(json) else {
+            panic!("expected an IP network");
+        };
+        assert_eq!(net.name.as_deref(), Some("EXAMPLE-NET"));
+        assert_eq!(net.cidr0_cidrs.len(), 2);
+        assert_eq!(net.cidrs(), vec!["203.0.113.0/24".to_string()]);
+    }
 }
@@ -1,29 +1,97 @@
 //! RDAP data models
+//!
+//! The object structs share a few members beyond those of RFC 9083:
+//!
+//! - `extensions` holds the parsed `rdapConformance` of the response. Only
+//!   [`parse_rdap`](crate::parse_rdap) fills it in, and only on the
+//!   top-level object; plain `serde_json` deserialization leaves it empty.
+//! - `redacted` lists the fields the server withheld (RFC 9537).
+//! - `extra` keeps members not covered by the struct, such as vendor
+//!   extensions (`fred_*`), so re-serializing doesn't lose them.
 
+pub mod abuse;
 pub mod autnum;
 pub mod common;
+pub mod contact;
 pub mod domain;
 pub mod entity;
 pub mod error;
+pub mod extension;
 pub mod ip_network;
 pub mod nameserver;
 pub mod search;
 pub mod vcard;
 
+pub use abuse::AbuseReport;
 pub use autnum::Autnum;
 pub use common::*;
-pub use domain::Domain;
+pub use contact::{Contact, ContactSource, abuse_contact, contact_by_role};
+pub use domain::{AbuseContact, Domain, NameOrder, Registrar};
 pub use entity::Entity;
 pub use error::ErrorResponse;
-pub use ip_network::IpNetwork;
+pub use extension::Extension;
+pub use ip_network::{Cidr0Cidr, IpNetwork};
 pub use nameserver::Nameserver;
 pub use search::*;
 pub use vcard::VCard;
 
+use crate::error::{RdapError, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Keys that identify the type of an RDAP response, highest priority first
+pub const DISCRIMINATORS: [&str; 5] = [
+    "errorCode",
+    "domainSearchResults",
+    "entitySearchResults",
+    "nameserverSearchResults",
+    "objectClassName",
+];
+
+/// Members that identify an object class when `objectClassName` is missing,
+/// checked in order (`ldhName` alone is shared by domains and nameservers, so
+/// nameserver-only members come first)
+pub const STRUCTURAL_HINTS: [(&str, &str); 9] = [
+    ("startAddress", "ip network"),
+    ("endAddress", "ip network"),
+    ("startAutnum", "autnum"),
+    ("endAutnum", "autnum"),
+    ("ipAddresses", "nameserver"),
+    ("nameservers", "domain"),
+    ("secureDNS", "domain"),
+    ("vcardArray", "entity"),
+    ("ldhName", "domain"),
+];
+
+/// Default limit on how deeply entities may nest inside each other
+pub const DEFAULT_MAX_ENTITY_DEPTH: usize = 10;
+
+/// How [`RdapObject::parse_with`] treats a response body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Reject responses missing members RFC 9083 requires
+    /// (see [`RdapObject::from_json_strict`])
+    pub strict: bool,
+    /// Entities nested deeper than this many levels are cut off with a remark
+    pub max_entity_depth: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            strict: false,
+            max_entity_depth: DEFAULT_MAX_ENTITY_DEPTH,
+        }
+    }
+}
 
 /// Top-level RDAP response object
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Deserialization detects the variant the same way as [`crate::parse_rdap`]
+/// rather than trying each variant in order. `rdapConformance` is only kept on
+/// the top-level object, so re-serializing yields conformant JSON even when a
+/// server repeats it on nested objects.
+#[derive(Debug, Clone, Serialize)]
 #[serde(untagged)]
 pub enum RdapObject {
     Domain(Domain),
@@ -38,15 +106,911 @@ pub enum RdapObject {
     Help(HelpResponse),
 }
 
+impl RdapObject {
+    /// Build an object from parsed JSON, detecting its type
+    ///
+    /// Classification follows [`DISCRIMINATORS`]: an error response wins over
+    /// search results, search results win over `objectClassName`. Without a
+    /// known `objectClassName` the class is guessed from [`STRUCTURAL_HINTS`],
+    /// and anything else is treated as a help response.
+    ///
+    /// `rdapConformance` members on nested objects are dropped, and entities
+    /// nested deeper than [`DEFAULT_MAX_ENTITY_DEPTH`] are cut off with a
+    /// remark. The top-level `rdapConformance` is parsed into
+    /// [`Self::extensions_set`].
+    ///
+    /// A body with no object, search results, error code or notices is
+    /// [`RdapError::NotFound`] rather than an empty [`Self::Help`].
+    pub fn from_json_value(value: serde_json::Value) -> Result<Self> {
+        Self::from_value_with_depth(value, DEFAULT_MAX_ENTITY_DEPTH)
+    }
+
+    /// Parse a response body with the given options
+    ///
+    /// Like [`crate::parse_rdap`], or [`Self::from_json_strict`] when
+    /// `options.strict` is set, but with `options.max_entity_depth` as the
+    /// entity nesting limit.
+    pub fn parse_with(json: &str, options: &ParseOptions) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        if options.strict {
+            let missing = missing_members(&value);
+            if !missing.is_empty() {
+                return Err(RdapError::NonConformant { missing });
+            }
+        }
+        Self::from_value_with_depth(value, options.max_entity_depth)
+    }
+
+    /// [`Self::from_json_value`] with entities cut off below `max_depth`
+    fn from_value_with_depth(mut value: serde_json::Value, max_depth: usize) -> Result<Self> {
+        strip_nested_conformance(&mut value);
+        truncate_entity_depth(&mut value, max_depth);
+
+        let extensions: BTreeSet<Extension> = value
+            .get("rdapConformance")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str())
+            .map(Extension::from)
+            .collect();
+        let mut object = Self::classify(value)?;
+        *object.extensions_mut() = extensions;
+        Ok(object)
+    }
+
+    /// Parse a response body, rejecting it if it lacks members RFC 9083
+    /// requires
+    ///
+    /// Where [`crate::parse_rdap`] fills in what's missing, this fails with
+    /// [`RdapError::NonConformant`] listing every absent member: the
+    /// top-level `rdapConformance`, `errorCode` on error responses, and
+    /// `objectClassName` on the object and each object nested in it or in
+    /// search results. Otherwise it parses like [`Self::from_json_value`].
+    pub fn from_json_strict(json: &str) -> Result<Self> {
+        Self::parse_with(
+            json,
+            &ParseOptions {
+                strict: true,
+                ..ParseOptions::default()
+            },
+        )
+    }
+
+    /// Deserialize into the variant picked by [`DISCRIMINATORS`]
+    fn classify(mut value: serde_json::Value) -> Result<Self> {
+        // Detect object type
+        let Some(obj) = value.as_object() else {
+            return Err(RdapError::Json(serde::de::Error::custom(
+                "Invalid RDAP response",
+            )));
+        };
+
+        let present: Vec<&str> = DISCRIMINATORS
+            .iter()
+            .copied()
+            .filter(|key| obj.contains_key(*key))
+            .collect();
+        if present.len() > 1 {
+            log::warn!(
+                "Ambiguous RDAP response contains {}; classifying by {}",
+                present.join(", "),
+                present[0]
+            );
+        }
+
+        // Check for error
+        if obj.contains_key("errorCode") {
+            return Ok(Self::Error(from_value(value, "error")?));
+        }
+
+        // Check for search results
+        if obj.contains_key("domainSearchResults") {
+            return Ok(Self::DomainSearch(from_value(
+                value,
+                "domainSearchResults",
+            )?));
+        }
+        if obj.contains_key("entitySearchResults") {
+            return Ok(Self::EntitySearch(from_value(
+                value,
+                "entitySearchResults",
+            )?));
+        }
+        if obj.contains_key("nameserverSearchResults") {
+            return Ok(Self::NameserverSearch(from_value(
+                value,
+                "nameserverSearchResults",
+            )?));
+        }
+
+        // Check objectClassName, then guess from the members present since
+        // some servers omit it
+        let class_name = obj
+            .get("objectClassName")
+            .and_then(|v| v.as_str())
+            .filter(|c| {
+                matches!(
+                    *c,
+                    "domain" | "entity" | "nameserver" | "autnum" | "ip network"
+                )
+            })
+            .or_else(|| {
+                let (key, class_name) = STRUCTURAL_HINTS
+                    .iter()
+                    .find(|(key, _)| obj.contains_key(*key))?;
+                log::debug!("No usable objectClassName; classifying as {class_name} by {key}");
+                Some(class_name)
+            })
+            .map(str::to_string);
+
+        // Some registries answer 200 with nothing but `rdapConformance`; with
+        // no object and no help notices either, that means "not found"
+        let has_notices = obj
+            .get("notices")
+            .and_then(|v| v.as_array())
+            .is_some_and(|notices| !notices.is_empty());
+        if class_name.is_none() && !obj.contains_key("objectClassName") && !has_notices {
+            return Err(RdapError::NotFound);
+        }
+
+        // Record the guess so re-serialized output carries it
+        if let (Some(class_name), Some(obj)) = (&class_name, value.as_object_mut()) {
+            obj.entry("objectClassName")
+                .or_insert_with(|| class_name.as_str().into());
+        }
+
+        match class_name.as_deref() {
+            Some("domain") => Ok(Self::Domain(from_value(value, "domain")?)),
+            Some("entity") => Ok(Self::Entity(from_value(value, "entity")?)),
+            Some("nameserver") => Ok(Self::Nameserver(from_value(value, "nameserver")?)),
+            Some("autnum") => Ok(Self::Autnum(from_value(value, "autnum")?)),
+            Some("ip network") => Ok(Self::IpNetwork(from_value(value, "ip network")?)),
+            // Default to Help
+            _ => Ok(Self::Help(from_value(value, "help")?)),
+        }
+    }
+
+    /// Extensions the server declared in the top-level `rdapConformance`
+    pub const fn extensions_set(&self) -> &BTreeSet<Extension> {
+        match self {
+            Self::Domain(d) => &d.extensions,
+            Self::Entity(e) => &e.extensions,
+            Self::Nameserver(ns) => &ns.extensions,
+            Self::Autnum(a) => &a.extensions,
+            Self::IpNetwork(ip) => &ip.extensions,
+            Self::Error(err) => &err.extensions,
+            Self::DomainSearch(r) => &r.extensions,
+            Self::EntitySearch(r) => &r.extensions,
+            Self::NameserverSearch(r) => &r.extensions,
+            Self::Help(h) => &h.extensions,
+        }
+    }
+
+    const fn extensions_mut(&mut self) -> &mut BTreeSet<Extension> {
+        match self {
+            Self::Domain(d) => &mut d.extensions,
+            Self::Entity(e) => &mut e.extensions,
+            Self::Nameserver(ns) => &mut ns.extensions,
+            Self::Autnum(a) => &mut a.extensions,
+            Self::IpNetwork(ip) => &mut ip.extensions,
+            Self::Error(err) => &mut err.extensions,
+            Self::DomainSearch(r) => &mut r.extensions,
+            Self::EntitySearch(r) => &mut r.extensions,
+            Self::NameserverSearch(r) => &mut r.extensions,
+            Self::Help(h) => &mut h.extensions,
+        }
+    }
+
+    /// Canonical URL of the object (its `self` link), for sharing
+    pub fn source_url(&self) -> Option<&str> {
+        self.self_link_entry().map(|l| l.href.as_str())
+    }
+
+    /// The object's `self` link as a URL, to tell whether two responses
+    /// describe the same object or to key a cache on
+    ///
+    /// A relative `href` is resolved against the link's `value` (its context
+    /// URI). `None` for searches, errors and help, which carry no links, and
+    /// for objects whose `self` link doesn't parse.
+    pub fn self_link(&self) -> Option<url::Url> {
+        self.self_link_entry()?.url()
+    }
+
+    fn self_link_entry(&self) -> Option<&Link> {
+        let links = match self {
+            Self::Domain(d) => &d.links,
+            Self::Entity(e) => &e.links,
+            Self::Nameserver(ns) => &ns.links,
+            Self::Autnum(a) => &a.links,
+            Self::IpNetwork(ip) => &ip.links,
+            _ => return None,
+        };
+        links.iter().find(|l| l.rel.as_deref() == Some("self"))
+    }
+
+    /// WHOIS server (`port43`) of the object, if it has one
+    pub fn port43(&self) -> Option<&str> {
+        match self {
+            Self::Domain(d) => d.port43.as_deref(),
+            Self::Entity(e) => e.port43.as_deref(),
+            Self::Nameserver(ns) => ns.port43.as_deref(),
+            Self::Autnum(a) => a.port43.as_deref(),
+            Self::IpNetwork(ip) => ip.port43.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RdapObject {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        Self::from_json_value(value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Remove `rdapConformance` from every object below the top level
+///
+/// RFC 9083 only allows it on the top-level response, but some servers repeat
+/// it on nested entities and networks.
+fn strip_nested_conformance(value: &mut serde_json::Value) {
+    fn strip(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                if map.remove("rdapConformance").is_some() {
+                    log::debug!("Dropping rdapConformance from nested object");
+                }
+                map.values_mut().for_each(strip);
+            }
+            serde_json::Value::Array(items) => items.iter_mut().for_each(strip),
+            _ => {}
+        }
+    }
+
+    if let serde_json::Value::Object(map) = value {
+        map.values_mut().for_each(strip);
+    }
+}
+
+/// Drop `entities` nested more than `max_depth` levels deep
+///
+/// Guards against runaway recursion from buggy or malicious servers. The
+/// object whose entities were dropped gets a remark saying so.
+fn truncate_entity_depth(value: &mut serde_json::Value, max_depth: usize) {
+    fn walk(value: &mut serde_json::Value, depth: usize, max_depth: usize) {
+        match value {
+            serde_json::Value::Object(map) => {
+                if depth >= max_depth && map.remove("entities").is_some() {
+                    log::warn!("Dropping entities nested deeper than {max_depth} levels");
+                    let remark = serde_json::json!({
+                        "title": "Truncated",
+                        "description": [format!(
+                            "Entities nested deeper than {max_depth} levels were omitted"
+                        )]
+                    });
+                    match map.get_mut("remarks") {
+                        Some(serde_json::Value::Array(remarks)) => remarks.push(remark),
+                        _ => {
+                            map.insert("remarks".to_string(), serde_json::json!([remark]));
+                        }
+                    }
+                }
+                for (key, child) in map.iter_mut() {
+                    let child_depth = if key == "entities" { depth + 1 } else { depth };
+                    walk(child, child_depth, max_depth);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    walk(item, depth, max_depth);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    walk(value, 0, max_depth);
+}
+
+/// Required RFC 9083 members absent from a response, as paths such as
+/// `entities[0].objectClassName`
+fn missing_members(value: &serde_json::Value) -> Vec<String> {
+    let mut missing = Vec::new();
+    let Some(obj) = value.as_object() else {
+        return missing;
+    };
+    if !obj.contains_key("rdapConformance") {
+        missing.push("rdapConformance".to_string());
+    }
+    if obj.contains_key("errorCode") {
+        return missing;
+    }
+
+    let search_key = [
+        "domainSearchResults",
+        "entitySearchResults",
+        "nameserverSearchResults",
+    ]
+    .into_iter()
+    .find(|key| obj.contains_key(*key));
+    if let Some(key) = search_key {
+        missing_in_array(obj, key, "", &mut missing);
+    } else if obj.contains_key("objectClassName")
+        || STRUCTURAL_HINTS
+            .iter()
+            .any(|(key, _)| obj.contains_key(*key))
+    {
+        missing_in_object(value, "", &mut missing);
+    }
+    missing
+}
+
+/// Required members absent from an error body (a non-success HTTP status)
+pub(crate) fn missing_error_members(value: &serde_json::Value) -> Vec<String> {
+    let mut missing = missing_members(value);
+    if value.get("errorCode").is_none() {
+        missing.push("errorCode".to_string());
+    }
+    missing
+}
+
+/// Record a missing `objectClassName` on `value` and the objects nested in it
+fn missing_in_object(value: &serde_json::Value, path: &str, missing: &mut Vec<String>) {
+    let Some(obj) = value.as_object() else {
+        return;
+    };
+    if !obj
+        .get("objectClassName")
+        .is_some_and(serde_json::Value::is_string)
+    {
+        missing.push(format!("{path}objectClassName"));
+    }
+    for key in ["entities", "nameservers", "networks", "autnums"] {
+        missing_in_array(obj, key, path, missing);
+    }
+    if let Some(network) = obj.get("network") {
+        missing_in_object(network, &format!("{path}network."), missing);
+    }
+}
+
+fn missing_in_array(
+    obj: &serde_json::Map<String, serde_json::Value>,
+    key: &str,
+    path: &str,
+    missing: &mut Vec<String>,
+) {
+    let items = obj
+        .get(key)
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten();
+    for (i, item) in items.enumerate() {
+        missing_in_object(item, &format!("{path}{key}[{i}]."), missing);
+    }
+}
+
+/// Deserialize a typed RDAP object, reporting which object class failed
+fn from_value<T: serde::de::DeserializeOwned>(
+    value: serde_json::Value,
+    object_class: &str,
+) -> Result<T> {
+    serde_json::from_value(value).map_err(|e| RdapError::Deserialize {
+        object_class: object_class.to_string(),
+        message: e.to_string(),
+    })
+}
+
 /// Help response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HelpResponse {
-    #[serde(rename = "rdapConformance", default)]
+    #[serde(
+        rename = "rdapConformance",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
     pub conformance: Vec<String>,
 
+    /// Parsed `rdapConformance` (see the [module docs](self))
+    #[serde(skip)]
+    pub extensions: BTreeSet<Extension>,
+
     #[serde(default)]
     pub notices: Vec<Notice>,
 
     #[serde(default)]
     pub lang: Option<String>,
+
+    /// Members not covered above (see the [module docs](self))
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl HelpResponse {
+    /// Notices with one per `type`, preferring those in `lang`
+    ///
+    /// See [`select_notices`] for the selection rules.
+    pub fn notices_in_lang(&self, lang: &str) -> Vec<&Notice> {
+        select_notices(&self.notices, Some(lang))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str_classifies_by_object_class() {
+        // Would match `Domain` first under untagged deserialization
+        let obj: RdapObject = serde_json::from_str(
+            r#"{"objectClassName": "nameserver", "ldhName": "ns1.example.com"}"#,
+        )
+        .unwrap();
+        assert!(matches!(obj, RdapObject::Nameserver(_)));
+
+        // Would match `Entity` first under untagged deserialization
+        let obj: RdapObject =
+            serde_json::from_str(r#"{"objectClassName": "autnum", "startAutnum": 64500}"#).unwrap();
+        assert!(matches!(obj, RdapObject::Autnum(_)));
+
+        let obj: RdapObject = serde_json::from_str(
+            r#"{"objectClassName": "ip network", "startAddress": "192.0.2.0"}"#,
+        )
+        .unwrap();
+        assert!(matches!(obj, RdapObject::IpNetwork(_)));
+
+        let obj: RdapObject = serde_json::from_str(r#"{"errorCode": 404}"#).unwrap();
+        assert!(matches!(obj, RdapObject::Error(_)));
+
+        let obj: RdapObject = serde_json::from_str(r#"{"entitySearchResults": []}"#).unwrap();
+        assert!(matches!(obj, RdapObject::EntitySearch(_)));
+    }
+
+    #[test]
+    fn test_ambiguous_bodies_follow_priority() {
+        // Error response wins over everything
+        let obj = RdapObject::from_json_value(serde_json::json!({
+            "errorCode": 404,
+            "objectClassName": "domain",
+            "domainSearchResults": []
+        }))
+        .unwrap();
+        assert!(matches!(obj, RdapObject::Error(_)));
+
+        // Search results win over objectClassName
+        let obj = RdapObject::from_json_value(serde_json::json!({
+            "objectClassName": "entity",
+            "domainSearchResults": [{"objectClassName": "domain", "ldhName": "example.com"}]
+        }))
+        .unwrap();
+        assert!(matches!(obj, RdapObject::DomainSearch(r) if r.domains.len() == 1));
+
+        // Among search results, domain > entity > nameserver
+        let obj = RdapObject::from_json_value(serde_json::json!({
+            "nameserverSearchResults": [],
+            "entitySearchResults": []
+        }))
+        .unwrap();
+        assert!(matches!(obj, RdapObject::EntitySearch(_)));
+
+        // Unknown objectClassName falls back to help
+        let obj = RdapObject::from_json_value(serde_json::json!({
+            "objectClassName": "widget"
+        }))
+        .unwrap();
+        assert!(matches!(obj, RdapObject::Help(_)));
+    }
+
+    #[test]
+    fn test_source_url_is_self_link() {
+        let obj: RdapObject = serde_json::from_str(
+            r#"{
+                "objectClassName": "domain",
+                "ldhName": "example.com",
+                "links": [
+                    {"rel": "related", "href": "https://rdap.registrar.example/domain/example.com"},
+                    {"rel": "self", "href": "https://rdap.example/domain/example.com"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            obj.source_url(),
+            Some("https://rdap.example/domain/example.com")
+        );
+
+        let obj: RdapObject = serde_json::from_str(r#"{"objectClassName": "autnum"}"#).unwrap();
+        assert!(obj.source_url().is_none());
+    }
+
+    #[test]
+    fn test_self_link_identifies_object() {
+        let parse = |json: &str| serde_json::from_str::<RdapObject>(json).unwrap();
+        let registry = parse(
+            r#"{
+                "objectClassName": "ip network",
+                "links": [{"rel": "self", "href": "https://rdap.example/ip/192.0.2.0/24"}]
+            }"#,
+        );
+        let relative = parse(
+            r#"{
+                "objectClassName": "ip network",
+                "links": [{
+                    "rel": "self",
+                    "value": "https://rdap.example/ip/192.0.2.1",
+                    "href": "/ip/192.0.2.0/24"
+                }]
+            }"#,
+        );
+        assert_eq!(
+            registry.self_link().unwrap().as_str(),
+            "https://rdap.example/ip/192.0.2.0/24"
+        );
+        assert_eq!(registry.self_link(), relative.self_link());
+
+        let search = parse(r#"{"domainSearchResults": []}"#);
+        assert!(search.self_link().is_none());
+    }
+
+    #[test]
+    fn test_from_json_strict_lists_missing_members() {
+        let err = RdapObject::from_json_strict(
+            r#"{
+                "ldhName": "example.com",
+                "entities": [
+                    {"objectClassName": "entity", "handle": "R1"},
+                    {"handle": "R2", "entities": [{"handle": "R3"}]}
+                ],
+                "nameservers": [{"ldhName": "ns1.example.com"}]
+            }"#,
+        )
+        .unwrap_err();
+        let RdapError::NonConformant { missing } = err else {
+            panic!("expected NonConformant, got {err:?}");
+        };
+        assert_eq!(
+            missing,
+            [
+                "rdapConformance",
+                "objectClassName",
+                "entities[1].objectClassName",
+                "entities[1].entities[0].objectClassName",
+                "nameservers[0].objectClassName",
+            ]
+        );
+
+        let err = RdapObject::from_json_strict(
+            r#"{"rdapConformance": ["rdap_level_0"], "domainSearchResults": [{"ldhName": "a.example"}]}"#,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, RdapError::NonConformant { missing } if missing == ["domainSearchResults[0].objectClassName"])
+        );
+
+        let obj = RdapObject::from_json_strict(
+            r#"{"rdapConformance": ["rdap_level_0"], "objectClassName": "autnum", "startAutnum": 64496}"#,
+        )
+        .unwrap();
+        assert!(matches!(obj, RdapObject::Autnum(_)));
+
+        let missing = missing_error_members(&serde_json::json!({"title": "Oops"}));
+        assert_eq!(missing, ["rdapConformance", "errorCode"]);
+    }
+
+    #[test]
+    fn test_from_str_reports_class_on_error() {
+        let err = serde_json::from_str::<RdapObject>(
+            r#"{"objectClassName": "autnum", "startAutnum": "x"}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("autnum"));
+    }
+
+    #[test]
+    fn test_help_notices_in_lang() {
+        let help: HelpResponse = serde_json::from_str(
+            r#"{
+                "lang": "en",
+                "notices": [
+                    {"type": "terms", "lang": "ja", "title": "利用規約", "description": []},
+                    {"type": "terms", "lang": "en", "title": "Terms of Service", "description": []}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let notices = help.notices_in_lang("ja");
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].title.as_deref(), Some("利用規約"));
+        let notices = help.notices_in_lang("en");
+        assert_eq!(notices[0].title.as_deref(), Some("Terms of Service"));
+    }
+
+    #[test]
+    fn test_nested_conformance_dropped() {
+        let obj = crate::parse_rdap(
+            r#"{
+                "objectClassName": "entity",
+                "handle": "XTOM",
+                "rdapConformance": ["rdap_level_0"],
+                "entities": [{
+                    "objectClassName": "entity",
+                    "handle": "ABUSE",
+                    "rdapConformance": ["rdap_level_0"]
+                }],
+                "networks": [{
+                    "objectClassName": "ip network",
+                    "rdapConformance": ["rdap_level_0", "cidr0"]
+                }]
+            }"#,
+        )
+        .unwrap();
+        let RdapObject::Entity(entity) = &obj else {
+            panic!("expected entity");
+        };
+        assert_eq!(entity.conformance, ["rdap_level_0"]);
+        assert!(entity.entities[0].conformance.is_empty());
+        assert!(entity.networks[0].conformance.is_empty());
+
+        let json = serde_json::to_value(&obj).unwrap();
+        assert_eq!(json["rdapConformance"], serde_json::json!(["rdap_level_0"]));
+        assert!(json["entities"][0].get("rdapConformance").is_none());
+        assert!(json["networks"][0].get("rdapConformance").is_none());
+    }
+
+    #[test]
+    fn test_extensions_set() {
+        let obj = crate::parse_rdap(
+            r#"{
+                "objectClassName": "ip network",
+                "rdapConformance": ["rdap_level_0", "cidr0", "rirSearch1", "x_vendor_ext", "cidr0"],
+                "entities": [{
+                    "objectClassName": "entity",
+                    "rdapConformance": ["redacted"]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let extensions: Vec<&Extension> = obj.extensions_set().iter().collect();
+        assert_eq!(
+            extensions,
+            [
+                &Extension::RdapLevel0,
+                &Extension::Cidr0,
+                &Extension::RirSearch1,
+                &Extension::Other("x_vendor_ext".to_string()),
+            ]
+        );
+        assert!(!obj.extensions_set().contains(&Extension::Redacted));
+        assert_eq!(
+            Extension::Other("x_vendor_ext".into()).to_string(),
+            "x_vendor_ext"
+        );
+
+        let RdapObject::IpNetwork(network) = &obj else {
+            panic!("expected IP network");
+        };
+        assert!(network.entities[0].extensions.is_empty());
+
+        let obj = crate::parse_rdap(r#"{"objectClassName": "domain"}"#).unwrap();
+        assert!(obj.extensions_set().is_empty());
+    }
+
+    #[test]
+    fn test_deep_entities_truncated() {
+        // Each level wraps the previous one: depth 15 in total
+        let mut nested = serde_json::json!({"objectClassName": "entity", "handle": "L15"});
+        for level in (1..15).rev() {
+            nested = serde_json::json!({
+                "objectClassName": "entity",
+                "handle": format!("L{level}"),
+                "entities": [nested]
+            });
+        }
+        let value = serde_json::json!({
+            "objectClassName": "domain",
+            "ldhName": "example.com",
+            "entities": [nested]
+        });
+
+        let depth_of = |obj: RdapObject| {
+            let RdapObject::Domain(domain) = obj else {
+                panic!("expected domain");
+            };
+            let mut depth = 0;
+            let mut entities = &domain.entities;
+            let mut last = None;
+            while let Some(entity) = entities.first() {
+                depth += 1;
+                last = Some(entity.clone());
+                entities = &entity.entities;
+            }
+            (depth, last.unwrap())
+        };
+
+        let (depth, last) = depth_of(RdapObject::from_json_value(value.clone()).unwrap());
+        assert_eq!(depth, DEFAULT_MAX_ENTITY_DEPTH);
+        assert_eq!(last.handle.as_deref(), Some("L10"));
+        assert_eq!(last.remarks[0].title.as_deref(), Some("Truncated"));
+
+        // The limit is a per-call option
+        let options = ParseOptions {
+            max_entity_depth: 3,
+            ..ParseOptions::default()
+        };
+        let (depth, last) = depth_of(RdapObject::parse_with(&value.to_string(), &options).unwrap());
+        assert_eq!(depth, 3);
+        assert_eq!(last.handle.as_deref(), Some("L3"));
+    }
+
+    #[test]
+    fn test_missing_object_class_detected_structurally() {
+        let cases = [
+            (
+                r#"{"ldhName": "ns1.example.com", "ipAddresses": {"v4": ["192.0.2.53"]}}"#,
+                "nameserver",
+            ),
+            (
+                r#"{"handle": "NET-192-0-2-0-1", "startAddress": "192.0.2.0", "endAddress": "192.0.2.255"}"#,
+                "ip network",
+            ),
+            (
+                r#"{"handle": "AS64500", "startAutnum": 64500, "endAutnum": 64500}"#,
+                "autnum",
+            ),
+            (r#"{"ldhName": "example.com", "nameservers": []}"#, "domain"),
+            (
+                r#"{"handle": "XTOM", "vcardArray": ["vcard", [["fn", {}, "text", "xTom"]]]}"#,
+                "entity",
+            ),
+            (
+                r#"{"notices": [{"title": "Terms of Service", "description": []}]}"#,
+                "help",
+            ),
+        ];
+        for (json, class_name) in cases {
+            let obj = crate::parse_rdap(json).unwrap();
+            assert_eq!(variant_class(&obj), class_name, "{json}");
+        }
+    }
+
+    /// Trimmed real responses from each RIR, with the expected variant
+    const RIR_SAMPLES: [(&str, &str, &str); 6] = [
+        (
+            "ARIN",
+            "ip network",
+            r#"{
+                "rdapConformance": ["nro_rdap_profile_0", "rdap_level_0", "cidr0", "arin_originas0"],
+                "notices": [{"title": "Terms of Service", "description": ["By using the ARIN RDAP/Whois service, you are agreeing to the RDAP/Whois Terms of Use"]}],
+                "handle": "NET-8-8-8-0-2",
+                "startAddress": "8.8.8.0",
+                "endAddress": "8.8.8.255",
+                "ipVersion": "v4",
+                "name": "GOGL",
+                "type": "DIRECT ALLOCATION",
+                "parentHandle": "NET-8-0-0-0-0",
+                "cidr0_cidrs": [{"v4prefix": "8.8.8.0", "length": 24}],
+                "events": [{"eventAction": "last changed", "eventDate": "2023-12-28T17:24:56-05:00"}],
+                "entities": [{"handle": "GOGL", "roles": ["registrant"], "objectClassName": "entity",
+                    "vcardArray": ["vcard", [["version", {}, "text", "4.0"], ["fn", {}, "text", "Google LLC"], ["kind", {}, "text", "org"]]]}],
+                "status": ["active"],
+                "objectClassName": "ip network"
+            }"#,
+        ),
+        (
+            "RIPE",
+            "autnum",
+            r#"{
+                "handle": "AS3333",
+                "name": "RIPE-NCC-AS",
+                "startAutnum": 3333,
+                "endAutnum": 3333,
+                "objectClassName": "autnum",
+                "entities": [{"handle": "ORG-RIEN1-RIPE", "roles": ["registrant"], "objectClassName": "entity"}],
+                "links": [{"value": "https://rdap.db.ripe.net/autnum/3333", "rel": "self", "href": "https://rdap.db.ripe.net/autnum/3333"}],
+                "events": [{"eventAction": "registration", "eventDate": "1970-01-01T00:00:00Z"}],
+                "rdapConformance": ["nro_rdap_profile_asn_flat_0", "cidr0", "rdap_level_0", "nro_rdap_profile_0", "redacted"],
+                "port43": "whois.ripe.net"
+            }"#,
+        ),
+        (
+            "APNIC",
+            "entity",
+            r#"{
+                "handle": "IRT-APNICRANDNET-AU",
+                "vcardArray": ["vcard", [["version", {}, "text", "4.0"], ["fn", {}, "text", "IRT-APNICRANDNET-AU"], ["kind", {}, "text", "group"],
+                    ["email", {}, "text", "helpdesk@apnic.net"], ["email", {"pref": "1"}, "text", "helpdesk@apnic.net"]]],
+                "roles": ["abuse"],
+                "objectClassName": "entity",
+                "rdapConformance": ["history_version_0", "nro_rdap_profile_0", "apnic_history_0", "cidr0", "rdap_level_0"],
+                "port43": "whois.apnic.net"
+            }"#,
+        ),
+        (
+            "LACNIC",
+            "ip network",
+            r#"{
+                "rdapConformance": ["rdap_level_0", "cidr0", "nro_rdap_profile_0", "nro_rdap_profile_asn_hierarchical_0"],
+                "lang": "en",
+                "handle": "200.160.0.0/20",
+                "startAddress": "200.160.0.0",
+                "endAddress": "200.160.15.255",
+                "ipVersion": "v4",
+                "name": "Núcleo de Inf. e Coord. do Ponto BR - NIC.BR",
+                "type": "ALLOCATED PORTABLE",
+                "country": "BR",
+                "parentHandle": "200.0.0.0/8",
+                "status": ["active"],
+                "objectClassName": "ip network"
+            }"#,
+        ),
+        (
+            "AFRINIC",
+            "ip network",
+            r#"{
+                "rdapConformance": ["rdap_level_0", "nro_rdap_profile_0", "cidr0"],
+                "handle": "196.216.2.0 - 196.216.3.255",
+                "startAddress": "196.216.2.0",
+                "endAddress": "196.216.3.255",
+                "ipVersion": "v4",
+                "name": "AFRINIC-Services",
+                "type": "ASSIGNED PI",
+                "country": "MU",
+                "cidr0_cidrs": [{"v4prefix": "196.216.2.0", "length": 23}]
+            }"#,
+        ),
+        (
+            "Nameserver without objectClassName",
+            "nameserver",
+            r#"{
+                "rdapConformance": ["rdap_level_0"],
+                "ldhName": "ns1.apnic.net",
+                "ipAddresses": {"v4": ["202.12.29.25"], "v6": ["2001:dc0:2001:a:4608::25"]},
+                "status": ["active"]
+            }"#,
+        ),
+    ];
+
+    /// Class name of the variant an object was parsed into
+    fn variant_class(obj: &RdapObject) -> &'static str {
+        match obj {
+            RdapObject::Domain(_) => "domain",
+            RdapObject::Entity(_) => "entity",
+            RdapObject::Nameserver(_) => "nameserver",
+            RdapObject::Autnum(_) => "autnum",
+            RdapObject::IpNetwork(_) => "ip network",
+            RdapObject::Error(_) => "error",
+            RdapObject::DomainSearch(_) => "domain search",
+            RdapObject::EntitySearch(_) => "entity search",
+            RdapObject::NameserverSearch(_) => "nameserver search",
+            RdapObject::Help(_) => "help",
+        }
+    }
+
+    #[test]
+    fn test_rir_samples_classify_and_round_trip() {
+        for (source, class_name, json) in RIR_SAMPLES {
+            let obj = crate::parse_rdap(json).unwrap();
+            assert_eq!(variant_class(&obj), class_name, "{source}");
+
+            // Serializing and parsing again keeps the variant and the data
+            let value = serde_json::to_value(&obj).unwrap();
+            let again = RdapObject::from_json_value(value.clone()).unwrap();
+            assert_eq!(variant_class(&again), class_name, "{source}");
+            assert_eq!(serde_json::to_value(&again).unwrap(), value, "{source}");
+            assert_eq!(again.extensions_set(), obj.extensions_set(), "{source}");
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let json = r#"{"objectClassName": "entity", "handle": "XTOM"}"#;
+        let obj: RdapObject = serde_json::from_str(json).unwrap();
+        let again: RdapObject =
+            serde_json::from_value(serde_json::to_value(&obj).unwrap()).unwrap();
+        assert!(matches!(again, RdapObject::Entity(e) if e.handle.as_deref() == Some("XTOM")));
+    }
 }
@@ -1,7 +1,9 @@
 //! Nameserver model
 
-use super::{Entity, Event, Link, Notice, Remark, Status};
+use super::{Entity, Event, Extension, Link, Notice, Redaction, Remark, Status};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::net::IpAddr;
 
 /// Nameserver information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,9 +11,17 @@ pub struct Nameserver {
     #[serde(rename = "objectClassName", default)]
     pub object_class_name: Option<String>,
 
-    #[serde(rename = "rdapConformance", default)]
+    #[serde(
+        rename = "rdapConformance",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
     pub conformance: Vec<String>,
 
+    /// Parsed `rdapConformance` (see [`models`](super))
+    #[serde(skip)]
+    pub extensions: BTreeSet<Extension>,
+
     #[serde(default)]
     pub notices: Vec<Notice>,
 
@@ -27,7 +37,7 @@ pub struct Nameserver {
     #[serde(rename = "ipAddresses", default)]
     pub ip_addresses: Option<IpAddressSet>,
 
-    #[serde(default)]
+    #[serde(default, deserialize_with = "super::common::lenient_vec")]
     pub entities: Vec<Entity>,
 
     #[serde(default)]
@@ -47,10 +57,22 @@ pub struct Nameserver {
 
     #[serde(default)]
     pub lang: Option<String>,
+
+    /// Redacted fields
+    #[serde(default, deserialize_with = "super::common::lenient_vec")]
+    pub redacted: Vec<Redaction>,
+
+    /// Members not covered above (see [`models`](super))
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// IP address set for nameserver
+///
+/// Besides the RFC 9083 `{"v4": [...], "v6": [...]}` object, a flat array of
+/// addresses (as some legacy servers send) is accepted and split by family.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct IpAddressSet {
     #[serde(default)]
     pub v4: Vec<String>,
@@ -58,3 +80,61 @@ pub struct IpAddressSet {
     #[serde(default)]
     pub v6: Vec<String>,
 }
+
+impl<'de> Deserialize<'de> for IpAddressSet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if !value.is_array() {
+            return Self::deserialize(value).map_err(D::Error::custom);
+        }
+
+        let addresses: Vec<String> = serde_json::from_value(value).map_err(D::Error::custom)?;
+        let (v6, v4) = addresses.into_iter().partition(|addr| {
+            addr.parse::<IpAddr>()
+                .map_or_else(|_| addr.contains(':'), |ip| ip.is_ipv6())
+        });
+        Ok(Self { v4, v6 })
+    }
+}
+
+impl Serialize for IpAddressSet {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Self::serialize(self, serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_addresses_flat_array() {
+        let ns: Nameserver = serde_json::from_str(
+            r#"{
+                "objectClassName": "nameserver",
+                "ldhName": "ns1.example.com",
+                "ipAddresses": ["192.0.2.1", "2001:db8::1", "198.51.100.7"]
+            }"#,
+        )
+        .unwrap();
+        let ips = ns.ip_addresses.unwrap();
+        assert_eq!(ips.v4, ["192.0.2.1", "198.51.100.7"]);
+        assert_eq!(ips.v6, ["2001:db8::1"]);
+
+        // Re-serialized in the RFC shape
+        let json = serde_json::to_value(&ips).unwrap();
+        assert_eq!(json["v6"], serde_json::json!(["2001:db8::1"]));
+
+        let ips: IpAddressSet = serde_json::from_str(r#"{"v6": ["2001:db8::53"]}"#).unwrap();
+        assert!(ips.v4.is_empty());
+        assert_eq!(ips.v6, ["2001:db8::53"]);
+    }
+}
@@ -1,14 +1,23 @@
 //! Search result models
 
-use super::{Domain, Entity, Nameserver, Notice};
+use super::{Domain, Entity, Extension, Link, Nameserver, Notice};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 /// Domain search results
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DomainSearchResults {
-    #[serde(rename = "rdapConformance", default)]
+    #[serde(
+        rename = "rdapConformance",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
     pub conformance: Vec<String>,
 
+    /// Parsed `rdapConformance` (see [`models`](super))
+    #[serde(skip)]
+    pub extensions: BTreeSet<Extension>,
+
     #[serde(default)]
     pub notices: Vec<Notice>,
 
@@ -17,30 +26,74 @@ pub struct DomainSearchResults {
 
     #[serde(default)]
     pub lang: Option<String>,
+
+    /// Result counts and page cursors (RFC 8977)
+    #[serde(
+        default,
+        deserialize_with = "lenient_paging",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub paging_metadata: Option<PagingMetadata>,
+
+    /// Members not covered above (see [`models`](super))
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Entity search results
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EntitySearchResults {
-    #[serde(rename = "rdapConformance", default)]
+    #[serde(
+        rename = "rdapConformance",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
     pub conformance: Vec<String>,
 
+    /// Parsed `rdapConformance` (see [`models`](super))
+    #[serde(skip)]
+    pub extensions: BTreeSet<Extension>,
+
     #[serde(default)]
     pub notices: Vec<Notice>,
 
-    #[serde(rename = "entitySearchResults", default)]
+    #[serde(
+        rename = "entitySearchResults",
+        default,
+        deserialize_with = "super::common::lenient_vec"
+    )]
     pub entities: Vec<Entity>,
 
     #[serde(default)]
     pub lang: Option<String>,
+
+    /// Result counts and page cursors (RFC 8977)
+    #[serde(
+        default,
+        deserialize_with = "lenient_paging",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub paging_metadata: Option<PagingMetadata>,
+
+    /// Members not covered above (see [`models`](super))
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Nameserver search results
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NameserverSearchResults {
-    #[serde(rename = "rdapConformance", default)]
+    #[serde(
+        rename = "rdapConformance",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
     pub conformance: Vec<String>,
 
+    /// Parsed `rdapConformance` (see [`models`](super))
+    #[serde(skip)]
+    pub extensions: BTreeSet<Extension>,
+
     #[serde(default)]
     pub notices: Vec<Notice>,
 
@@ -49,4 +102,144 @@ pub struct NameserverSearchResults {
 
     #[serde(default)]
     pub lang: Option<String>,
+
+    /// Result counts and page cursors (RFC 8977)
+    #[serde(
+        default,
+        deserialize_with = "lenient_paging",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub paging_metadata: Option<PagingMetadata>,
+
+    /// Members not covered above (see [`models`](super))
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl DomainSearchResults {
+    /// Size of the whole result set, if the server reports it
+    pub fn total_count(&self) -> Option<u64> {
+        self.paging_metadata.as_ref()?.total_count
+    }
+
+    /// URL of the next page of results, if there is one
+    pub fn next_page_url(&self) -> Option<url::Url> {
+        self.paging_metadata.as_ref()?.next_page_url()
+    }
+}
+
+impl EntitySearchResults {
+    /// Size of the whole result set, if the server reports it
+    pub fn total_count(&self) -> Option<u64> {
+        self.paging_metadata.as_ref()?.total_count
+    }
+
+    /// URL of the next page of results, if there is one
+    pub fn next_page_url(&self) -> Option<url::Url> {
+        self.paging_metadata.as_ref()?.next_page_url()
+    }
+}
+
+impl NameserverSearchResults {
+    /// Size of the whole result set, if the server reports it
+    pub fn total_count(&self) -> Option<u64> {
+        self.paging_metadata.as_ref()?.total_count
+    }
+
+    /// URL of the next page of results, if there is one
+    pub fn next_page_url(&self) -> Option<url::Url> {
+        self.paging_metadata.as_ref()?.next_page_url()
+    }
+}
+
+/// `paging_metadata` of a paged search result (RFC 8977)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PagingMetadata {
+    /// Size of the whole result set
+    #[serde(
+        rename = "totalCount",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub total_count: Option<u64>,
+
+    #[serde(rename = "pageSize", default, skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u64>,
+
+    /// Number of this page, starting at 1
+    #[serde(
+        rename = "pageNumber",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub page_number: Option<u64>,
+
+    /// Page cursors, e.g. `rel: "next"`
+    #[serde(default)]
+    pub links: Vec<Link>,
+}
+
+impl PagingMetadata {
+    /// URL of the next page (the `next` link)
+    pub fn next_page_url(&self) -> Option<url::Url> {
+        self.links
+            .iter()
+            .find(|l| l.rel.as_deref() == Some("next"))
+            .and_then(Link::url)
+    }
+}
+
+/// Deserialize `paging_metadata`, dropping it with a warning when malformed
+fn lenient_paging<'de, D>(deserializer: D) -> Result<Option<PagingMetadata>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(value.and_then(|value| match serde_json::from_value(value) {
+        Ok(paging) => Some(paging),
+        Err(e) => {
+            log::warn!("Ignoring invalid paging_metadata: {e}");
+            None
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paging_metadata() {
+        let results: DomainSearchResults = serde_json::from_str(
+            r#"{
+                "rdapConformance": ["rdap_level_0", "paging"],
+                "domainSearchResults": [{"objectClassName": "domain", "ldhName": "a.example"}],
+                "paging_metadata": {
+                    "totalCount": 1200,
+                    "pageSize": 50,
+                    "pageNumber": 1,
+                    "links": [{
+                        "value": "https://rdap.example/domains?name=*.example",
+                        "rel": "next",
+                        "href": "/domains?name=*.example&cursor=wJlCDLIl6KTWypN7T6vc6nWEmEYe99Hjf1XY1xmqV-M=",
+                        "type": "application/rdap+json"
+                    }]
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(results.total_count(), Some(1200));
+        assert_eq!(
+            results.next_page_url().unwrap().as_str(),
+            "https://rdap.example/domains?name=*.example&cursor=wJlCDLIl6KTWypN7T6vc6nWEmEYe99Hjf1XY1xmqV-M="
+        );
+
+        // A malformed block doesn't fail the search
+        let results: EntitySearchResults = serde_json::from_str(
+            r#"{"entitySearchResults": [], "paging_metadata": {"totalCount": "many"}}"#,
+        )
+        .unwrap();
+        assert!(results.paging_metadata.is_none());
+        assert_eq!(results.total_count(), None);
+    }
 }
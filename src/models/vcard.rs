@@ -47,6 +47,37 @@ impl VCard {
         self.get_property_value("tel")
     }
 
+    /// Get all email addresses
+    pub fn emails(&self) -> Vec<&str> {
+        self.property_values("email").collect()
+    }
+
+    /// Get all telephone numbers with their `type` parameter
+    ///
+    /// For multi-valued types such as `["work", "fax"]`, the first type that
+    /// isn't the `work`/`home` context is returned, so fax and voice lines can
+    /// be told apart.
+    pub fn tels(&self) -> Vec<(&str, Option<&str>)> {
+        self.properties
+            .iter()
+            .filter(|p| p.name == "tel")
+            .filter_map(|p| {
+                let types = p.types();
+                let kind = types
+                    .iter()
+                    .find(|t| !matches!(**t, "work" | "home"))
+                    .or_else(|| types.first())
+                    .copied();
+                p.value.as_str().map(|value| (value, kind))
+            })
+            .collect()
+    }
+
+    /// Get the kind of object described (`individual`, `org`, `group`, ...)
+    pub fn kind(&self) -> Option<&str> {
+        self.get_property_value("kind")
+    }
+
     /// Get organization
     pub fn org(&self) -> Option<&str> {
         self.get_property_value("org")
@@ -81,10 +112,14 @@ impl VCard {
     }
 
     fn get_property_value(&self, name: &str) -> Option<&str> {
+        self.property_values(name).next()
+    }
+
+    fn property_values<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a str> {
         self.properties
             .iter()
-            .find(|p| p.name == name)
-            .and_then(|p| p.value.as_str())
+            .filter(move |p| p.name == name)
+            .filter_map(|p| p.value.as_str())
     }
 
     pub fn properties(&self) -> &[VCardProperty] {
@@ -102,6 +137,15 @@ pub struct VCardProperty {
 }
 
 impl VCardProperty {
+    /// Values of the `type` parameter, which may be a string or an array
+    pub fn types(&self) -> Vec<&str> {
+        match self.parameters.get("type") {
+            Some(Value::String(t)) => vec![t.as_str()],
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        }
+    }
+
     fn from_value(val: &Value) -> Option<Self> {
         let arr = val.as_array()?;
         if arr.len() < 4 {
@@ -142,7 +186,8 @@ impl VCardValue {
                 if items.len() == arr.len() {
                     Self::Structured(items)
                 } else {
-                    Self::Array(vec![])
+                    // Flatten one level of nesting; deeper values stay as compact JSON
+                    Self::Array(arr.iter().flat_map(flatten_one_level).collect())
                 }
             }
             _ => Self::Text(val.to_string()),
@@ -155,6 +200,34 @@ impl VCardValue {
             _ => None,
         }
     }
+
+    /// Value as a single line of text, with components separated by "; "
+    pub fn to_text(&self) -> String {
+        match self {
+            Self::Text(s) => s.clone(),
+            Self::Structured(parts) | Self::Array(parts) => parts
+                .iter()
+                .filter(|p| !p.is_empty())
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("; "),
+        }
+    }
+}
+
+/// Render an array element as strings, expanding one level of nested arrays
+fn flatten_one_level(val: &Value) -> Vec<String> {
+    match val {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map_or_else(|| v.to_string(), ToString::to_string)
+            })
+            .collect(),
+        _ => vec![val.to_string()],
+    }
 }
 
 /// vCard address
@@ -209,3 +282,39 @@ impl Serialize for VCard {
         seq.end()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multiple_emails_and_tels() {
+        let vcard = VCard::from_array(
+            &serde_json::from_str::<Vec<Value>>(
+                r#"["vcard", [
+                ["version", {}, "text", "4.0"],
+                ["kind", {}, "text", "org"],
+                ["email", {}, "text", "noc@example.net"],
+                ["email", {"type": "work"}, "text", "abuse@example.net"],
+                ["tel", {"type": ["work", "voice"]}, "uri", "tel:+1-555-0100"],
+                ["tel", {"type": "fax"}, "uri", "tel:+1-555-0199"],
+                ["tel", {}, "text", "+1 555 0123"]
+            ]]"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(vcard.kind(), Some("org"));
+        assert_eq!(vcard.emails(), ["noc@example.net", "abuse@example.net"]);
+        assert_eq!(vcard.email(), Some("noc@example.net"));
+        assert_eq!(
+            vcard.tels(),
+            [
+                ("tel:+1-555-0100", Some("voice")),
+                ("tel:+1-555-0199", Some("fax")),
+                ("+1 555 0123", None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Address and AS number containment checks
+//!
+//! These are the checks used to match queries against bootstrap registry
+//! entries, exposed for reuse.
+
+use crate::error::{RdapError, Result};
+use ipnet::IpNet;
+use std::net::IpAddr;
+
+/// Check whether `addr` lies inside `cidr` (e.g. `192.0.2.0/24` or `2001:db8::/32`)
+///
+/// A bare address without a prefix is treated as a single-host network.
+/// Addresses never match a network of the other family, and malformed
+/// networks match nothing.
+pub fn ip_in_cidr(addr: IpAddr, cidr: &str) -> bool {
+    let cidr = cidr.trim();
+    if let Ok(network) = cidr.parse::<IpNet>() {
+        return network.contains(&addr);
+    }
+    cidr.parse::<IpAddr>().is_ok_and(|host| host == addr)
+}
+
+/// Check whether `asn` lies inside a registry range (`64496-64511` or `64496`)
+///
+/// Malformed ranges match nothing.
+pub fn asn_in_range(asn: u32, range: &str) -> bool {
+    parse_asn_range(range).is_ok_and(|(start, end)| (start..=end).contains(&asn))
+}
+
+/// Parse a registry ASN entry ("1000-2000" or "1000") into an inclusive range
+pub fn parse_asn_range(range: &str) -> Result<(u32, u32)> {
+    let invalid = || RdapError::Bootstrap(format!("Ignoring malformed ASN range entry: {range}"));
+
+    let (start, end) = range.split_once('-').unwrap_or((range, range));
+    let start = start.trim().parse::<u32>().map_err(|_| invalid())?;
+    let end = end.trim().parse::<u32>().map_err(|_| invalid())?;
+
+    if start > end {
+        return Err(invalid());
+    }
+    Ok((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_ip_in_cidr_prefix_zero() {
+        assert!(ip_in_cidr(addr("203.0.113.7"), "0.0.0.0/0"));
+        assert!(ip_in_cidr(addr("2001:db8::1"), "::/0"));
+    }
+
+    #[test]
+    fn test_ip_in_cidr_host_prefixes() {
+        assert!(ip_in_cidr(addr("192.0.2.1"), "192.0.2.1/32"));
+        assert!(!ip_in_cidr(addr("192.0.2.2"), "192.0.2.1/32"));
+        assert!(ip_in_cidr(addr("2001:db8::1"), "2001:db8::1/128"));
+        assert!(!ip_in_cidr(addr("2001:db8::2"), "2001:db8::1/128"));
+        assert!(ip_in_cidr(addr("192.0.2.1"), "192.0.2.1"));
+    }
+
+    #[test]
+    fn test_ip_in_cidr_family_mismatch() {
+        assert!(!ip_in_cidr(addr("192.0.2.1"), "::/0"));
+        assert!(!ip_in_cidr(addr("2001:db8::1"), "0.0.0.0/0"));
+        assert!(!ip_in_cidr(addr("::ffff:192.0.2.1"), "192.0.2.0/24"));
+    }
+
+    #[test]
+    fn test_ip_in_cidr_malformed() {
+        assert!(!ip_in_cidr(addr("192.0.2.1"), "192.0.2.0/33"));
+        assert!(!ip_in_cidr(addr("192.0.2.1"), "not a network"));
+        assert!(!ip_in_cidr(addr("192.0.2.1"), ""));
+    }
+
+    #[test]
+    fn test_asn_in_range() {
+        assert!(asn_in_range(64500, "64496-64511"));
+        assert!(asn_in_range(64496, "64496-64511"));
+        assert!(asn_in_range(64511, "64496-64511"));
+        assert!(!asn_in_range(64512, "64496-64511"));
+        assert!(asn_in_range(0, "0"));
+        assert!(asn_in_range(u32::MAX, "4294967295"));
+        assert!(!asn_in_range(5, "10-1"));
+    }
+
+    #[test]
+    fn test_parse_asn_range() {
+        assert_eq!(parse_asn_range("1000-2000").unwrap(), (1000, 2000));
+        assert_eq!(parse_asn_range("1000").unwrap(), (1000, 1000));
+        assert!(parse_asn_range("4294967296").is_err());
+        assert!(parse_asn_range("1-4294967296").is_err());
+        assert!(parse_asn_range("2000-1000").is_err());
+        assert!(parse_asn_range("abc").is_err());
+    }
+}
@@ -0,0 +1,171 @@
+//! Combined human-readable and raw JSON reports, e.g. for attaching to a ticket
+
+use crate::RdapObject;
+use crate::display::{DisplayOptions, RdapDisplayWithQuery};
+use std::io::{self, Write};
+use std::path::Path;
+use url::Url;
+
+/// Layout of a [`Report`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    /// Text display in a `text` fence followed by the body in a `json` fence
+    #[default]
+    Markdown,
+    /// Standalone HTML page with both sections in `<pre>` blocks
+    Html,
+}
+
+impl ReportFormat {
+    /// Pick the format from a file extension: `.html`/`.htm` for HTML,
+    /// Markdown for anything else
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm") => {
+                Self::Html
+            }
+            _ => Self::Markdown,
+        }
+    }
+}
+
+/// A queried object together with the response body it was parsed from
+///
+/// Built by [`crate::RdapClient::query_report`]. The body is kept byte for
+/// byte, so the JSON section is the server's own answer rather than a
+/// re-serialization of the parsed model.
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// Query as the user typed it
+    pub query: String,
+    /// URL that answered
+    pub url: Url,
+    /// Parsed response
+    pub object: RdapObject,
+    /// Unparsed response body
+    pub body: String,
+}
+
+impl Report {
+    /// Write the text display, shown with `opts`, followed by the raw JSON body
+    ///
+    /// The display is colored according to `colored`'s global settings, so
+    /// callers writing to a file should turn colors off first.
+    pub fn write(
+        &self,
+        w: &mut dyn Write,
+        format: ReportFormat,
+        opts: &DisplayOptions,
+    ) -> io::Result<()> {
+        let mut display = Vec::new();
+        self.object
+            .write_for_query(&mut display, &self.query, opts)?;
+        let display = String::from_utf8_lossy(&display);
+        let display = display.trim_matches('\n');
+        let body = self.body.trim_end();
+
+        match format {
+            ReportFormat::Markdown => {
+                writeln!(w, "# RDAP report: {}", self.query)?;
+                writeln!(w)?;
+                writeln!(w, "Query from <{}>", self.url)?;
+                writeln!(w)?;
+                writeln!(w, "```text\n{display}\n```")?;
+                writeln!(w)?;
+                writeln!(w, "```json\n{body}\n```")?;
+            }
+            ReportFormat::Html => {
+                let query = escape_html(&self.query);
+                let url = escape_html(self.url.as_str());
+                writeln!(w, "<!DOCTYPE html>")?;
+                writeln!(w, "<html>\n<head>\n<meta charset=\"utf-8\">")?;
+                writeln!(w, "<title>RDAP report: {query}</title>\n</head>\n<body>")?;
+                writeln!(w, "<h1>RDAP report: {query}</h1>")?;
+                writeln!(w, "<p>Query from <a href=\"{url}\">{url}</a></p>")?;
+                writeln!(w, "<pre>{}</pre>", escape_html(display))?;
+                writeln!(
+                    w,
+                    "<pre><code class=\"language-json\">{}</code></pre>",
+                    escape_html(body)
+                )?;
+                writeln!(w, "</body>\n</html>")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::strip_ansi;
+
+    fn report() -> Report {
+        let body = r#"{"objectClassName": "domain", "ldhName": "example.com", "fred_x": "<1>"}"#;
+        Report {
+            query: "example.com".to_string(),
+            url: Url::parse("https://rdap.example/domain/example.com").unwrap(),
+            object: crate::parse_rdap(body).unwrap(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_markdown_report_has_both_sections() {
+        let mut out = Vec::new();
+        report()
+            .write(&mut out, ReportFormat::Markdown, &DisplayOptions::default())
+            .unwrap();
+        let out = strip_ansi(&String::from_utf8(out).unwrap());
+
+        let text = out.find("```text\n").unwrap();
+        let json = out.find("```json\n").unwrap();
+        assert!(text < json);
+        assert!(out[text..json].contains("example.com"));
+        assert!(out[json..].contains(r#""fred_x": "<1>""#));
+        assert!(out.trim_end().ends_with("```"));
+    }
+
+    #[test]
+    fn test_html_report_escapes_body() {
+        let mut out = Vec::new();
+        report()
+            .write(&mut out, ReportFormat::Html, &DisplayOptions::default())
+            .unwrap();
+        let out = strip_ansi(&String::from_utf8(out).unwrap());
+
+        assert!(out.contains("<h1>RDAP report: example.com</h1>"));
+        assert!(out.contains("&quot;fred_x&quot;: &quot;&lt;1&gt;&quot;"));
+        assert!(out.contains("</pre>"));
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            ReportFormat::from_path(Path::new("report.HTML")),
+            ReportFormat::Html
+        );
+        assert_eq!(
+            ReportFormat::from_path(Path::new("report.md")),
+            ReportFormat::Markdown
+        );
+        assert_eq!(
+            ReportFormat::from_path(Path::new("report")),
+            ReportFormat::Markdown
+        );
+    }
+}
@@ -1,7 +1,8 @@
 //! RDAP request types and builders
 
-use crate::error::Result;
+use crate::error::{RdapError, Result};
 use crate::ip;
+use std::borrow::Cow;
 use std::fmt;
 use url::Url;
 
@@ -36,6 +37,18 @@ pub enum QueryType {
     EntitySearch,
     /// Entity search by handle
     EntitySearchByHandle,
+    /// Reverse search: domains related to an entity handle (RFC 9536)
+    DomainsReverseByEntity,
+    /// Reverse search: domains related to an entity full name (`fn`)
+    DomainsReverseByEntityName,
+    /// Reverse search: domains related to an entity email address
+    DomainsReverseByEntityEmail,
+    /// Reverse search: nameservers related to an entity handle
+    NameserversReverseByEntity,
+    /// Reverse search: entities related to an entity handle
+    EntitiesReverseByEntity,
+    /// Full RDAP URL, fetched as-is without bootstrap
+    Url,
 }
 
 impl fmt::Display for QueryType {
@@ -55,6 +68,12 @@ impl fmt::Display for QueryType {
             Self::NameserverSearchByIp => "nameserver-search-by-ip",
             Self::EntitySearch => "entity-search",
             Self::EntitySearchByHandle => "entity-search-by-handle",
+            Self::DomainsReverseByEntity => "domains-reverse-by-entity",
+            Self::DomainsReverseByEntityName => "domains-reverse-by-entity-name",
+            Self::DomainsReverseByEntityEmail => "domains-reverse-by-entity-email",
+            Self::NameserversReverseByEntity => "nameservers-reverse-by-entity",
+            Self::EntitiesReverseByEntity => "entities-reverse-by-entity",
+            Self::Url => "url",
         };
         write!(f, "{s}")
     }
@@ -66,6 +85,10 @@ pub struct RdapRequest {
     pub query_type: QueryType,
     pub query: String,
     pub server: Option<Url>,
+    /// Extra query parameters appended to search URLs
+    pub query_params: Vec<(String, String)>,
+    /// Server-specific path used instead of the standard one, see [`Self::with_raw_path`]
+    pub raw_path: Option<String>,
 }
 
 impl RdapRequest {
@@ -75,6 +98,8 @@ impl RdapRequest {
             query_type,
             query: query.into(),
             server: None,
+            query_params: Vec::new(),
+            raw_path: None,
         }
     }
 
@@ -84,52 +109,150 @@ impl RdapRequest {
         self
     }
 
+    /// Append a raw query parameter to search requests (repeatable)
+    ///
+    /// Parameters are added in order after the standard search parameter,
+    /// for server-specific filters such as `status=active`. Lookups ignore them,
+    /// unless they have a [raw path](Self::with_raw_path).
+    pub fn with_query_param(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.query_params.push((key.into(), value.into()));
+        self
+    }
+
+    /// Request a server-specific path instead of the standard one for this query
+    ///
+    /// An escape hatch for extensions the crate doesn't model, such as ARIN's
+    /// `ip/{address}/history`. The server is still found by bootstrap from the
+    /// query and its type; `path` (which may carry a query string) is then
+    /// joined onto that server's base URL. A leading `/` is ignored, so the
+    /// path stays under the base URL; paths with a scheme, a host (`//host`,
+    /// `\\host`) or a `..` segment are rejected by [`Self::build_url`]. Extra
+    /// query parameters are appended.
+    pub fn with_raw_path(mut self, path: impl Into<String>) -> Self {
+        self.raw_path = Some(path.into());
+        self
+    }
+
+    /// Return a copy of this request with domain-style queries normalized
+    ///
+    /// See [`normalize_domain`] for the rules applied.
+    pub fn normalized(&self) -> Result<Self> {
+        let mut request = self.clone();
+        if matches!(
+            self.query_type,
+            QueryType::Domain | QueryType::Tld | QueryType::Nameserver
+        ) {
+            request.query = normalize_domain(&self.query)?;
+        }
+        Ok(request)
+    }
+
     /// Build the full RDAP URL
+    ///
+    /// [`QueryType::Url`] queries are returned unchanged and ignore `base_url`.
     pub fn build_url(&self, base_url: &Url) -> Result<Url> {
+        if let Some(raw_path) = &self.raw_path
+            && self.query_type != QueryType::Url
+        {
+            let url = self.search_url(base_url, checked_raw_path(raw_path)?)?;
+            // Belt and braces: whatever URL parsing does with the path, the
+            // result must be on the same server, under its base path (up to
+            // the last `/`, which is what joining keeps)
+            let base_path = base_url.path();
+            let base_dir = &base_path[..=base_path.rfind('/').unwrap_or_default()];
+            if url.scheme() != base_url.scheme()
+                || url.host() != base_url.host()
+                || url.port_or_known_default() != base_url.port_or_known_default()
+                || !url.path().starts_with(base_dir)
+            {
+                return Err(raw_path_error(raw_path));
+            }
+            return Ok(url);
+        }
         let encoded_query = urlencoding::encode(&self.query);
         let path = match self.query_type {
             QueryType::Domain | QueryType::Tld => {
-                format!("domain/{encoded_query}")
+                format!(
+                    "domain/{}",
+                    urlencoding::encode(&to_ascii_domain(&self.query)?)
+                )
             }
             QueryType::Ip => format!("ip/{}", self.query),
-            QueryType::Autnum => {
-                // Case-insensitive strip of "AS" prefix
-                let asn = if self.query.to_uppercase().starts_with("AS") {
-                    &self.query[2..]
-                } else {
-                    &self.query
-                };
-                format!("autnum/{asn}")
-            }
+            QueryType::Autnum => format!("autnum/{}", parse_asn(&self.query)?),
             QueryType::Entity => format!("entity/{encoded_query}"),
-            QueryType::Nameserver => format!("nameserver/{encoded_query}"),
+            QueryType::Nameserver => {
+                format!(
+                    "nameserver/{}",
+                    urlencoding::encode(&to_ascii_domain(&self.query)?)
+                )
+            }
             QueryType::Help => "help".to_owned(),
             QueryType::DomainSearch => {
-                return Ok(base_url.join(&format!("domains?name={encoded_query}"))?);
+                return self.search_url(base_url, &format!("domains?name={encoded_query}"));
             }
             QueryType::DomainSearchByNameserver => {
-                return Ok(base_url.join(&format!("domains?nsLdhName={encoded_query}"))?);
+                return self.search_url(base_url, &format!("domains?nsLdhName={encoded_query}"));
             }
             QueryType::DomainSearchByNameserverIp => {
-                return Ok(base_url.join(&format!("domains?nsIp={}", self.query))?);
+                return self.search_url(base_url, &format!("domains?nsIp={}", self.query));
             }
             QueryType::NameserverSearch => {
-                return Ok(base_url.join(&format!("nameservers?name={encoded_query}"))?);
+                return self.search_url(base_url, &format!("nameservers?name={encoded_query}"));
             }
             QueryType::NameserverSearchByIp => {
-                return Ok(base_url.join(&format!("nameservers?ip={}", self.query))?);
+                return self.search_url(base_url, &format!("nameservers?ip={}", self.query));
             }
             QueryType::EntitySearch => {
-                return Ok(base_url.join(&format!("entities?fn={encoded_query}"))?);
+                return self.search_url(base_url, &format!("entities?fn={encoded_query}"));
             }
             QueryType::EntitySearchByHandle => {
-                return Ok(base_url.join(&format!("entities?handle={encoded_query}"))?);
+                return self.search_url(base_url, &format!("entities?handle={encoded_query}"));
+            }
+            QueryType::DomainsReverseByEntity => {
+                return self.search_url(
+                    base_url,
+                    &format!("domains/reverse_search/entity?handle={encoded_query}"),
+                );
+            }
+            QueryType::DomainsReverseByEntityName => {
+                return self.search_url(
+                    base_url,
+                    &format!("domains/reverse_search/entity?fn={encoded_query}"),
+                );
+            }
+            QueryType::DomainsReverseByEntityEmail => {
+                return self.search_url(
+                    base_url,
+                    &format!("domains/reverse_search/entity?email={encoded_query}"),
+                );
+            }
+            QueryType::NameserversReverseByEntity => {
+                return self.search_url(
+                    base_url,
+                    &format!("nameservers/reverse_search/entity?handle={encoded_query}"),
+                );
+            }
+            QueryType::EntitiesReverseByEntity => {
+                return self.search_url(
+                    base_url,
+                    &format!("entities/reverse_search/entity?handle={encoded_query}"),
+                );
             }
+            QueryType::Url => return Ok(Url::parse(&self.query)?),
         };
 
         Ok(base_url.join(&path)?)
     }
 
+    /// Join a search (or raw) path onto `base_url` and append the extra query parameters
+    fn search_url(&self, base_url: &Url, search: &str) -> Result<Url> {
+        let mut url = base_url.join(search)?;
+        if !self.query_params.is_empty() {
+            url.query_pairs_mut().extend_pairs(&self.query_params);
+        }
+        Ok(url)
+    }
+
     /// Detect query type from string
     pub fn detect_type(query: &str) -> Result<QueryType> {
         Self::detect_type_with_tld_check(query, |_| false)
@@ -140,24 +263,19 @@ impl RdapRequest {
     where
         F: Fn(&str) -> bool,
     {
-        // Check for AS number
-        if query.to_uppercase().starts_with("AS") && query[2..].chars().all(|c| c.is_ascii_digit())
-        {
-            return Ok(QueryType::Autnum);
+        // Check for a full RDAP URL; other pasted URLs are most likely
+        // meant as a domain lookup
+        if is_http_url(query) {
+            if is_rdap_url(query) {
+                return Ok(QueryType::Url);
+            }
+            return Err(pasted_url_error(query));
         }
 
-        // Check for pure number (AS number without AS prefix)
-        // But not if it looks like an IP (e.g., large numbers that could be IPs)
-        if query.chars().all(|c| c.is_ascii_digit()) {
-            // Numbers > 4_294_967_295 can't be AS numbers or IPs
-            if let Ok(n) = query.parse::<u64>()
-                && n <= 4_294_967_295
-                && n <= 4_294_967_294
-            {
-                // Could be AS number or IP in numeric form
-                // Treat as AS number if it's a reasonable AS number range
-                return Ok(QueryType::Autnum);
-            }
+        // Check for AS number, with or without the AS prefix. Digits beyond
+        // the 32-bit range are rejected here rather than by the server.
+        if asn_digits(query).is_some() {
+            parse_asn(query)?;
             return Ok(QueryType::Autnum);
         }
 
@@ -166,6 +284,11 @@ impl RdapRequest {
             return Ok(QueryType::Ip);
         }
 
+        // A scheme or path can't be part of a domain name
+        if query.contains("://") || query.contains('/') {
+            return Err(pasted_url_error(query));
+        }
+
         // Check if it's a single word that's a valid TLD (no dots)
         if !query.contains('.') && is_tld(query) {
             return Ok(QueryType::Tld);
@@ -176,6 +299,227 @@ impl RdapRequest {
     }
 }
 
+/// Digits of an AS number query (`AS64496`, `as64496` or `64496`)
+fn asn_digits(query: &str) -> Option<&str> {
+    let digits = match query.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("AS") => &query[2..],
+        _ => query,
+    };
+    (!digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())).then_some(digits)
+}
+
+/// Strip the leading `/` of a raw path, rejecting paths that would leave the
+/// server's base URL: absolute URLs, `//host` or `\\host` prefixes and `..`
+/// segments (also percent-encoded)
+fn checked_raw_path(raw_path: &str) -> Result<&str> {
+    let path = raw_path.strip_prefix('/').unwrap_or(raw_path);
+    let escapes = path.starts_with(['/', '\\'])
+        || Url::parse(path).is_ok()
+        || path
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .split(['/', '\\'])
+            .any(|segment| urlencoding::decode(segment).is_ok_and(|s| s == ".."));
+    if escapes {
+        return Err(raw_path_error(raw_path));
+    }
+    Ok(path)
+}
+
+fn raw_path_error(raw_path: &str) -> RdapError {
+    RdapError::InvalidQuery(format!(
+        "Raw path must stay under the server's base URL: {raw_path}"
+    ))
+}
+
+/// Parse an AS number query into its 32-bit value (RFC 6793)
+pub fn parse_asn(query: &str) -> Result<u32> {
+    let digits = asn_digits(query)
+        .ok_or_else(|| RdapError::InvalidQuery(format!("Invalid AS number: {query}")))?;
+    digits.parse().map_err(|_| {
+        RdapError::InvalidQuery(format!(
+            "AS number out of range: {query} (the maximum is 4294967295)"
+        ))
+    })
+}
+
+/// Entity search combining `fn`, `handle` and `role` criteria
+///
+/// RFC 9082 entity searches need a full name or a handle; further criteria
+/// are sent as extra parameters, which RIRs such as RIPE use to filter at
+/// the server (e.g. `entities?handle=ORG-*&role=abuse`).
+#[derive(Debug, Clone, Default)]
+pub struct EntitySearchQuery {
+    name: Option<String>,
+    handle: Option<String>,
+    role: Option<String>,
+}
+
+impl EntitySearchQuery {
+    /// Create an empty search
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match the entity's full name (`fn`), wildcards allowed
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Match the entity handle, wildcards allowed
+    pub fn with_handle(mut self, handle: impl Into<String>) -> Self {
+        self.handle = Some(handle.into());
+        self
+    }
+
+    /// Only return entities with this role, e.g. `abuse`
+    pub fn with_role(mut self, role: impl Into<String>) -> Self {
+        self.role = Some(role.into());
+        self
+    }
+
+    /// Build the search request
+    ///
+    /// The name, when given, is the primary criterion; otherwise the handle is.
+    pub fn into_request(self) -> Result<RdapRequest> {
+        let mut request = match (self.name, self.handle) {
+            (Some(name), handle) => {
+                let request = RdapRequest::new(QueryType::EntitySearch, name);
+                match handle {
+                    Some(handle) => request.with_query_param("handle", handle),
+                    None => request,
+                }
+            }
+            (None, Some(handle)) => RdapRequest::new(QueryType::EntitySearchByHandle, handle),
+            (None, None) => {
+                return Err(RdapError::InvalidQuery(
+                    "Entity search needs a name or a handle".to_string(),
+                ));
+            }
+        };
+        if let Some(role) = self.role {
+            request = request.with_query_param("role", role);
+        }
+        Ok(request)
+    }
+}
+
+/// Check whether a query is an `http://` or `https://` URL
+fn is_http_url(query: &str) -> bool {
+    let lower = query.get(..8).unwrap_or(query).to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Check whether a URL looks like an RDAP query rather than a web page
+///
+/// The path must end in an RDAP lookup (`.../domain/example.com`,
+/// `.../ip/192.0.2.0/24`), a search with a query string
+/// (`.../domains?name=ex*`) or `.../help`. URLs on an `rdap.` host (e.g. a
+/// bare base URL like `https://rdap.example/v1/`) are accepted as they are.
+fn is_rdap_url(query: &str) -> bool {
+    const LOOKUPS: [&str; 5] = ["domain", "ip", "autnum", "entity", "nameserver"];
+    const SEARCHES: [&str; 3] = ["domains", "nameservers", "entities"];
+    let Ok(url) = Url::parse(query) else {
+        return false;
+    };
+    if url
+        .host_str()
+        .is_some_and(|host| host.to_ascii_lowercase().starts_with("rdap."))
+    {
+        return true;
+    }
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    match segments.as_slice() {
+        [.., "ip", _, _] | [.., "help"] => true,
+        [.., object_type, _] if LOOKUPS.contains(object_type) => true,
+        [.., search] => SEARCHES.contains(search) && url.query().is_some(),
+        [] => false,
+    }
+}
+
+/// Error for a URL or path given where a domain was expected, naming the
+/// hostname to query instead when one can be extracted
+fn pasted_url_error(query: &str) -> RdapError {
+    let host = Url::parse(query)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .or_else(|| {
+            let host = query.split(['/', '?', '#']).next().unwrap_or_default();
+            host.contains('.').then(|| host.to_string())
+        });
+    RdapError::InvalidQuery(host.map_or_else(
+        || format!("{query} looks like a URL or path, not a domain; pass just the hostname"),
+        |host| format!("{query} looks like a URL, not a domain; pass just the hostname: {host}"),
+    ))
+}
+
+/// Convert a Unicode domain name to its ASCII-compatible (A-label) form
+///
+/// ASCII input is returned unchanged, so lookups the server might accept
+/// despite not being strict IDNA still go through as typed.
+pub fn to_ascii_domain(input: &str) -> Result<Cow<'_, str>> {
+    if input.is_ascii() {
+        return Ok(Cow::Borrowed(input));
+    }
+    idna::domain_to_ascii(input).map(Cow::Owned).map_err(|_| {
+        RdapError::InvalidQuery(format!("Invalid internationalized domain name: {input}"))
+    })
+}
+
+/// Registrable domain (eTLD+1) of a name, per the public suffix list
+///
+/// `www.example.co.uk` gives `example.co.uk`, where splitting on dots can't
+/// tell `co.uk` from a registered name. Only ICANN suffixes count, and the
+/// result is lowercased, in A-label form. `None` for a public suffix itself
+/// (`co.uk`) or a name that isn't valid IDNA.
+pub fn registrable_domain(name: &str) -> Option<String> {
+    use publicsuffix::Psl;
+
+    let ascii = to_ascii_domain(name.trim().trim_end_matches('.'))
+        .ok()?
+        .to_lowercase();
+    let domain = crate::config::public_suffixes().domain(ascii.as_bytes())?;
+    let len = domain.as_bytes().len();
+    Some(ascii[ascii.len() - len..].to_string())
+}
+
+/// Normalize a domain name query
+///
+/// Surrounding whitespace and trailing dots are removed, percent-encoded
+/// characters (e.g. `example%2Ecom`) are decoded, and ASCII letters are
+/// lowercased, so `EXAMPLE.COM.` and `example.com` share a URL and cache
+/// entry. Non-ASCII labels are left for IDNA mapping in
+/// [`RdapRequest::build_url`]. Inputs that still contain URL syntax after
+/// decoding are rejected.
+pub fn normalize_domain(input: &str) -> Result<String> {
+    let trimmed = input.trim();
+    let decoded = if trimmed.contains('%') {
+        urlencoding::decode(trimmed)
+            .map_err(|_| RdapError::InvalidQuery(format!("Invalid percent-encoding: {input}")))?
+            .into_owned()
+    } else {
+        trimmed.to_string()
+    };
+
+    let name = decoded.trim_end_matches('.');
+    if name.is_empty()
+        || name
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '/' | '?' | '#' | '%'))
+    {
+        return Err(RdapError::InvalidQuery(format!(
+            "Invalid domain name: {input}"
+        )));
+    }
+
+    Ok(name.to_ascii_lowercase())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,6 +548,247 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_detect_asn_boundaries() {
+        for query in ["AS65535", "AS65536", "as4200000000", "4294967295"] {
+            assert_eq!(
+                RdapRequest::detect_type(query).unwrap(),
+                QueryType::Autnum,
+                "{query}"
+            );
+        }
+        for query in ["AS4294967296", "4294967296", "99999999999999999999"] {
+            assert!(
+                matches!(
+                    RdapRequest::detect_type(query),
+                    Err(RdapError::InvalidQuery(_))
+                ),
+                "{query}"
+            );
+        }
+        // A bare "as" is the American Samoa TLD, not an empty AS number
+        assert_eq!(RdapRequest::detect_type("as").unwrap(), QueryType::Domain);
+    }
+
+    #[test]
+    fn test_build_url_autnum() {
+        let base = Url::parse("https://rdap.arin.net/registry/").unwrap();
+        let url = RdapRequest::new(QueryType::Autnum, "as4200000000")
+            .build_url(&base)
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://rdap.arin.net/registry/autnum/4200000000"
+        );
+
+        // Explicit -t autnum skips detection, so the range is checked here too
+        let err = RdapRequest::new(QueryType::Autnum, "4294967296")
+            .build_url(&base)
+            .unwrap_err();
+        assert!(matches!(err, RdapError::InvalidQuery(_)));
+    }
+
+    #[test]
+    fn test_entity_search_query() {
+        let base = Url::parse("https://rdap.db.ripe.net/").unwrap();
+        let url = |search: EntitySearchQuery| {
+            search
+                .into_request()
+                .unwrap()
+                .build_url(&base)
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(
+            url(EntitySearchQuery::new()
+                .with_handle("ORG-*")
+                .with_role("abuse")),
+            "https://rdap.db.ripe.net/entities?handle=ORG-%2A&role=abuse"
+        );
+        assert_eq!(
+            url(EntitySearchQuery::new()
+                .with_role("abuse")
+                .with_handle("AR*")
+                .with_name("Example Abuse")),
+            "https://rdap.db.ripe.net/entities?fn=Example%20Abuse&handle=AR*&role=abuse"
+        );
+        assert_eq!(
+            url(EntitySearchQuery::new().with_name("Example*")),
+            "https://rdap.db.ripe.net/entities?fn=Example%2A"
+        );
+
+        let err = EntitySearchQuery::new()
+            .with_role("abuse")
+            .into_request()
+            .unwrap_err();
+        assert!(matches!(err, RdapError::InvalidQuery(_)));
+    }
+
+    #[test]
+    fn test_url_query() {
+        let link = "https://rdap.verisign.com/com/v1/domain/example.com";
+        assert_eq!(RdapRequest::detect_type(link).unwrap(), QueryType::Url);
+        assert_eq!(
+            RdapRequest::detect_type("HTTP://rdap.example/ip/192.0.2.1").unwrap(),
+            QueryType::Url
+        );
+        for url in [
+            "https://rdap.example/v1/",
+            "https://registry.example/rdap/ip/192.0.2.0/24",
+            "https://registry.example/rdap/domains?name=ex*",
+            "https://registry.example/rdap/help",
+        ] {
+            assert_eq!(
+                RdapRequest::detect_type(url).unwrap(),
+                QueryType::Url,
+                "{url}"
+            );
+        }
+
+        // The base URL is ignored
+        let base = Url::parse("https://rdap.arin.net/registry/").unwrap();
+        let req = RdapRequest::new(QueryType::Url, link);
+        assert_eq!(req.build_url(&base).unwrap().as_str(), link);
+
+        let req = RdapRequest::new(QueryType::Url, "not a url");
+        assert!(req.build_url(&base).is_err());
+    }
+
+    #[test]
+    fn test_pasted_url_rejected() {
+        let err = RdapRequest::detect_type("https://example.com/path").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("pass just the hostname: example.com")
+        );
+
+        let err = RdapRequest::detect_type("example.com/whois?q=1").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("pass just the hostname: example.com")
+        );
+
+        // RDAP words in a path don't make a web page an RDAP URL
+        for url in [
+            "https://example.com/help/contact",
+            "https://example.com/ip/",
+            "https://example.com/entities",
+        ] {
+            assert!(RdapRequest::detect_type(url).is_err(), "{url}");
+        }
+
+        let err = RdapRequest::detect_type("whois/example").unwrap_err();
+        assert!(matches!(err, RdapError::InvalidQuery(_)));
+
+        // CIDR notation is still an IP query
+        assert_eq!(
+            RdapRequest::detect_type("192.0.2.0/24").unwrap(),
+            QueryType::Ip
+        );
+    }
+
+    #[test]
+    fn test_search_extra_query_params() {
+        let base = Url::parse("https://rdap.example/").unwrap();
+        let req = RdapRequest::new(QueryType::DomainSearch, "exam*.com")
+            .with_query_param("status", "active")
+            .with_query_param("registrationDate", "2024-01-01")
+            .with_query_param("status", "client hold");
+        assert_eq!(
+            req.build_url(&base).unwrap().as_str(),
+            "https://rdap.example/domains?name=exam%2A.com&status=active&registrationDate=2024-01-01&status=client+hold"
+        );
+
+        // Lookups are unaffected
+        let req = RdapRequest::new(QueryType::Domain, "example.com").with_query_param("x", "1");
+        assert_eq!(
+            req.build_url(&base).unwrap().as_str(),
+            "https://rdap.example/domain/example.com"
+        );
+    }
+
+    #[test]
+    fn test_raw_path() {
+        let base = Url::parse("https://rdap.arin.net/registry/").unwrap();
+        let req = RdapRequest::new(QueryType::Ip, "192.0.2.1")
+            .with_raw_path("/ip/192.0.2.1/history?status=removed");
+        assert_eq!(
+            req.build_url(&base).unwrap().as_str(),
+            "https://rdap.arin.net/registry/ip/192.0.2.1/history?status=removed"
+        );
+
+        let req = RdapRequest::new(QueryType::Domain, "example.com")
+            .with_raw_path("domain/example.com/history")
+            .with_query_param("limit", "5");
+        assert_eq!(
+            req.build_url(&base).unwrap().as_str(),
+            "https://rdap.arin.net/registry/domain/example.com/history?limit=5"
+        );
+
+        for path in [
+            "../other/ip/192.0.2.1",
+            "ip/%2E%2E/%2e%2e/x",
+            "ip\\..\\..\\x",
+            "https://other.example/ip/192.0.2.1",
+            "//other.example/ip/192.0.2.1",
+            "\\\\evil.example/x",
+            "/\\evil.example/x",
+        ] {
+            let req = RdapRequest::new(QueryType::Ip, "192.0.2.1").with_raw_path(path);
+            assert!(
+                matches!(req.build_url(&base), Err(RdapError::InvalidQuery(_))),
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_reverse_search_urls() {
+        let base = Url::parse("https://rdap.example/rdap/").unwrap();
+        let cases = [
+            (
+                QueryType::DomainsReverseByEntity,
+                "XTOM-1",
+                "https://rdap.example/rdap/domains/reverse_search/entity?handle=XTOM-1",
+            ),
+            (
+                QueryType::DomainsReverseByEntityName,
+                "Bobby Joe*",
+                "https://rdap.example/rdap/domains/reverse_search/entity?fn=Bobby%20Joe%2A",
+            ),
+            (
+                QueryType::DomainsReverseByEntityEmail,
+                "abuse@example.net",
+                "https://rdap.example/rdap/domains/reverse_search/entity?email=abuse%40example.net",
+            ),
+            (
+                QueryType::NameserversReverseByEntity,
+                "XTOM-1",
+                "https://rdap.example/rdap/nameservers/reverse_search/entity?handle=XTOM-1",
+            ),
+            (
+                QueryType::EntitiesReverseByEntity,
+                "XTOM-1",
+                "https://rdap.example/rdap/entities/reverse_search/entity?handle=XTOM-1",
+            ),
+        ];
+        for (query_type, query, expected) in cases {
+            let req = RdapRequest::new(query_type, query);
+            assert_eq!(req.build_url(&base).unwrap().as_str(), expected);
+        }
+
+        // Roles narrow the search through an extra parameter
+        let req = RdapRequest::new(QueryType::DomainsReverseByEntityEmail, "abuse@example.net")
+            .with_query_param("role", "registrant");
+        assert!(
+            req.build_url(&base)
+                .unwrap()
+                .as_str()
+                .ends_with("?email=abuse%40example.net&role=registrant")
+        );
+    }
+
     #[test]
     fn test_build_url_ipv6() {
         let base = Url::parse("https://rdap.apnic.net/").unwrap();
@@ -219,4 +804,130 @@ mod tests {
         let url = req.build_url(&base).unwrap();
         assert_eq!(url.as_str(), "https://rdap.arin.net/registry/ip/8.8.8.8");
     }
+
+    #[test]
+    fn test_build_url_unicode_domain() {
+        let base = Url::parse("https://rdap.denic.de/").unwrap();
+        let req = RdapRequest::new(QueryType::Domain, "münchen.de");
+        let url = req.build_url(&base).unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://rdap.denic.de/domain/xn--mnchen-3ya.de"
+        );
+        // The request keeps the form the user typed
+        assert_eq!(req.query, "münchen.de");
+
+        let req = RdapRequest::new(QueryType::Nameserver, "ns1.bücher.example");
+        let url = req.build_url(&base).unwrap();
+        assert!(
+            url.as_str()
+                .ends_with("/nameserver/ns1.xn--bcher-kva.example")
+        );
+    }
+
+    #[test]
+    fn test_build_url_invalid_idn() {
+        let base = Url::parse("https://rdap.denic.de/").unwrap();
+        let req = RdapRequest::new(QueryType::Domain, "\u{301}münchen.de");
+        let err = req.build_url(&base).unwrap_err();
+        assert!(matches!(err, RdapError::InvalidQuery(_)));
+    }
+
+    #[test]
+    fn test_to_ascii_domain_passes_ascii_through() {
+        assert!(matches!(
+            to_ascii_domain("Example.COM"),
+            Ok(Cow::Borrowed("Example.COM"))
+        ));
+        assert_eq!(to_ascii_domain("ÉCOLE.fr").unwrap(), "xn--cole-9oa.fr");
+    }
+
+    #[test]
+    fn test_normalize_domain_a_label_case() {
+        assert_eq!(
+            normalize_domain("XN--MLLER-KVA.DE").unwrap(),
+            "xn--mller-kva.de"
+        );
+        assert_eq!(
+            normalize_domain("www.Xn--Mller-Kva.de").unwrap(),
+            "www.xn--mller-kva.de"
+        );
+        assert_eq!(normalize_domain("example.com").unwrap(), "example.com");
+    }
+
+    #[test]
+    fn test_normalize_domain_case_and_trailing_dot() {
+        assert_eq!(normalize_domain("EXAMPLE.COM.").unwrap(), "example.com");
+        assert_eq!(normalize_domain(" Example.Com ").unwrap(), "example.com");
+        assert_eq!(
+            normalize_domain("NS1.Example.com..").unwrap(),
+            "ns1.example.com"
+        );
+        // Only ASCII is lowercased here; IDNA mapping happens in build_url
+        assert_eq!(normalize_domain("MÜNCHEN.DE").unwrap(), "mÜnchen.de");
+        assert!(normalize_domain(".").is_err());
+        assert!(normalize_domain("exa mple.com").is_err());
+    }
+
+    #[test]
+    fn test_normalize_domain_percent_encoding() {
+        assert_eq!(normalize_domain("example%2Ecom").unwrap(), "example.com");
+        assert_eq!(
+            normalize_domain("xn--mller-kva%2ede").unwrap(),
+            "xn--mller-kva.de"
+        );
+        assert!(normalize_domain("example%2Fcom").is_err());
+        assert!(normalize_domain("example%252Ecom").is_err());
+        assert!(normalize_domain("example%FF.com").is_err());
+    }
+
+    #[test]
+    fn test_registrable_domain() {
+        assert_eq!(
+            registrable_domain("www.example.co.uk").as_deref(),
+            Some("example.co.uk")
+        );
+        assert_eq!(
+            registrable_domain("WWW.Example.COM.").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            registrable_domain("mail.пример.рф").as_deref(),
+            Some("xn--e1afmkfd.xn--p1ai")
+        );
+        // Private suffixes don't count: github.io is registered under .io
+        assert_eq!(
+            registrable_domain("user.github.io").as_deref(),
+            Some("github.io")
+        );
+        assert_eq!(registrable_domain("co.uk"), None);
+        assert_eq!(registrable_domain("com"), None);
+    }
+
+    #[test]
+    fn test_normalized_request_url() {
+        let base = Url::parse("https://rdap.denic.de/").unwrap();
+        let req = RdapRequest::new(QueryType::Domain, "XN--MLLER-KVA%2Ede")
+            .normalized()
+            .unwrap();
+        let url = req.build_url(&base).unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://rdap.denic.de/domain/xn--mller-kva.de"
+        );
+
+        let req = RdapRequest::new(QueryType::Nameserver, "NS1.EXAMPLE.COM.")
+            .normalized()
+            .unwrap();
+        assert_eq!(
+            req.build_url(&base).unwrap().as_str(),
+            "https://rdap.denic.de/nameserver/ns1.example.com"
+        );
+
+        // Non-domain queries are left untouched
+        let req = RdapRequest::new(QueryType::Entity, "ABC%2E1")
+            .normalized()
+            .unwrap();
+        assert_eq!(req.query, "ABC%2E1");
+    }
 }
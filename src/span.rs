@@ -0,0 +1,99 @@
+//! Per-query `tracing` spans for telling concurrent queries apart
+//!
+//! Every client query runs inside an `rdap_query` span carrying a
+//! correlation `id` and the `query` string. Bootstrap lookup, each server
+//! attempt and parsing run in an `rdap_stage` child span with a `stage`
+//! field. A `tracing` subscriber can then attribute log lines to their
+//! query, as the CLI does to prefix each line with e.g.
+//! `[#3 example.com > bootstrap]`.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::instrument::{Instrument, Instrumented};
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Name of the span wrapping a whole query
+pub(crate) const QUERY_SPAN: &str = "rdap_query";
+
+/// Name of the spans wrapping one stage of a query
+pub(crate) const STAGE_SPAN: &str = "rdap_stage";
+
+/// Run `future` in a new query span for `query`
+///
+/// Inside a query already (e.g. [`crate::RdapClient::query`] calling
+/// [`crate::RdapClient::query_with_referral`]), the current span is kept so
+/// nested client calls share one id.
+pub(crate) fn in_query<F: Future>(query: &str, future: F) -> Instrumented<F> {
+    let current = tracing::Span::current();
+    if current
+        .metadata()
+        .is_some_and(|m| m.name() == QUERY_SPAN || m.name() == STAGE_SPAN)
+    {
+        return future.instrument(tracing::Span::none());
+    }
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    future.instrument(tracing::debug_span!("rdap_query", id, query))
+}
+
+/// Run `future` in a stage span of the current query
+pub(crate) fn in_stage<F: Future>(stage: &str, future: F) -> Instrumented<F> {
+    future.instrument(tracing::debug_span!("rdap_stage", stage))
+}
+
+/// Run `f` in a stage span of the current query
+pub(crate) fn in_stage_sync<T>(stage: &str, f: impl FnOnce() -> T) -> T {
+    tracing::debug_span!("rdap_stage", stage).in_scope(f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing::Subscriber;
+    use tracing::span::{Attributes, Id, Record};
+
+    /// Records the name of every span created and the span current at each
+    /// `enter`, innermost last
+    #[derive(Default, Clone)]
+    struct Recorder {
+        spans: Arc<Mutex<Vec<&'static str>>>,
+        entered: Arc<Mutex<Vec<u64>>>,
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(attrs.metadata().name());
+            Id::from_u64(spans.len() as u64)
+        }
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, _: &tracing::Event<'_>) {}
+        fn enter(&self, span: &Id) {
+            self.entered.lock().unwrap().push(span.into_u64());
+        }
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[tokio::test]
+    async fn test_stages_run_in_query_span() {
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        in_query("example.com", async {
+            in_stage("bootstrap", async {}).await;
+            in_stage_sync("parse", || {});
+        })
+        .await;
+
+        assert_eq!(
+            *recorder.spans.lock().unwrap(),
+            [QUERY_SPAN, STAGE_SPAN, STAGE_SPAN]
+        );
+        assert!(recorder.entered.lock().unwrap().contains(&1));
+    }
+}
@@ -0,0 +1,174 @@
+//! Color themes for the terminal output of [`crate::display`]
+//!
+//! A theme is chosen per call with [`crate::display::DisplayOptions::theme`].
+//! Colors are applied only when `colored` is enabled, so `--no-color` and
+//! `NO_COLOR` still win over any theme.
+
+use crate::config::ThemeConfig;
+use crate::error::{RdapError, Result};
+use colored::{Color, ColoredString, Colorize};
+
+/// Colors for each kind of output text; `None` keeps the terminal's default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Field labels, e.g. `Status`
+    pub label: Option<Color>,
+    /// Values picked out from the labels: names, addresses, handles
+    pub value: Option<Color>,
+    /// Healthy status values such as `active`, and `yes` answers
+    pub good: Option<Color>,
+    /// Locked or failing status values, `no` answers and warnings
+    pub bad: Option<Color>,
+    /// Other status values, roles and contact emails
+    pub neutral: Option<Color>,
+    pub link: Option<Color>,
+}
+
+impl Theme {
+    /// For dark terminal backgrounds (the default)
+    pub const DARK: Self = Self {
+        label: Some(Color::White),
+        value: Some(Color::Cyan),
+        good: Some(Color::Green),
+        bad: Some(Color::Red),
+        neutral: Some(Color::Yellow),
+        link: Some(Color::Cyan),
+    };
+
+    /// For light terminal backgrounds: no white or yellow text
+    pub const LIGHT: Self = Self {
+        label: None,
+        value: Some(Color::Blue),
+        good: Some(Color::Green),
+        bad: Some(Color::Red),
+        neutral: Some(Color::Magenta),
+        link: Some(Color::Blue),
+    };
+
+    /// Names accepted by [`Self::named`]
+    pub const NAMES: [&str; 2] = ["dark", "light"];
+
+    /// A built-in theme by name, case-insensitive
+    pub fn named(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "dark" => Some(Self::DARK),
+            "light" => Some(Self::LIGHT),
+            _ => None,
+        }
+    }
+
+    /// The configured theme: its `base` (dark by default) with the configured colors on top
+    pub fn from_config(config: &ThemeConfig) -> Result<Self> {
+        let mut theme = match &config.base {
+            Some(name) => Self::named(name).ok_or_else(|| {
+                RdapError::Other(format!(
+                    "Unknown theme `{name}` (expected one of: {})",
+                    Self::NAMES.join(", ")
+                ))
+            })?,
+            None => Self::DARK,
+        };
+        let slots = [
+            (&config.label, &mut theme.label, "label"),
+            (&config.value, &mut theme.value, "value"),
+            (&config.good, &mut theme.good, "good"),
+            (&config.bad, &mut theme.bad, "bad"),
+            (&config.neutral, &mut theme.neutral, "neutral"),
+            (&config.link, &mut theme.link, "link"),
+        ];
+        for (name, slot, key) in slots {
+            if let Some(name) = name {
+                *slot = parse_color(name).map_err(|()| {
+                    RdapError::Other(format!("Invalid theme.{key} color `{name}`"))
+                })?;
+            }
+        }
+        Ok(theme)
+    }
+
+    pub fn label(&self, text: &str) -> ColoredString {
+        paint(text, self.label)
+    }
+
+    pub fn value(&self, text: &str) -> ColoredString {
+        paint(text, self.value)
+    }
+
+    pub fn good(&self, text: &str) -> ColoredString {
+        paint(text, self.good)
+    }
+
+    pub fn bad(&self, text: &str) -> ColoredString {
+        paint(text, self.bad)
+    }
+
+    pub fn neutral(&self, text: &str) -> ColoredString {
+        paint(text, self.neutral)
+    }
+
+    pub fn link(&self, text: &str) -> ColoredString {
+        paint(text, self.link)
+    }
+
+    /// A status value colored by what it means for the object
+    pub fn status(&self, status: &str) -> ColoredString {
+        match status {
+            s if s.contains("active") => self.good(s),
+            s if s.contains("delete") || s.contains("prohibit") => self.bad(s),
+            s => self.neutral(s),
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+fn paint(text: &str, color: Option<Color>) -> ColoredString {
+    color.map_or_else(|| text.normal(), |color| text.color(color))
+}
+
+/// A color name (`cyan`, `bright blue`), `#rrggbb`, or `none` for the terminal default
+fn parse_color(name: &str) -> std::result::Result<Option<Color>, ()> {
+    if name.eq_ignore_ascii_case("none") || name.eq_ignore_ascii_case("default") {
+        return Ok(None);
+    }
+    name.parse().map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_from_config() {
+        let config: ThemeConfig = serde_json::from_str(
+            r##"{"base": "light", "label": "bright black", "link": "#1e90ff", "good": "none"}"##,
+        )
+        .unwrap();
+        let theme = Theme::from_config(&config).unwrap();
+        assert_eq!(theme.label, Some(Color::BrightBlack));
+        assert_eq!(
+            theme.link,
+            Some(Color::TrueColor {
+                r: 0x1e,
+                g: 0x90,
+                b: 0xff
+            })
+        );
+        assert_eq!(theme.good, None);
+        assert_eq!(theme.value, Theme::LIGHT.value);
+
+        assert_eq!(
+            Theme::from_config(&ThemeConfig::default()).unwrap(),
+            Theme::DARK
+        );
+
+        let config: ThemeConfig = serde_json::from_str(r#"{"bad": "crimson"}"#).unwrap();
+        let err = Theme::from_config(&config).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid theme.bad color `crimson`");
+        assert!(Theme::named("Solarized").is_none());
+    }
+}
@@ -0,0 +1,344 @@
+//! WHOIS-style flat `Key: Value` output for RDAP objects
+
+use crate::display::{event_action_label, sanitize_field};
+use crate::models::{
+    Autnum, DEFAULT_MAX_ENTITY_DEPTH, Domain, Entity, Event, IpNetwork, Nameserver, RdapObject,
+};
+
+/// Render an object as classic WHOIS lines, one `Key: Value` per line
+///
+/// Contacts from nested entities are flattened with their role as a prefix
+/// (e.g. `Registrant Email`), and search results are separated by blank
+/// lines. The output never contains color codes.
+pub fn to_whois_lines(obj: &RdapObject) -> Vec<String> {
+    to_whois_lines_with_depth(obj, DEFAULT_MAX_ENTITY_DEPTH)
+}
+
+/// [`to_whois_lines`], following nested entities down to `max_depth` levels
+pub fn to_whois_lines_with_depth(obj: &RdapObject, max_depth: usize) -> Vec<String> {
+    let mut out = WhoisLines {
+        lines: Vec::new(),
+        max_depth,
+    };
+    match obj {
+        RdapObject::Domain(domain) => out.domain(domain),
+        RdapObject::Entity(entity) => out.entity(entity, "", 1),
+        RdapObject::Nameserver(ns) => out.nameserver(ns),
+        RdapObject::Autnum(asn) => out.autnum(asn),
+        RdapObject::IpNetwork(ip) => out.ip_network(ip),
+        RdapObject::Error(err) => {
+            out.field("Error Code", err.error_code.map(|c| c.to_string()));
+            out.field("Error", err.title.as_deref());
+            for desc in &err.description {
+                out.field("Description", Some(desc));
+            }
+        }
+        RdapObject::DomainSearch(results) => {
+            for (i, domain) in results.domains.iter().enumerate() {
+                out.separator(i);
+                out.domain(domain);
+            }
+        }
+        RdapObject::EntitySearch(results) => {
+            for (i, entity) in results.entities.iter().enumerate() {
+                out.separator(i);
+                out.entity(entity, "", 1);
+            }
+        }
+        RdapObject::NameserverSearch(results) => {
+            for (i, ns) in results.nameservers.iter().enumerate() {
+                out.separator(i);
+                out.nameserver(ns);
+            }
+        }
+        RdapObject::Help(help) => {
+            for notice in &help.notices {
+                out.field("Notice", notice.title.as_deref());
+                for desc in &notice.description {
+                    out.field("Notice", Some(desc));
+                }
+            }
+        }
+    }
+    out.lines
+}
+
+/// Accumulates output lines
+struct WhoisLines {
+    lines: Vec<String>,
+    /// Nested entities deeper than this are skipped
+    max_depth: usize,
+}
+
+impl WhoisLines {
+    /// Add `Key: Value`, skipping missing or empty values
+    fn field<V: AsRef<str>>(&mut self, key: &str, value: Option<V>) {
+        if let Some(value) = value {
+            let value = sanitize_field(value.as_ref(), 0);
+            if !value.is_empty() {
+                self.lines.push(format!("{key}: {value}"));
+            }
+        }
+    }
+
+    /// Blank line between search results
+    fn separator(&mut self, index: usize) {
+        if index > 0 {
+            self.lines.push(String::new());
+        }
+    }
+
+    fn domain(&mut self, domain: &Domain) {
+        self.field("Domain Name", domain.ascii_name());
+        self.field("Registry Domain ID", domain.handle.as_deref());
+        self.field("WHOIS Server", domain.port43.as_deref());
+        for status in &domain.status {
+            self.field("Domain Status", Some(status));
+        }
+        for ns in &domain.nameservers {
+            self.field("Name Server", ns.ldh_name.as_deref());
+        }
+        if let Some(signed) = domain.secure_dns.as_ref().and_then(|d| d.delegation_signed) {
+            self.field(
+                "DNSSEC",
+                Some(if signed {
+                    "signedDelegation"
+                } else {
+                    "unsigned"
+                }),
+            );
+        }
+        self.events(&domain.events);
+        self.entities(&domain.entities);
+    }
+
+    fn nameserver(&mut self, ns: &Nameserver) {
+        self.field("Name Server", ns.ldh_name.as_deref());
+        self.field("Handle", ns.handle.as_deref());
+        if let Some(ips) = &ns.ip_addresses {
+            for addr in ips.v4.iter().chain(&ips.v6) {
+                self.field("IP Address", Some(addr));
+            }
+        }
+        for status in &ns.status {
+            self.field("Status", Some(status));
+        }
+        self.events(&ns.events);
+        self.entities(&ns.entities);
+    }
+
+    fn autnum(&mut self, asn: &Autnum) {
+        let range = match (asn.start_autnum, asn.end_autnum) {
+            (Some(start), Some(end)) if start != end => Some(format!("AS{start} - AS{end}")),
+            (Some(start), _) => Some(format!("AS{start}")),
+            _ => None,
+        };
+        self.field("ASNumber", range);
+        self.field("ASName", asn.name.as_deref());
+        self.field("ASHandle", asn.handle.as_deref());
+        self.field("ASType", asn.as_type.as_deref());
+        self.field("Country", asn.country.as_deref());
+        for status in &asn.status {
+            self.field("Status", Some(status));
+        }
+        self.events(&asn.events);
+        self.entities(&asn.entities);
+    }
+
+    fn ip_network(&mut self, ip: &IpNetwork) {
+        if let (Some(start), Some(end)) = (&ip.start_address, &ip.end_address) {
+            self.field("NetRange", Some(format!("{start} - {end}")));
+        }
+        for cidr in ip.cidrs() {
+            self.field("CIDR", Some(cidr));
+        }
+        self.field("NetName", ip.name.as_deref());
+        self.field("NetHandle", ip.handle.as_deref());
+        self.field("Parent", ip.parent_handle.as_deref());
+        self.field("NetType", ip.network_type.as_deref());
+        self.field("Country", ip.country.as_deref());
+        for status in &ip.status {
+            self.field("Status", Some(status));
+        }
+        self.events(&ip.events);
+        self.entities(&ip.entities);
+    }
+
+    fn events(&mut self, events: &[Event]) {
+        for event in events {
+            self.field(event_action_label(&event.action), Some(&event.date));
+        }
+    }
+
+    fn entities(&mut self, entities: &[Entity]) {
+        for entity in entities {
+            self.entity(entity, "", 1);
+        }
+    }
+
+    /// Flatten an entity once per role, e.g. `Registrant Email`
+    ///
+    /// Nested entities extend the prefix of the first role (`Registrar Abuse
+    /// Email`), down to `max_depth` levels. They are listed once, not once per
+    /// role, so the output stays linear in the number of entities.
+    fn entity(&mut self, entity: &Entity, prefix: &str, depth: usize) {
+        if depth > self.max_depth {
+            return;
+        }
+        let mut prefixes: Vec<String> = entity
+            .roles
+            .iter()
+            .map(|role| join_prefix(prefix, role_label(role)))
+            .collect();
+        if prefixes.is_empty() {
+            prefixes.push(join_prefix(prefix, "Contact"));
+        }
+
+        for prefix in &prefixes {
+            let key = |field: &str| format!("{prefix} {field}");
+            self.field(&key("Handle"), entity.handle.as_deref());
+            if let Some(vcard) = &entity.vcard {
+                self.field(&key("Name"), vcard.name());
+                self.field(&key("Organization"), vcard.org());
+                if let Some(addr) = vcard.address() {
+                    self.field(&key("Street"), Some(&addr.street));
+                    self.field(&key("City"), Some(&addr.locality));
+                    self.field(&key("State/Province"), Some(&addr.region));
+                    self.field(&key("Postal Code"), Some(&addr.postal_code));
+                    self.field(&key("Country"), Some(&addr.country));
+                }
+                self.field(&key("Phone"), vcard.tel());
+                self.field(&key("Email"), vcard.email());
+            }
+        }
+        for nested in &entity.entities {
+            self.entity(nested, &prefixes[0], depth + 1);
+        }
+    }
+}
+
+/// WHOIS-style label for an RDAP role
+fn role_label(role: &str) -> &str {
+    match role {
+        "registrant" => "Registrant",
+        "administrative" => "Admin",
+        "technical" => "Tech",
+        "billing" => "Billing",
+        "abuse" => "Abuse",
+        "registrar" => "Registrar",
+        "reseller" => "Reseller",
+        "sponsor" => "Sponsor",
+        "proxy" => "Proxy",
+        "notifications" => "Notifications",
+        "noc" => "NOC",
+        r => r,
+    }
+}
+
+/// Append a role label to a parent prefix
+fn join_prefix(prefix: &str, label: &str) -> String {
+    if prefix.is_empty() {
+        label.to_string()
+    } else {
+        format!("{prefix} {label}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_whois_lines() {
+        let obj = crate::parse_rdap(
+            r#"{
+                "objectClassName": "domain",
+                "ldhName": "EXAMPLE.COM",
+                "status": ["client transfer prohibited"],
+                "nameservers": [{"objectClassName": "nameserver", "ldhName": "A.IANA-SERVERS.NET"}],
+                "secureDNS": {"delegationSigned": true},
+                "events": [{"eventAction": "registration", "eventDate": "1995-08-14T04:00:00Z"}],
+                "entities": [
+                    {
+                        "objectClassName": "entity",
+                        "roles": ["registrar"],
+                        "vcardArray": ["vcard", [["fn", {}, "text", "RESERVED-Internet Assigned Numbers Authority"]]],
+                        "entities": [{
+                            "objectClassName": "entity",
+                            "roles": ["abuse"],
+                            "vcardArray": ["vcard", [["email", {}, "text", "abuse@example.net"]]]
+                        }]
+                    },
+                    {
+                        "objectClassName": "entity",
+                        "roles": ["registrant", "technical"],
+                        "vcardArray": ["vcard", [["email", {}, "text", "hostmaster@example.com\nInjected: line"]]]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            to_whois_lines(&obj),
+            [
+                "Domain Name: EXAMPLE.COM",
+                "Domain Status: client transfer prohibited",
+                "Name Server: A.IANA-SERVERS.NET",
+                "DNSSEC: signedDelegation",
+                "Registration: 1995-08-14T04:00:00Z",
+                "Registrar Name: RESERVED-Internet Assigned Numbers Authority",
+                "Registrar Abuse Email: abuse@example.net",
+                "Registrant Email: hostmaster@example.com Injected: line",
+                "Tech Email: hostmaster@example.com Injected: line",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_results_separated() {
+        let obj = crate::parse_rdap(
+            r#"{"domainSearchResults": [
+                {"objectClassName": "domain", "ldhName": "a.example"},
+                {"objectClassName": "domain", "ldhName": "b.example"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            to_whois_lines(&obj),
+            ["Domain Name: a.example", "", "Domain Name: b.example"]
+        );
+    }
+
+    #[test]
+    fn test_nested_entities_listed_once() {
+        // Each level has two roles; nesting must not repeat the subtree
+        let mut entity = serde_json::json!({
+            "objectClassName": "entity",
+            "handle": "LEAF",
+            "roles": ["technical", "administrative"]
+        });
+        for level in 0..8 {
+            entity = serde_json::json!({
+                "objectClassName": "entity",
+                "handle": format!("L{level}"),
+                "roles": ["registrant", "billing"],
+                "entities": [entity]
+            });
+        }
+        let obj = crate::parse_rdap(
+            &serde_json::json!({
+                "objectClassName": "domain",
+                "ldhName": "example.com",
+                "entities": [entity]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let lines = to_whois_lines_with_depth(&obj, usize::MAX);
+        // Domain name plus one handle line per role of each of the 9 entities
+        assert_eq!(lines.len(), 1 + 9 * 2);
+        assert!(lines.contains(&"Registrant Billing Handle: L6".to_string()));
+    }
+}